@@ -150,6 +150,10 @@ Do not generate data beyond these periods.";
                 StreamEvent::Chunk(chunk) => {
                     print!("{chunk}");
                 }
+                StreamEvent::Partial(_) => {}
+                StreamEvent::Refining => {
+                    println!("\nPolishing result...");
+                }
                 StreamEvent::Complete(outcome) => {
                     final_outcome = Some(outcome);
                 }
@@ -404,6 +408,12 @@ fn truncate(s: &str, max_len: usize) -> String {
     if s.len() <= max_len {
         s.to_string()
     } else {
-        format!("{}...", &s[..max_len])
+        let cut = s
+            .char_indices()
+            .map(|(i, _)| i)
+            .take_while(|&i| i <= max_len)
+            .last()
+            .unwrap_or(0);
+        format!("{}...", &s[..cut])
     }
 }
@@ -119,6 +119,9 @@ async fn main() -> Result<()> {
             WorkflowEvent::StepEnd { step_name, duration_ms } => {
                 println!("[{}ms] END: {} (took {}ms)", entry.timestamp % 100000, step_name, duration_ms);
             }
+            WorkflowEvent::Retry { step_name, retries, delay_ms, message } => {
+                println!("[{}ms] RETRY #{}: {} in {}ms - {}", entry.timestamp % 100000, retries, step_name, delay_ms, message);
+            }
             WorkflowEvent::Error { step_name, message } => {
                 println!("[{}ms] ERROR: {} - {}", entry.timestamp % 100000, step_name, message);
             }
@@ -118,12 +118,7 @@ async fn main() -> Result<()> {
 
     // Display workflow metrics
     println!("\n=== Workflow Metrics ===");
-    println!("Steps completed: {}", metrics.steps_completed);
-    println!("Total tokens: {}", metrics.total_token_count);
-    println!("  - Prompt tokens: {}", metrics.prompt_token_count);
-    println!("  - Response tokens: {}", metrics.candidates_token_count);
-    println!("Network attempts: {}", metrics.network_attempts);
-    println!("Parse attempts: {}", metrics.parse_attempts);
+    print!("{}", metrics.report());
 
     Ok(())
 }
@@ -146,18 +146,7 @@ async fn main() -> Result<()> {
     println!("\nRecommended Action: {}", brief.recommended_action);
 
     println!("\n=== Workflow Metrics ===");
-    println!("Steps completed: {}", metrics.steps_completed);
-    println!("Total tokens: {}", metrics.total_token_count);
-    println!("  - Prompt tokens: {}", metrics.prompt_token_count);
-    println!("  - Response tokens: {}", metrics.candidates_token_count);
-    println!("Network attempts: {}", metrics.network_attempts);
-    println!("Parse correction attempts: {}", metrics.parse_attempts);
-    if !metrics.failures.is_empty() {
-        println!("Failures recorded: {}", metrics.failures.len());
-        for failure in &metrics.failures {
-            println!("  - {}", failure);
-        }
-    }
+    print!("{}", metrics.report());
 
     // --- Example 2: Stateful workflow to avoid tuple hell ---
     println!("\n\n=== Example 2: Blackboard State (no tuples) ===\n");
@@ -206,15 +195,15 @@ async fn main() -> Result<()> {
         |state, brief| {
             state.brief = Some(brief);
         },
-    );
-
-    let (final_state, metrics2) = state_workflow.run().await?;
-    let enriched = final_state
-        .enriched
-        .expect("stateful workflow populates enriched analysis");
-    let brief2 = final_state
-        .brief
-        .expect("stateful workflow populates executive brief");
+    )
+    .with_finalizer(|state: AnalysisState| match (state.enriched, state.brief) {
+        (Some(enriched), Some(brief)) => Ok((enriched, brief)),
+        _ => Err(StructuredError::Context(
+            "stateful workflow did not populate analysis and brief".into(),
+        )),
+    });
+
+    let ((enriched, brief2), metrics2) = state_workflow.run_finalized().await?;
 
     println!("=== Enriched Analysis ===");
     println!("Summary: {}", enriched.analysis.summary);
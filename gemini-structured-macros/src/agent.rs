@@ -205,6 +205,10 @@ fn generate_generic_step_impl(params: &StepGenParams) -> TokenStream {
                 #temp_setter
                 #retry_setter
 
+                if let Some(model) = ctx.model_override() {
+                    request = request.with_model(model.clone());
+                }
+
                 let outcome = request.execute().await?;
 
                 // Automatic metric recording
@@ -274,6 +278,10 @@ fn generate_typed_step_impl(
                 #temp_setter
                 #retry_setter
 
+                if let Some(model) = ctx.model_override() {
+                    request = request.with_model(model.clone());
+                }
+
                 let outcome = request.execute().await?;
 
                 // Automatic metric recording
@@ -26,6 +26,9 @@ use syn::{parse_macro_input, DeriveInput};
 ///
 /// - `description` (required): A description of what the tool does
 /// - `name` (optional): Override the tool name (defaults to function name)
+/// - `context` (optional): When `true`, the function takes a second
+///   `Option<ToolContext>` argument carrying the request's
+///   `StructuredRequest::with_tool_context` value
 ///
 /// # Example
 ///
@@ -8,6 +8,9 @@ pub struct ToolArgs {
     #[darling(default)]
     pub name: Option<String>,
     pub description: String,
+    /// Pass the request's `ToolContext` as a second argument.
+    #[darling(default)]
+    pub context: bool,
 }
 
 pub fn generate_tool(args: ToolArgs, input: ItemFn) -> TokenStream {
@@ -24,8 +27,8 @@ pub fn generate_tool(args: ToolArgs, input: ItemFn) -> TokenStream {
         };
     }
 
-    // Extract input type (expecting exactly one argument)
-    let input_type = match extract_input_type(&input) {
+    // Extract input type (one argument, plus the context when requested)
+    let input_type = match extract_input_type(&input, args.context) {
         Ok(ty) => ty,
         Err(err) => return err,
     };
@@ -39,6 +42,24 @@ pub fn generate_tool(args: ToolArgs, input: ItemFn) -> TokenStream {
     // Generate module name from function name
     let mod_name = quote::format_ident!("{}_tool", fn_name);
 
+    let register_call = if args.context {
+        quote! {
+            registry.register_tool_with_ctx::<#input_type, #return_type, _, _>(
+                #tool_name,
+                #description,
+                super::#fn_name
+            )
+        }
+    } else {
+        quote! {
+            registry.register_with_handler::<#input_type, #return_type, _, _>(
+                #tool_name,
+                #description,
+                super::#fn_name
+            )
+        }
+    };
+
     quote! {
         #input
 
@@ -61,11 +82,7 @@ pub fn generate_tool(args: ToolArgs, input: ItemFn) -> TokenStream {
 
             /// Register this tool with a ToolRegistry
             pub fn register(registry: gemini_structured_output::tools::ToolRegistry) -> gemini_structured_output::tools::ToolRegistry {
-                registry.register_with_handler::<#input_type, #return_type, _, _>(
-                    #tool_name,
-                    #description,
-                    super::#fn_name
-                )
+                #register_call
             }
 
             /// Create a tool registration closure for use with `register_tool`
@@ -76,10 +93,15 @@ pub fn generate_tool(args: ToolArgs, input: ItemFn) -> TokenStream {
     }
 }
 
-fn extract_input_type(func: &ItemFn) -> Result<&Type, TokenStream> {
+fn extract_input_type(func: &ItemFn, context: bool) -> Result<&Type, TokenStream> {
     let inputs = &func.sig.inputs;
 
-    if inputs.len() != 1 {
+    if context && inputs.len() != 2 {
+        return Err(quote! {
+            compile_error!("Tool function with `context = true` must take the tool arguments and an `Option<ToolContext>`");
+        });
+    }
+    if !context && inputs.len() != 1 {
         return Err(quote! {
             compile_error!("Tool function must take exactly one argument");
         });
@@ -1,14 +1,26 @@
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    any::Any,
+    collections::HashMap,
+    io::ErrorKind,
+    path::{Path, PathBuf},
+    sync::{
+        atomic::{AtomicU64, Ordering},
+        Arc, Mutex as StdMutex,
+    },
+    time::{Duration, Instant, SystemTime},
+};
 
+use futures::future::{BoxFuture, FutureExt, Shared};
 use gemini_rust::{
     cache::{CachedContentHandle, Error as CacheError},
     ClientError, Gemini, Tool,
 };
+use serde::{Deserialize, Serialize};
 use sha2::{Digest, Sha256};
-use tokio::sync::Mutex;
+use tokio::sync::{oneshot, Mutex};
 use tracing::{debug, warn};
 
-use crate::{error::Result, schema::GeminiStructured};
+use crate::{error::Result, models::GenerationOutcome, schema::GeminiStructured};
 
 #[derive(Clone, Copy)]
 pub enum CachePolicy {
@@ -21,6 +33,8 @@ pub enum CachePolicy {
 pub struct CacheSettings {
     pub key: Option<String>,
     pub ttl_override: Option<Duration>,
+    /// Invalidate any existing entry and create a fresh one for this request.
+    pub force_refresh: bool,
 }
 
 impl CacheSettings {
@@ -42,16 +56,93 @@ impl CacheSettings {
         Self {
             key: Some(key.into()),
             ttl_override: Some(ttl),
+            ..Default::default()
+        }
+    }
+
+    /// Bypass and overwrite the cached entry, e.g. after the system prompt changed.
+    pub fn force_refresh(mut self) -> Self {
+        self.force_refresh = true;
+        self
+    }
+}
+
+/// Entries this close to expiry are recreated rather than reused.
+const EXPIRY_MARGIN: Duration = Duration::from_secs(60);
+
+#[derive(Clone)]
+struct CacheEntry {
+    handle: CachedContentHandle,
+    expires_at: SystemTime,
+    /// False for entries reloaded from disk until the handle is confirmed to exist.
+    verified: bool,
+}
+
+/// On-disk form of a [`CacheEntry`] for [`SchemaCache::with_persistence`].
+#[derive(Serialize, Deserialize)]
+struct PersistedEntry {
+    name: String,
+    expires_at: SystemTime,
+}
+
+/// Snapshot of [`SchemaCache`] effectiveness counters.
+///
+/// Lookups skipped because the content is below the minimum cacheable size are not
+/// counted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    /// Lookups served by an existing cached content entry.
+    pub hits: u64,
+    /// Lookups that found no usable entry.
+    pub misses: u64,
+    /// Cached content entries created on the server.
+    pub creations: u64,
+    /// Entries dropped because they neared expiry, were deleted server-side or were
+    /// invalidated.
+    pub evictions: u64,
+}
+
+impl CacheStats {
+    /// Fraction of lookups that were hits, or 0.0 before any lookup.
+    pub fn hit_rate(&self) -> f64 {
+        let lookups = self.hits + self.misses;
+        if lookups == 0 {
+            return 0.0;
         }
+        self.hits as f64 / lookups as f64
     }
 }
 
+impl std::fmt::Display for CacheStats {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "hit rate {:.1}% ({} hits, {} misses, {} created, {} evicted)",
+            self.hit_rate() * 100.0,
+            self.hits,
+            self.misses,
+            self.creations,
+            self.evictions
+        )
+    }
+}
+
+#[derive(Default)]
+struct CacheCounters {
+    hits: AtomicU64,
+    misses: AtomicU64,
+    creations: AtomicU64,
+    evictions: AtomicU64,
+}
+
 /// Lightweight cache helper to avoid re-uploading heavy schemas or prompts.
 #[derive(Clone)]
 pub struct SchemaCache {
     client: Arc<Gemini>,
-    inner: Arc<Mutex<HashMap<String, CachedContentHandle>>>,
+    inner: Arc<Mutex<HashMap<String, CacheEntry>>>,
     policy: CachePolicy,
+    counters: Arc<CacheCounters>,
+    persist_path: Option<PathBuf>,
 }
 
 impl SchemaCache {
@@ -60,13 +151,38 @@ impl SchemaCache {
             client,
             inner: Arc::new(Mutex::new(HashMap::new())),
             policy,
+            counters: Arc::default(),
+            persist_path: None,
         }
     }
 
+    /// Keep the cache-key to cached-content map as JSON at `path`.
+    ///
+    /// Unexpired entries in an existing file are loaded now, so restarts reuse
+    /// server-side cached content instead of re-uploading the system prompt. A reloaded
+    /// handle is checked once before use; if it was deleted server-side the entry is
+    /// recreated. A missing or unreadable file starts an empty cache.
+    pub fn with_persistence(mut self, path: impl Into<PathBuf>) -> Self {
+        let path = path.into();
+        self.inner = Arc::new(Mutex::new(load_persisted(&self.client, &path)));
+        self.persist_path = Some(path);
+        self
+    }
+
     pub fn policy(&self) -> CachePolicy {
         self.policy
     }
 
+    /// Current hit, miss, creation and eviction counts.
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.counters.hits.load(Ordering::Relaxed),
+            misses: self.counters.misses.load(Ordering::Relaxed),
+            creations: self.counters.creations.load(Ordering::Relaxed),
+            evictions: self.counters.evictions.load(Ordering::Relaxed),
+        }
+    }
+
     /// Builds a deterministic cache key from system text, schema, and tool set.
     pub fn cache_key<T: GeminiStructured>(system: &str, tools: &[Tool]) -> String {
         let mut hasher = Sha256::new();
@@ -91,6 +207,23 @@ impl SchemaCache {
         tools: &[Tool],
         ttl_override: Option<Duration>,
     ) -> Result<Option<CachedContentHandle>> {
+        Ok(self
+            .get_or_create_with_expiry(name, system_instruction, tools, ttl_override)
+            .await?
+            .map(|(handle, _)| handle))
+    }
+
+    /// Like [`get_or_create`](Self::get_or_create), also returning when the entry expires.
+    ///
+    /// Entries within a minute of expiry are recreated, so calling this again shortly
+    /// before the returned time refreshes the cache.
+    pub async fn get_or_create_with_expiry(
+        &self,
+        name: &str,
+        system_instruction: &str,
+        tools: &[Tool],
+        ttl_override: Option<Duration>,
+    ) -> Result<Option<(CachedContentHandle, SystemTime)>> {
         match self.policy {
             CachePolicy::Disabled => Ok(None),
             CachePolicy::Enabled { ttl } => {
@@ -107,10 +240,21 @@ impl SchemaCache {
                 }
 
                 let ttl = ttl_override.unwrap_or(ttl);
-                // Fast path: local map
+                // Fast path: local map, skipping entries about to expire
                 if let Some(existing) = self.inner.lock().await.get(name).cloned() {
-                    return Ok(Some(existing));
+                    let fresh = existing
+                        .expires_at
+                        .duration_since(SystemTime::now())
+                        .is_ok_and(|remaining| remaining > EXPIRY_MARGIN);
+                    if !fresh {
+                        debug!(cache_key = name, "Cached content near expiry; recreating");
+                    } else if self.confirm_exists(name, &existing).await {
+                        self.counters.hits.fetch_add(1, Ordering::Relaxed);
+                        return Ok(Some((existing.handle, existing.expires_at)));
+                    }
+                    self.counters.evictions.fetch_add(1, Ordering::Relaxed);
                 }
+                self.counters.misses.fetch_add(1, Ordering::Relaxed);
 
                 let mut builder = self
                     .client
@@ -123,13 +267,22 @@ impl SchemaCache {
                     builder = builder.with_tools(tools.to_vec());
                 }
 
+                let created_at = SystemTime::now();
                 match builder.execute().await {
                     Ok(handle) => {
-                        self.inner
-                            .lock()
-                            .await
-                            .insert(name.to_string(), handle.clone());
-                        Ok(Some(handle))
+                        let expires_at = created_at + ttl;
+                        self.counters.creations.fetch_add(1, Ordering::Relaxed);
+                        let mut entries = self.inner.lock().await;
+                        entries.insert(
+                            name.to_string(),
+                            CacheEntry {
+                                handle: handle.clone(),
+                                expires_at,
+                                verified: true,
+                            },
+                        );
+                        self.persist(&entries).await;
+                        Ok(Some((handle, expires_at)))
                     }
                     Err(CacheError::Client { source }) => {
                         if let ClientError::BadResponse {
@@ -152,4 +305,296 @@ impl SchemaCache {
             }
         }
     }
+
+    /// Store a cached content handle created elsewhere under `name`.
+    ///
+    /// The handle is trusted as-is: it is served until `expires_at` nears without being
+    /// checked server-side, and it is written to the persistence file if one is set.
+    pub async fn insert(&self, name: &str, handle: CachedContentHandle, expires_at: SystemTime) {
+        let mut entries = self.inner.lock().await;
+        entries.insert(
+            name.to_string(),
+            CacheEntry {
+                handle,
+                expires_at,
+                verified: true,
+            },
+        );
+        self.persist(&entries).await;
+    }
+
+    /// Remove the entry for `name`, returning whether one existed.
+    ///
+    /// The cached content is also deleted server-side on a best-effort basis: failures
+    /// are logged and the remote copy then lingers until its TTL lapses.
+    pub async fn invalidate(&self, name: &str) -> bool {
+        let removed = {
+            let mut entries = self.inner.lock().await;
+            let removed = entries.remove(name);
+            if removed.is_some() {
+                self.persist(&entries).await;
+            }
+            removed
+        };
+        match removed {
+            Some(entry) => {
+                self.counters.evictions.fetch_add(1, Ordering::Relaxed);
+                delete_remote(name, entry.handle).await;
+                true
+            }
+            None => false,
+        }
+    }
+
+    /// Remove every entry, deleting the cached content server-side on a best-effort basis.
+    pub async fn clear(&self) {
+        let removed: Vec<(String, CacheEntry)> = {
+            let mut entries = self.inner.lock().await;
+            let removed = entries.drain().collect();
+            self.persist(&entries).await;
+            removed
+        };
+        self.counters
+            .evictions
+            .fetch_add(removed.len() as u64, Ordering::Relaxed);
+        for (name, entry) in removed {
+            delete_remote(&name, entry.handle).await;
+        }
+    }
+
+    /// Check once that a handle reloaded from disk still exists server-side.
+    async fn confirm_exists(&self, name: &str, entry: &CacheEntry) -> bool {
+        if entry.verified {
+            return true;
+        }
+        match entry.handle.get().await {
+            Ok(_) => {
+                if let Some(entry) = self.inner.lock().await.get_mut(name) {
+                    entry.verified = true;
+                }
+                true
+            }
+            Err(err) => {
+                warn!(cache_key = name, error = %err, "Persisted cached content is gone; recreating");
+                false
+            }
+        }
+    }
+
+    /// Write unexpired entries to the [`with_persistence`](Self::with_persistence) file.
+    ///
+    /// Failures are logged rather than returned: the in-memory cache stays usable.
+    async fn persist(&self, entries: &HashMap<String, CacheEntry>) {
+        let Some(path) = &self.persist_path else {
+            return;
+        };
+        let now = SystemTime::now();
+        let persisted: HashMap<&str, PersistedEntry> = entries
+            .iter()
+            .filter(|(_, entry)| entry.expires_at > now)
+            .map(|(key, entry)| {
+                let persisted = PersistedEntry {
+                    name: entry.handle.name().to_string(),
+                    expires_at: entry.expires_at,
+                };
+                (key.as_str(), persisted)
+            })
+            .collect();
+        let written = match serde_json::to_vec_pretty(&persisted) {
+            Ok(bytes) => tokio::fs::write(path, bytes).await,
+            Err(err) => Err(err.into()),
+        };
+        if let Err(err) = written {
+            warn!(path = %path.display(), error = %err, "Failed to persist cache entries");
+        }
+    }
+}
+
+async fn delete_remote(name: &str, handle: CachedContentHandle) {
+    if handle.delete().await.is_err() {
+        warn!(
+            cache_key = name,
+            "Failed to delete cached content server-side; it will expire with its TTL"
+        );
+    }
+}
+
+/// Reload unexpired entries written by [`SchemaCache::persist`].
+fn load_persisted(client: &Gemini, path: &Path) -> HashMap<String, CacheEntry> {
+    let bytes = match std::fs::read(path) {
+        Ok(bytes) => bytes,
+        Err(err) if err.kind() == ErrorKind::NotFound => return HashMap::new(),
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "Failed to read persisted cache");
+            return HashMap::new();
+        }
+    };
+    let persisted: HashMap<String, PersistedEntry> = match serde_json::from_slice(&bytes) {
+        Ok(persisted) => persisted,
+        Err(err) => {
+            warn!(path = %path.display(), error = %err, "Ignoring unreadable persisted cache");
+            return HashMap::new();
+        }
+    };
+
+    let now = SystemTime::now();
+    persisted
+        .into_iter()
+        .filter(|(_, entry)| entry.expires_at > now)
+        .map(|(key, entry)| {
+            let handle = client.get_cached_content(&entry.name);
+            let entry = CacheEntry {
+                handle,
+                expires_at: entry.expires_at,
+                verified: false,
+            };
+            (key, entry)
+        })
+        .collect()
+}
+
+/// Bounds for the client's idempotency cache.
+#[derive(Clone, Debug)]
+pub struct IdempotencyConfig {
+    /// How long a completed outcome is replayed for the same key (default: 5 minutes).
+    pub ttl: Duration,
+    /// Maximum number of remembered keys; the oldest entry is evicted first (default: 256).
+    pub max_entries: usize,
+}
+
+impl Default for IdempotencyConfig {
+    fn default() -> Self {
+        Self {
+            ttl: Duration::from_secs(300),
+            max_entries: 256,
+        }
+    }
+}
+
+struct IdempotencyEntry {
+    stored_at: Instant,
+    outcome: Arc<dyn Any + Send + Sync>,
+}
+
+/// Short-lived map from idempotency key to a previously returned outcome.
+#[derive(Clone)]
+pub(crate) struct IdempotencyCache {
+    config: IdempotencyConfig,
+    entries: Arc<StdMutex<HashMap<String, IdempotencyEntry>>>,
+}
+
+impl IdempotencyCache {
+    pub(crate) fn new(config: IdempotencyConfig) -> Self {
+        Self {
+            config: IdempotencyConfig {
+                max_entries: config.max_entries.max(1),
+                ..config
+            },
+            entries: Arc::new(StdMutex::new(HashMap::new())),
+        }
+    }
+
+    /// Outcome stored under `key` within the TTL, if it has the requested type.
+    pub(crate) fn get<T: Clone + 'static>(&self, key: &str) -> Option<GenerationOutcome<T>> {
+        let mut entries = self.entries.lock().unwrap();
+        self.purge_expired(&mut entries);
+        entries
+            .get(key)
+            .and_then(|entry| entry.outcome.downcast_ref::<GenerationOutcome<T>>())
+            .cloned()
+    }
+
+    pub(crate) fn insert<T: Clone + Send + Sync + 'static>(
+        &self,
+        key: String,
+        outcome: &GenerationOutcome<T>,
+    ) {
+        let mut entries = self.entries.lock().unwrap();
+        self.purge_expired(&mut entries);
+        if entries.len() >= self.config.max_entries && !entries.contains_key(&key) {
+            let oldest = entries
+                .iter()
+                .min_by_key(|(_, entry)| entry.stored_at)
+                .map(|(key, _)| key.clone());
+            if let Some(oldest) = oldest {
+                entries.remove(&oldest);
+            }
+        }
+        entries.insert(
+            key,
+            IdempotencyEntry {
+                stored_at: Instant::now(),
+                outcome: Arc::new(outcome.clone()),
+            },
+        );
+    }
+
+    fn purge_expired(&self, entries: &mut HashMap<String, IdempotencyEntry>) {
+        let ttl = self.config.ttl;
+        entries.retain(|_, entry| entry.stored_at.elapsed() < ttl);
+    }
+}
+
+/// Result a single-flight leader hands to its followers.
+pub(crate) type FlightResult =
+    std::result::Result<Arc<dyn Any + Send + Sync>, Arc<crate::error::StructuredError>>;
+
+/// Resolves to the leader's result, or `None` if the leader was dropped first.
+pub(crate) type FlightWaiter = Shared<BoxFuture<'static, Option<FlightResult>>>;
+
+/// Deduplicates identical requests that are in flight at the same time.
+#[derive(Clone, Default)]
+pub(crate) struct SingleFlight {
+    inflight: Arc<StdMutex<HashMap<String, FlightWaiter>>>,
+}
+
+/// Role of a caller that joined a single-flight key.
+pub(crate) enum Flight {
+    /// First caller for the key: run the request and report through the guard.
+    Leader(FlightGuard),
+    /// A matching request is already running: await its result.
+    Follower(FlightWaiter),
+}
+
+impl SingleFlight {
+    pub(crate) fn join(&self, key: &str) -> Flight {
+        let mut inflight = self.inflight.lock().unwrap();
+        if let Some(waiter) = inflight.get(key) {
+            return Flight::Follower(waiter.clone());
+        }
+        let (sender, receiver) = oneshot::channel();
+        inflight.insert(
+            key.to_string(),
+            async move { receiver.await.ok() }.boxed().shared(),
+        );
+        Flight::Leader(FlightGuard {
+            flights: self.clone(),
+            key: key.to_string(),
+            sender: Some(sender),
+        })
+    }
+}
+
+/// Held by the leader; clears the key when finished or dropped.
+pub(crate) struct FlightGuard {
+    flights: SingleFlight,
+    key: String,
+    sender: Option<oneshot::Sender<FlightResult>>,
+}
+
+impl FlightGuard {
+    pub(crate) fn finish(mut self, result: FlightResult) {
+        self.flights.inflight.lock().unwrap().remove(&self.key);
+        if let Some(sender) = self.sender.take() {
+            let _ = sender.send(result);
+        }
+    }
+}
+
+impl Drop for FlightGuard {
+    fn drop(&mut self) {
+        if self.sender.is_some() {
+            self.flights.inflight.lock().unwrap().remove(&self.key);
+        }
+    }
 }
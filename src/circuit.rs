@@ -0,0 +1,316 @@
+//! Circuit breaker that stops hammering a failing backend.
+//!
+//! After a configurable number of consecutive failures within a window the breaker
+//! opens and requests fail fast with [`StructuredError::CircuitOpen`]. Once the cooldown
+//! elapses a single probe request is let through (half-open); success closes the
+//! breaker again, failure re-opens it.
+//!
+//! [`CircuitBreaker::acquire`] hands out a [`CircuitPermit`]; if the permit that admitted
+//! the probe is dropped without an outcome (the call was cancelled, timed out or failed
+//! for a non-backend reason) the probe slot is released so the next request can probe.
+
+use std::{
+    sync::{Arc, Mutex},
+    time::{Duration, Instant},
+};
+
+use tracing::{info, warn};
+
+use crate::error::{Result, StructuredError};
+
+/// Configuration for [`CircuitBreaker`].
+#[derive(Clone, Debug)]
+pub struct CircuitBreakerConfig {
+    /// Consecutive failures required to open the circuit (default: 5).
+    pub failure_threshold: usize,
+    /// Failures older than this no longer count toward the threshold (default: 60s).
+    pub window: Duration,
+    /// How long the circuit stays open before allowing a probe request (default: 30s).
+    pub cooldown: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            window: Duration::from_secs(60),
+            cooldown: Duration::from_secs(30),
+        }
+    }
+}
+
+/// Observable state of a [`CircuitBreaker`], suitable for health checks.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    /// Requests flow normally.
+    Closed,
+    /// Requests are rejected until the cooldown elapses.
+    Open,
+    /// The cooldown elapsed; the next request is a recovery probe.
+    HalfOpen,
+}
+
+#[derive(Debug)]
+struct BreakerInner {
+    consecutive_failures: usize,
+    first_failure_at: Option<Instant>,
+    opened_at: Option<Instant>,
+    probe_in_flight: bool,
+}
+
+/// Shared circuit breaker. Clones observe and update the same state.
+#[derive(Clone, Debug)]
+pub struct CircuitBreaker {
+    config: CircuitBreakerConfig,
+    inner: Arc<Mutex<BreakerInner>>,
+}
+
+impl CircuitBreaker {
+    pub fn new(config: CircuitBreakerConfig) -> Self {
+        Self {
+            config: CircuitBreakerConfig {
+                failure_threshold: config.failure_threshold.max(1),
+                ..config
+            },
+            inner: Arc::new(Mutex::new(BreakerInner {
+                consecutive_failures: 0,
+                first_failure_at: None,
+                opened_at: None,
+                probe_in_flight: false,
+            })),
+        }
+    }
+
+    /// Current breaker configuration.
+    pub fn config(&self) -> &CircuitBreakerConfig {
+        &self.config
+    }
+
+    /// Current breaker state.
+    pub fn state(&self) -> CircuitState {
+        let inner = self.inner.lock().unwrap();
+        self.state_of(&inner)
+    }
+
+    fn state_of(&self, inner: &BreakerInner) -> CircuitState {
+        match inner.opened_at {
+            None => CircuitState::Closed,
+            Some(opened) if opened.elapsed() >= self.config.cooldown => CircuitState::HalfOpen,
+            Some(_) => CircuitState::Open,
+        }
+    }
+
+    /// Check whether a request may proceed.
+    ///
+    /// Returns [`StructuredError::CircuitOpen`] while the circuit is open, or while a
+    /// half-open probe is already in flight. A probe admitted here stays in flight until
+    /// [`record_success`](Self::record_success) or [`record_failure`](Self::record_failure)
+    /// is called; prefer [`acquire`](Self::acquire), which releases it on drop.
+    pub fn check(&self) -> Result<()> {
+        self.admit().map(|_| ())
+    }
+
+    /// Like [`check`](Self::check), but returns a permit that records the outcome and
+    /// releases the half-open probe slot if dropped without one.
+    pub fn acquire(&self) -> Result<CircuitPermit> {
+        let probe = self.admit()?;
+        Ok(CircuitPermit {
+            breaker: self.clone(),
+            probe,
+        })
+    }
+
+    /// Admit a request, returning whether it is the half-open probe.
+    fn admit(&self) -> Result<bool> {
+        let mut inner = self.inner.lock().unwrap();
+        match self.state_of(&inner) {
+            CircuitState::Closed => Ok(false),
+            CircuitState::HalfOpen if !inner.probe_in_flight => {
+                info!("Circuit half-open; allowing recovery probe");
+                inner.probe_in_flight = true;
+                Ok(true)
+            }
+            _ => {
+                let remaining = inner
+                    .opened_at
+                    .map(|opened| self.config.cooldown.saturating_sub(opened.elapsed()))
+                    .unwrap_or_default();
+                Err(StructuredError::CircuitOpen {
+                    retry_after_secs: remaining.as_secs().max(1),
+                })
+            }
+        }
+    }
+
+    /// Record a successful backend call, closing the circuit.
+    pub fn record_success(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        if inner.opened_at.is_some() {
+            info!("Circuit closed after successful probe");
+        }
+        inner.consecutive_failures = 0;
+        inner.first_failure_at = None;
+        inner.opened_at = None;
+        inner.probe_in_flight = false;
+    }
+
+    /// Record a failed backend call, opening the circuit once the threshold is reached.
+    pub fn record_failure(&self) {
+        let mut inner = self.inner.lock().unwrap();
+        let now = Instant::now();
+
+        if inner.probe_in_flight {
+            warn!("Recovery probe failed; re-opening circuit");
+            inner.probe_in_flight = false;
+            inner.opened_at = Some(now);
+            return;
+        }
+
+        let window_expired = inner
+            .first_failure_at
+            .map(|first| now.duration_since(first) > self.config.window)
+            .unwrap_or(true);
+        if window_expired {
+            inner.consecutive_failures = 0;
+            inner.first_failure_at = Some(now);
+        }
+        inner.consecutive_failures += 1;
+
+        if inner.opened_at.is_none() && inner.consecutive_failures >= self.config.failure_threshold
+        {
+            warn!(
+                failures = inner.consecutive_failures,
+                cooldown_secs = self.config.cooldown.as_secs(),
+                "Circuit opened after consecutive failures"
+            );
+            inner.opened_at = Some(now);
+        }
+    }
+}
+
+/// Permission to make one backend call, returned by [`CircuitBreaker::acquire`].
+///
+/// Report the outcome with [`record_success`](Self::record_success) or
+/// [`record_failure`](Self::record_failure). Dropping the permit without an outcome
+/// leaves the failure count untouched and frees the probe slot if this was the probe.
+#[must_use = "dropping the permit immediately releases a half-open probe"]
+#[derive(Debug)]
+pub struct CircuitPermit {
+    breaker: CircuitBreaker,
+    probe: bool,
+}
+
+impl CircuitPermit {
+    /// Record a successful backend call, closing the circuit.
+    pub fn record_success(mut self) {
+        self.probe = false;
+        self.breaker.record_success();
+    }
+
+    /// Record a failed backend call.
+    pub fn record_failure(mut self) {
+        self.probe = false;
+        self.breaker.record_failure();
+    }
+}
+
+impl Drop for CircuitPermit {
+    fn drop(&mut self) {
+        if self.probe {
+            let mut inner = self.breaker.inner.lock().unwrap();
+            inner.probe_in_flight = false;
+        }
+    }
+}
+
+/// Whether a client error indicates the backend itself is unhealthy.
+///
+/// Client-side mistakes (4xx other than 429) do not count toward opening the circuit.
+pub(crate) fn is_backend_failure(err: &gemini_rust::ClientError) -> bool {
+    match err {
+        gemini_rust::ClientError::BadResponse { code, .. } => *code == 429 || *code >= 500,
+        _ => true,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn breaker(threshold: usize, cooldown: Duration) -> CircuitBreaker {
+        CircuitBreaker::new(CircuitBreakerConfig {
+            failure_threshold: threshold,
+            window: Duration::from_secs(60),
+            cooldown,
+        })
+    }
+
+    #[test]
+    fn opens_after_threshold() {
+        let cb = breaker(3, Duration::from_secs(60));
+        cb.record_failure();
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Open);
+        assert!(matches!(
+            cb.check(),
+            Err(StructuredError::CircuitOpen { .. })
+        ));
+    }
+
+    #[test]
+    fn success_resets_failures() {
+        let cb = breaker(2, Duration::from_secs(60));
+        cb.record_failure();
+        cb.record_success();
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn half_open_allows_single_probe() {
+        let cb = breaker(1, Duration::ZERO);
+        cb.record_failure();
+        assert_eq!(cb.state(), CircuitState::HalfOpen);
+        assert!(cb.check().is_ok());
+        assert!(cb.check().is_err());
+
+        cb.record_failure();
+        assert!(cb.check().is_ok());
+        cb.record_success();
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[test]
+    fn dropped_probe_permit_releases_slot() {
+        let cb = breaker(1, Duration::ZERO);
+        cb.record_failure();
+
+        let probe = cb.acquire().unwrap();
+        assert!(cb.acquire().is_err());
+        drop(probe);
+
+        let next = cb.acquire().unwrap();
+        next.record_success();
+        assert_eq!(cb.state(), CircuitState::Closed);
+    }
+
+    #[tokio::test]
+    async fn cancelled_probe_future_releases_slot() {
+        let cb = breaker(1, Duration::ZERO);
+        cb.record_failure();
+
+        let probe_cb = cb.clone();
+        let probe = async move {
+            let permit = probe_cb.acquire()?;
+            std::future::pending::<()>().await;
+            permit.record_success();
+            Ok::<_, StructuredError>(())
+        };
+        let timed_out = tokio::time::timeout(Duration::from_millis(10), probe).await;
+        assert!(timed_out.is_err());
+
+        assert!(cb.acquire().is_ok());
+    }
+}
@@ -1,27 +1,33 @@
-use std::sync::Arc;
-use std::time::Instant;
+use std::collections::HashMap;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant, SystemTime};
 
 use gemini_rust::{
     generation::builder::ContentBuilder, generation::model::UsageMetadata, tools::FunctionCall,
     Gemini, GenerationConfig, Message, Model, Role, SafetySetting, Tool,
 };
 use serde::{de::DeserializeOwned, Serialize};
-use tracing::{debug, info, instrument, warn};
+use tracing::{debug, info, instrument, trace, warn};
 
 use crate::{
-    caching::{CachePolicy, CacheSettings, SchemaCache},
+    caching::{
+        CachePolicy, CacheSettings, CacheStats, IdempotencyCache, IdempotencyConfig, SchemaCache,
+        SingleFlight,
+    },
+    circuit::{CircuitBreaker, CircuitBreakerConfig, CircuitState},
     context::ContextBuilder,
     error::{Result, StructuredError},
     files::FileManager,
-    models::GenerationOutcome,
+    models::{GenerationOutcome, JsonlOutcome, ListEnvelope, Pair, Quad, Triple},
     patching::{
-        ArrayPatchStrategy, PatchStrategy, RefinementConfig, RefinementEngine, RefinementRequest,
-        ValidationFailureStrategy,
+        ArrayPatchStrategy, PatchFormat, PatchStrategy, RefinementConfig, RefinementEngine,
+        RefinementMode, RefinementRequest, ValidationFailureStrategy,
     },
     schema::{
         clean_schema_for_gemini, GeminiStructured, StructuredValidator, STRICT_SCHEMA_DEPTH_LIMIT,
     },
-    tools::ToolRegistry,
+    tools::{function_names, ToolRegistry},
     StructuredRequest,
 };
 
@@ -51,15 +57,38 @@ pub type MockHandler = Arc<dyn Fn(MockRequest) -> Result<String> + Send + Sync>;
 /// ```
 pub type ResponseHook = Arc<dyn Fn(&mut serde_json::Value) + Send + Sync>;
 
+/// Decides whether a failed network call should be retried.
+///
+/// When set, the classifier is consulted first and its answer is final, replacing the
+/// built-in [`StructuredError::is_retryable`] check. Call `is_retryable` inside the
+/// classifier to extend the built-in rules rather than replace them.
+///
+/// # Example
+/// ```rust,ignore
+/// let client = StructuredClientBuilder::new("api-key")
+///     .with_retry_classifier(|err: &StructuredError| {
+///         // Our gateway reports upstream timeouts as 599.
+///         err.is_retryable() || err.to_string().contains("599")
+///     })
+///     .build()?;
+/// ```
+pub type RetryClassifier = Arc<dyn Fn(&StructuredError) -> bool + Send + Sync>;
+
 /// Minimal view of a structured request passed to [`MockHandler`].
 #[derive(Debug, Clone)]
 pub struct MockRequest {
     /// The target Rust type name (for logging/debugging only).
     pub target: String,
+    /// Name of the model the call would be sent to, after overrides and escalation.
+    pub model: String,
     /// The system instruction, if any.
     pub system_instruction: Option<String>,
     /// A debug representation of the prompt messages.
     pub prompt_preview: String,
+    /// JSON Schema the response must match, when the call has one.
+    pub schema: Option<serde_json::Value>,
+    /// Names of the function tools offered on this call (built-in tools are not listed).
+    pub tools: Vec<String>,
 }
 
 /// Strategy for handling model fallbacks during generation and refinement.
@@ -81,6 +110,41 @@ pub enum FallbackStrategy {
         /// The model to escalate to.
         target: Model,
     },
+    /// Escalate through several models as attempts fail.
+    ///
+    /// Each `(after_attempts, model)` tier takes over once more than `after_attempts`
+    /// attempts have failed; the highest exceeded threshold wins. Order does not matter.
+    ///
+    /// ```rust,ignore
+    /// FallbackStrategy::Chain(vec![
+    ///     (1, Model::Gemini3Flash),
+    ///     (3, Model::Gemini25Pro),
+    /// ])
+    /// ```
+    Chain(Vec<(usize, Model)>),
+}
+
+impl FallbackStrategy {
+    /// Escalation tiers as `(after_attempts, model)`, in ascending threshold order.
+    pub fn tiers(&self) -> Vec<(usize, Model)> {
+        let mut tiers = match self {
+            Self::None => Vec::new(),
+            Self::Escalate {
+                after_attempts,
+                target,
+            } => vec![(*after_attempts, target.clone())],
+            Self::Chain(tiers) => tiers.clone(),
+        };
+        tiers.sort_by_key(|(after_attempts, _)| *after_attempts);
+        tiers
+    }
+
+    /// Index into [`tiers`](Self::tiers) of the tier active after `attempt` failures.
+    pub fn active_tier(&self, attempt: usize) -> Option<usize> {
+        self.tiers()
+            .iter()
+            .rposition(|(after_attempts, _)| attempt > *after_attempts)
+    }
 }
 
 /// Options for building a configured content request.
@@ -92,6 +156,10 @@ pub(crate) struct BuilderOptions<'a> {
     pub system_instruction: &'a Option<String>,
     pub safety_settings: &'a Option<Vec<SafetySetting>>,
     pub force_prompt_schema: bool,
+    /// Model the request is bound to when it differs from the client's primary model.
+    pub model: Option<&'a Model>,
+    /// Request-specific transforms applied to the response schema, in order.
+    pub schema_transforms: &'a [Box<dyn crate::schema::SchemaTransform>],
 }
 
 /// Global configuration options for the client.
@@ -109,6 +177,21 @@ pub struct ClientConfig {
     pub array_strategy: ArrayPatchStrategy,
     /// Strategy for enum-keyed map schemas (default: Preserve)
     pub map_schema_mode: crate::schema::MapSchemaMode,
+    /// Skip embedding the schema when the system prompt already has it (default: true)
+    pub dedupe_prompt_schema: bool,
+    /// Embed prompt schemas as compact JSON to save tokens (default: false)
+    pub compact_prompt_schema: bool,
+    /// JSON pointers whose values are replaced with `"[REDACTED]"` in logged responses
+    /// and patches (default: empty)
+    pub redact_paths: Vec<String>,
+    /// Locale applied to every request via
+    /// [`StructuredRequest::with_locale`](crate::StructuredRequest::with_locale) (default: none)
+    pub default_locale: Option<String>,
+    /// Maximum concurrent requests in [`StructuredClient::quick_generate_batch`] (default: 4)
+    pub batch_concurrency: usize,
+    /// Sampler seed applied to every request via
+    /// [`StructuredRequest::seed`](crate::StructuredRequest::seed) (default: none)
+    pub default_seed: Option<i64>,
 }
 
 impl Default for ClientConfig {
@@ -120,24 +203,43 @@ impl Default for ClientConfig {
             default_tool_steps: 5,
             array_strategy: ArrayPatchStrategy::ReplaceWhole,
             map_schema_mode: crate::schema::MapSchemaMode::Preserve,
+            dedupe_prompt_schema: true,
+            compact_prompt_schema: false,
+            redact_paths: Vec::new(),
+            default_locale: None,
+            batch_concurrency: 4,
+            default_seed: None,
         }
     }
 }
 
+const PROMPT_SCHEMA_PREFIX: &str = "You must output valid JSON matching this schema exactly:";
+
 /// Builder for [`StructuredClient`].
 pub struct StructuredClientBuilder {
     api_key: String,
     model: Model,
     cache_policy: CachePolicy,
+    cache_path: Option<PathBuf>,
     refinement_retries: usize,
     refinement_temperature: f32,
     refinement_network_retries: usize,
     refinement_strategy: PatchStrategy,
+    refinement_mode: RefinementMode,
+    patch_format: PatchFormat,
+    refinement_best_effort: bool,
+    validation_failure_strategy: ValidationFailureStrategy,
     fallback_strategy: FallbackStrategy,
     config: ClientConfig,
     mock_handler: Option<MockHandler>,
+    validate_mocks: bool,
     refinement_engine_override: Option<RefinementEngine>,
     response_hook: Option<ResponseHook>,
+    retry_classifier: Option<RetryClassifier>,
+    circuit_breaker: Option<CircuitBreakerConfig>,
+    idempotency: IdempotencyConfig,
+    default_tools: Option<ToolRegistry>,
+    single_flight: bool,
 }
 
 impl StructuredClientBuilder {
@@ -146,15 +248,26 @@ impl StructuredClientBuilder {
             api_key: api_key.into(),
             model: Model::Gemini25Flash,
             cache_policy: CachePolicy::Disabled,
+            cache_path: None,
             refinement_retries: 3,
             refinement_temperature: 0.0,
             refinement_network_retries: 3,
             refinement_strategy: PatchStrategy::PartialApply,
+            refinement_mode: RefinementMode::default(),
+            patch_format: PatchFormat::default(),
+            refinement_best_effort: false,
+            validation_failure_strategy: ValidationFailureStrategy::default(),
             fallback_strategy: FallbackStrategy::default(),
             config: ClientConfig::default(),
             mock_handler: None,
+            validate_mocks: false,
             refinement_engine_override: None,
             response_hook: None,
+            retry_classifier: None,
+            circuit_breaker: None,
+            idempotency: IdempotencyConfig::default(),
+            default_tools: None,
+            single_flight: false,
         }
     }
 
@@ -170,6 +283,15 @@ impl StructuredClientBuilder {
         self
     }
 
+    /// Persist cached content handles as JSON at `path` so they survive restarts.
+    ///
+    /// See [`SchemaCache::with_persistence`]. Only takes effect with an enabled
+    /// [`CachePolicy`].
+    pub fn with_cache_persistence(mut self, path: impl Into<PathBuf>) -> Self {
+        self.cache_path = Some(path.into());
+        self
+    }
+
     /// Set maximum refinement retry attempts.
     pub fn with_refinement_retries(mut self, retries: usize) -> Self {
         self.refinement_retries = retries.max(1);
@@ -195,6 +317,38 @@ impl StructuredClientBuilder {
         self
     }
 
+    /// Ask refinement for JSON Patches (default) or complete corrected documents.
+    ///
+    /// See [`RefinementMode`].
+    pub fn with_refinement_mode(mut self, mode: RefinementMode) -> Self {
+        self.refinement_mode = mode;
+        self
+    }
+
+    /// Ask refinement for RFC 6902 JSON Patches (default) or RFC 7386 merge patches.
+    ///
+    /// See [`PatchFormat`].
+    pub fn with_patch_format(mut self, format: PatchFormat) -> Self {
+        self.patch_format = format;
+        self
+    }
+
+    /// Return the last schema-valid value when refinement exhausts its retries.
+    ///
+    /// See [`RefinementConfig::return_best_effort`].
+    pub fn with_refinement_best_effort(mut self, enabled: bool) -> Self {
+        self.refinement_best_effort = enabled;
+        self
+    }
+
+    /// Strategy for handling validation failures during refinement (default: iterate forward).
+    ///
+    /// See [`ValidationFailureStrategy`].
+    pub fn with_validation_failure_strategy(mut self, strategy: ValidationFailureStrategy) -> Self {
+        self.validation_failure_strategy = strategy;
+        self
+    }
+
     /// Set the fallback strategy for model escalation.
     ///
     /// When enabled, the client will automatically switch to a more capable model
@@ -224,6 +378,12 @@ impl StructuredClientBuilder {
         self
     }
 
+    /// Pin the sampler seed for every request by default.
+    pub fn with_default_seed(mut self, seed: i64) -> Self {
+        self.config.default_seed = Some(seed);
+        self
+    }
+
     /// Set the default number of retries for transient errors.
     pub fn with_default_retries(mut self, retries: usize) -> Self {
         self.config.default_retries = retries;
@@ -254,6 +414,29 @@ impl StructuredClientBuilder {
         self
     }
 
+    /// Redact sensitive fields from debug and trace logging.
+    ///
+    /// Each path is a JSON pointer into the structured output (e.g. `/contact/email`);
+    /// a `*` segment matches every array element or key (e.g. `/people/*/ssn`). Matching
+    /// values are logged as `"[REDACTED]"` in raw responses, parse failures and refinement
+    /// patches. Returned values are never modified.
+    pub fn with_redaction(mut self, paths: Vec<String>) -> Self {
+        self.config.redact_paths = paths;
+        self
+    }
+
+    /// Ask for natural-language output in `locale` on every request by default.
+    pub fn with_default_locale(mut self, locale: impl Into<String>) -> Self {
+        self.config.default_locale = Some(locale.into());
+        self
+    }
+
+    /// Set how many requests [`StructuredClient::quick_generate_batch`] runs at once.
+    pub fn with_batch_concurrency(mut self, concurrency: usize) -> Self {
+        self.config.batch_concurrency = concurrency.max(1);
+        self
+    }
+
     /// Apply a complete client configuration.
     pub fn with_config(mut self, config: ClientConfig) -> Self {
         self.config = config;
@@ -271,6 +454,36 @@ impl StructuredClientBuilder {
         self
     }
 
+    /// Answer every request with a [`schema_example`](crate::schema::schema_example) of
+    /// its response schema.
+    ///
+    /// Lets dynamically assembled pipelines run end to end without network calls, e.g.
+    /// via [`Workflow::validate_wiring`](crate::Workflow::validate_wiring).
+    pub fn with_schema_example_mock(self) -> Self {
+        self.with_mock(|request| match request.schema {
+            Some(schema) => Ok(crate::schema::schema_example(&schema).to_string()),
+            None => Err(StructuredError::Context(format!(
+                "no response schema to derive a mock for {}",
+                request.target
+            ))),
+        })
+    }
+
+    /// Like [`with_mock`](Self::with_mock), but validate mock output against the target schema.
+    ///
+    /// The mock JSON runs through the same normalization as real responses and is then
+    /// checked against `T::gemini_schema()`. Mismatches fail with a
+    /// [`StructuredError::Validation`] listing each schema violation, which catches stale
+    /// mocks after `T` changes instead of surfacing a confusing parse error.
+    pub fn with_mock_validated(
+        mut self,
+        handler: impl Fn(MockRequest) -> Result<String> + Send + Sync + 'static,
+    ) -> Self {
+        self.mock_handler = Some(Arc::new(handler));
+        self.validate_mocks = true;
+        self
+    }
+
     /// Override the refinement engine (useful for offline tests or custom backends).
     pub fn with_refinement_engine(mut self, engine: RefinementEngine) -> Self {
         self.refinement_engine_override = Some(engine);
@@ -302,17 +515,95 @@ impl StructuredClientBuilder {
         self
     }
 
+    /// Override which errors are retried in the request and refinement retry loops.
+    ///
+    /// See [`RetryClassifier`] for precedence. Without a classifier the built-in
+    /// [`StructuredError::is_retryable`] rules apply (503 and non-quota 429).
+    pub fn with_retry_classifier(
+        mut self,
+        classifier: impl Fn(&StructuredError) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        self.retry_classifier = Some(Arc::new(classifier));
+        self
+    }
+
+    /// Enable a circuit breaker for backend failures.
+    ///
+    /// After `failure_threshold` consecutive failures (5xx, 429 or transport errors)
+    /// requests fail fast with [`StructuredError::CircuitOpen`] until the cooldown
+    /// elapses, instead of running the full retry loop against an unhealthy backend.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let client = StructuredClientBuilder::new("api-key")
+    ///     .with_circuit_breaker(CircuitBreakerConfig {
+    ///         failure_threshold: 5,
+    ///         window: Duration::from_secs(60),
+    ///         cooldown: Duration::from_secs(30),
+    ///     })
+    ///     .build()?;
+    /// ```
+    pub fn with_circuit_breaker(mut self, config: CircuitBreakerConfig) -> Self {
+        self.circuit_breaker = Some(config);
+        self
+    }
+
+    /// Configure the TTL and size bound of the idempotency cache used by
+    /// [`StructuredRequest::idempotency_key`].
+    pub fn with_idempotency_config(mut self, config: IdempotencyConfig) -> Self {
+        self.idempotency = config;
+        self
+    }
+
+    /// Make `registry` available to every request built from this client.
+    ///
+    /// Requests merge it with their own [`StructuredRequest::with_tools`] registry via
+    /// [`ToolRegistry::merge`], so per-request tools win on name collisions. Opt out
+    /// per request with [`StructuredRequest::without_default_tools`].
+    pub fn with_default_tools(mut self, registry: ToolRegistry) -> Self {
+        self.default_tools = Some(registry);
+        self
+    }
+
+    /// Share one API call between identical requests that are in flight together
+    /// (default: false).
+    ///
+    /// Requests match when a SHA-256 hash of their target type and every setting that
+    /// shapes the response (model override, system instruction, messages, tools,
+    /// generation config, parsing and retry options) is equal. Requests with a
+    /// post-processor, tool registry, tool context or schema transforms carry closures
+    /// that cannot be compared and never share a call.
+    ///
+    /// Later callers wait for the first one and receive a clone of its outcome, or a copy
+    /// of its error made with [`StructuredError::try_clone`]. When the error cannot be
+    /// cloned, or the first caller is cancelled, they run on their own. Combine with
+    /// [`StructuredRequest::idempotency_key`] to also replay results after completion.
+    pub fn with_single_flight(mut self, enabled: bool) -> Self {
+        self.single_flight = enabled;
+        self
+    }
+
     /// Build the client.
     pub fn build(self) -> Result<StructuredClient> {
         let client = Arc::new(Gemini::with_model(&self.api_key, self.model.clone())?);
 
-        // Create fallback client if escalation is enabled
-        let fallback_client = match &self.fallback_strategy {
-            FallbackStrategy::Escalate { target, .. } => {
-                Some(Arc::new(Gemini::with_model(&self.api_key, target.clone())?))
-            }
-            FallbackStrategy::None => None,
-        };
+        // Create one client per escalation tier
+        let fallback_clients = self
+            .fallback_strategy
+            .tiers()
+            .into_iter()
+            .map(|(_, model)| Ok(Arc::new(Gemini::with_model(&self.api_key, model)?)))
+            .collect::<Result<Vec<_>>>()?;
+        let fallback_client = fallback_clients.first().cloned();
+        let mut model_clients = HashMap::from([(self.model.as_str().to_string(), client.clone())]);
+        for ((_, model), tier_client) in
+            self.fallback_strategy.tiers().iter().zip(&fallback_clients)
+        {
+            model_clients
+                .entry(model.as_str().to_string())
+                .or_insert_with(|| tier_client.clone());
+        }
 
         let refiner_config = RefinementConfig {
             max_retries: self.refinement_retries,
@@ -321,35 +612,65 @@ impl StructuredClientBuilder {
             array_strategy: self.config.array_strategy.clone(),
             network_retries: self.refinement_network_retries,
             fallback_strategy: self.fallback_strategy.clone(),
-            validation_failure_strategy: ValidationFailureStrategy::default(),
+            validation_failure_strategy: self.validation_failure_strategy,
+            return_best_effort: self.refinement_best_effort,
+            redact_paths: self.config.redact_paths.clone(),
+            mode: self.refinement_mode,
+            patch_format: self.patch_format,
         };
 
         let refiner = if let Some(engine) = self.refinement_engine_override {
             engine.with_config(refiner_config)
         } else {
-            RefinementEngine::new(client.clone(), fallback_client.clone())
+            RefinementEngine::new(client.clone(), None)
+                .with_fallback_clients(fallback_clients.clone())
                 .with_config(refiner_config)
         };
+        let refiner = match &self.retry_classifier {
+            Some(classifier) => refiner.with_retry_classifier(classifier.clone()),
+            None => refiner,
+        };
+
+        let cache = SchemaCache::new(client.clone(), self.cache_policy);
+        let cache = match self.cache_path {
+            Some(path) => cache.with_persistence(path),
+            None => cache,
+        };
 
         Ok(StructuredClient {
+            api_key: self.api_key,
             client: client.clone(),
             fallback_client,
+            fallback_clients,
+            model_clients: Arc::new(Mutex::new(model_clients)),
             fallback_strategy: self.fallback_strategy,
             model: self.model,
             file_manager: FileManager::new(client.clone()),
             refiner,
-            cache: SchemaCache::new(client.clone(), self.cache_policy),
+            cache,
             config: self.config,
             mock_handler: self.mock_handler,
+            validate_mocks: self.validate_mocks,
             response_hook: self.response_hook,
+            retry_classifier: self.retry_classifier,
+            circuit_breaker: self.circuit_breaker.map(CircuitBreaker::new),
+            idempotency: IdempotencyCache::new(self.idempotency),
+            default_tools: self.default_tools,
+            single_flight: self.single_flight.then(SingleFlight::default),
         })
     }
 }
 
 #[derive(Clone)]
 pub struct StructuredClient {
+    api_key: String,
     pub client: Arc<Gemini>,
+    /// First escalation tier's client, also used for empty-response escalation.
     pub fallback_client: Option<Arc<Gemini>>,
+    /// One client per [`FallbackStrategy::tiers`] entry, in the same order.
+    pub(crate) fallback_clients: Vec<Arc<Gemini>>,
+    /// Clients for per-request model overrides, keyed by model name and built on first use.
+    model_clients: Arc<Mutex<HashMap<String, Arc<Gemini>>>>,
     pub fallback_strategy: FallbackStrategy,
     pub model: Model,
     pub file_manager: FileManager,
@@ -357,7 +678,13 @@ pub struct StructuredClient {
     cache: SchemaCache,
     config: ClientConfig,
     pub(crate) mock_handler: Option<MockHandler>,
+    validate_mocks: bool,
     pub(crate) response_hook: Option<ResponseHook>,
+    retry_classifier: Option<RetryClassifier>,
+    pub(crate) circuit_breaker: Option<CircuitBreaker>,
+    pub(crate) idempotency: IdempotencyCache,
+    pub(crate) default_tools: Option<ToolRegistry>,
+    pub(crate) single_flight: Option<SingleFlight>,
 }
 
 impl StructuredClient {
@@ -401,6 +728,49 @@ impl StructuredClient {
         Ok(result.value)
     }
 
+    /// Run [`quick_generate`](Self::quick_generate) for many prompts concurrently.
+    ///
+    /// At most [`ClientConfig::batch_concurrency`] requests are in flight at once.
+    /// Results come back in prompt order, and each prompt's error is kept in its own slot
+    /// so one failure does not abort the batch.
+    #[instrument(skip_all, fields(target = std::any::type_name::<T>(), count = prompts.len()))]
+    pub async fn quick_generate_batch<T>(&self, prompts: Vec<String>) -> Result<Vec<Result<T>>>
+    where
+        T: GeminiStructured
+            + StructuredValidator
+            + Serialize
+            + DeserializeOwned
+            + Clone
+            + Send
+            + Sync
+            + 'static,
+    {
+        let semaphore = Arc::new(tokio::sync::Semaphore::new(
+            self.config.batch_concurrency.max(1),
+        ));
+        let handles: Vec<_> = prompts
+            .into_iter()
+            .map(|prompt| {
+                let client = self.clone();
+                let semaphore = Arc::clone(&semaphore);
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await.map_err(|e| {
+                        StructuredError::Context(format!("Batch semaphore closed: {e}"))
+                    })?;
+                    client.quick_generate::<T>(prompt).await
+                })
+            })
+            .collect();
+
+        let mut results = Vec::with_capacity(handles.len());
+        for handle in handles {
+            results.push(handle.await.unwrap_or_else(|e| {
+                Err(StructuredError::Context(format!("Batch task failed: {e}")))
+            }));
+        }
+        Ok(results)
+    }
+
     /// Quick generation with a system instruction.
     #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
     pub async fn quick_generate_with_system<T>(
@@ -428,6 +798,206 @@ impl StructuredClient {
         Ok(result.value)
     }
 
+    /// Extract two distinct types from one prompt in a single call.
+    ///
+    /// Both outputs are generated against a combined `{ first: A, second: B }` schema,
+    /// so a large input is only sent (and billed) once.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let (summary, metadata): (Summary, Metadata) = client.generate_pair(document).await?;
+    /// ```
+    pub async fn generate_pair<A, B>(&self, prompt: impl Into<String>) -> Result<(A, B)>
+    where
+        Pair<A, B>: GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        Ok(self
+            .generate_combined::<Pair<A, B>>(prompt)
+            .await?
+            .into_tuple())
+    }
+
+    /// Extract three distinct types from one prompt in a single call.
+    ///
+    /// See [`generate_pair`](Self::generate_pair).
+    pub async fn generate_triple<A, B, C>(&self, prompt: impl Into<String>) -> Result<(A, B, C)>
+    where
+        Triple<A, B, C>:
+            GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        Ok(self
+            .generate_combined::<Triple<A, B, C>>(prompt)
+            .await?
+            .into_tuple())
+    }
+
+    /// Extract four distinct types from one prompt in a single call.
+    ///
+    /// See [`generate_pair`](Self::generate_pair).
+    pub async fn generate_quad<A, B, C, D>(&self, prompt: impl Into<String>) -> Result<(A, B, C, D)>
+    where
+        Quad<A, B, C, D>:
+            GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        Ok(self
+            .generate_combined::<Quad<A, B, C, D>>(prompt)
+            .await?
+            .into_tuple())
+    }
+
+    /// Extract a list of `T` from a prompt.
+    ///
+    /// The model fills a `{ items: [...] }` envelope that is unwrapped before returning,
+    /// so no throwaway wrapper struct is needed. A bare array response is accepted too.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let contacts: Vec<Contact> = client.generate_list(email_thread).await?;
+    /// ```
+    #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
+    pub async fn generate_list<T>(&self, prompt: impl Into<String>) -> Result<Vec<T>>
+    where
+        ListEnvelope<T>:
+            GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        Ok(self.quick_generate::<ListEnvelope<T>>(prompt).await?.items)
+    }
+
+    /// Extract a list of `T` from a prompt with a system instruction.
+    ///
+    /// See [`generate_list`](Self::generate_list).
+    #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
+    pub async fn generate_list_with_system<T>(
+        &self,
+        system: impl Into<String>,
+        prompt: impl Into<String>,
+    ) -> Result<Vec<T>>
+    where
+        ListEnvelope<T>:
+            GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        Ok(self
+            .quick_generate_with_system::<ListEnvelope<T>>(system, prompt)
+            .await?
+            .items)
+    }
+
+    /// Extract `T` records as JSON Lines, one object per line.
+    ///
+    /// Each non-empty line is parsed on its own, so a truncated or garbled line only
+    /// loses that record. With `skip_malformed`, failing lines are logged and dropped;
+    /// otherwise any failure returns [`StructuredError::Validation`] naming the lines.
+    /// Use [`generate_jsonl_report`](Self::generate_jsonl_report) to inspect failures.
+    ///
+    /// # Example
+    /// ```rust,ignore
+    /// let events: Vec<LogEvent> = client.generate_jsonl(raw_logs, true).await?;
+    /// ```
+    #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
+    pub async fn generate_jsonl<T>(
+        &self,
+        prompt: impl Into<String>,
+        skip_malformed: bool,
+    ) -> Result<Vec<T>>
+    where
+        T: GeminiStructured + DeserializeOwned,
+    {
+        let outcome = self.generate_jsonl_report::<T>(prompt).await?;
+        if outcome.failed_lines.is_empty() {
+            return Ok(outcome.items);
+        }
+        if !skip_malformed {
+            let failures: Vec<String> = outcome
+                .failed_lines
+                .iter()
+                .map(ToString::to_string)
+                .collect();
+            return Err(StructuredError::Validation(format!(
+                "{} JSONL line(s) failed to parse: {}",
+                failures.len(),
+                failures.join("; ")
+            )));
+        }
+        for failure in &outcome.failed_lines {
+            warn!(line = failure.line, error = %failure.error, "Skipping malformed JSONL line");
+        }
+        Ok(outcome.items)
+    }
+
+    /// Extract `T` records as JSON Lines, returning parsed items and failed lines.
+    ///
+    /// The call goes through the client's circuit breaker and retry policy, and the
+    /// response hook runs on each line before it is deserialized. See
+    /// [`generate_jsonl`](Self::generate_jsonl).
+    #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
+    pub async fn generate_jsonl_report<T>(
+        &self,
+        prompt: impl Into<String>,
+    ) -> Result<JsonlOutcome<T>>
+    where
+        T: GeminiStructured + DeserializeOwned,
+    {
+        let prompt = prompt.into();
+        let schema = serde_json::to_string(&T::gemini_schema())?;
+        let system = format!(
+            "Respond in JSON Lines: one compact JSON object per line, each matching this \
+             JSON Schema:\n{schema}\nDo not wrap the objects in an array and do not add \
+             commentary."
+        );
+
+        let text = match &self.mock_handler {
+            Some(mock) => (mock)(MockRequest {
+                target: std::any::type_name::<T>().to_string(),
+                model: self.model.as_str().to_string(),
+                system_instruction: Some(system),
+                prompt_preview: prompt,
+                schema: Some(T::gemini_schema()),
+                tools: Vec::new(),
+            })?,
+            None => {
+                let config = GenerationConfig {
+                    temperature: Some(self.config.default_temperature),
+                    seed: self.config.default_seed,
+                    ..Default::default()
+                };
+                let response = self
+                    .execute_with_retries(self.config.default_retries, || {
+                        self.client
+                            .generate_content()
+                            .with_system_instruction(system.as_str())
+                            .with_generation_config(config.clone())
+                            .with_message(Message::user(prompt.as_str()))
+                    })
+                    .await?;
+                response.text()
+            }
+        };
+
+        let outcome = JsonlOutcome::parse_with_hook(&text, self.response_hook());
+        debug!(
+            items = outcome.items.len(),
+            failed = outcome.failed_lines.len(),
+            "Parsed JSONL response"
+        );
+        Ok(outcome)
+    }
+
+    async fn generate_combined<W>(&self, prompt: impl Into<String>) -> Result<W>
+    where
+        W: GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+    {
+        let result = self
+            .request::<W>()
+            .system(
+                "Produce every requested output from the same input. \
+                 Fill each field independently according to its own schema.",
+            )
+            .user_text(prompt)
+            .execute()
+            .await?;
+        Ok(result.value)
+    }
+
     /// Generate a structured response validated by `T`'s schema.
     #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
     pub async fn generate<T>(&self, ctx: ContextBuilder, tools: Option<ToolRegistry>) -> Result<T>
@@ -504,37 +1074,237 @@ impl StructuredClient {
         &self.config
     }
 
+    /// Hit, miss, creation and eviction counts for cached content, e.g. to log after a
+    /// batch run whether [`CacheSettings`] are saving tokens.
+    pub fn cache_stats(&self) -> CacheStats {
+        self.cache.stats()
+    }
+
+    /// Drop the cached content entry for `key` so the next request recreates it.
+    ///
+    /// `key` is a [`CacheSettings::key`] or a derived
+    /// [`SchemaCache::cache_key`]. Deleting the remote cached content is best-effort;
+    /// if it fails the server-side copy simply expires with its TTL. Returns whether a
+    /// local entry existed.
+    pub async fn invalidate_cache_key(&self, key: &str) -> bool {
+        self.cache.invalidate(key).await
+    }
+
+    /// Drop every cached content entry, deleting remote copies on a best-effort basis.
+    pub async fn clear_cache(&self) {
+        self.cache.clear().await
+    }
+
     /// Get the fallback strategy.
     pub fn fallback_strategy(&self) -> &FallbackStrategy {
         &self.fallback_strategy
     }
 
+    /// Current circuit breaker state, or `None` when no breaker is configured.
+    ///
+    /// Intended for health checks and readiness probes.
+    pub fn circuit_state(&self) -> Option<CircuitState> {
+        self.circuit_breaker.as_ref().map(|cb| cb.state())
+    }
+
+    /// Parse a mock handler's output, validating it against `T`'s schema when enabled.
+    ///
+    /// The value goes through the same normalization as a live response, including the
+    /// request's field defaults and enum-case handling.
+    pub(crate) fn parse_mock_response<T>(
+        &self,
+        raw: &str,
+        field_defaults: &HashMap<String, serde_json::Value>,
+        normalize_enum_case: bool,
+    ) -> Result<T>
+    where
+        T: GeminiStructured + DeserializeOwned,
+    {
+        let mut value: serde_json::Value =
+            serde_json::from_str(raw).map_err(|e| StructuredError::parse_error(e, raw))?;
+        let schema = T::gemini_schema();
+        self.normalize_response(&mut value, &schema, field_defaults, normalize_enum_case);
+        if !self.validate_mocks {
+            return serde_json::from_value(value).map_err(|e| StructuredError::parse_error(e, raw));
+        }
+
+        let validator = crate::schema::compile_validator::<T>()?;
+        let errors: Vec<String> = validator
+            .iter_errors(&value)
+            .map(|err| format!("{}: {}", err.instance_path(), err))
+            .collect();
+        if !errors.is_empty() {
+            return Err(StructuredError::Validation(format!(
+                "Mock response for {} does not match its schema: {}",
+                std::any::type_name::<T>(),
+                errors.join("; ")
+            )));
+        }
+
+        serde_json::from_value(value).map_err(|e| StructuredError::parse_error(e, raw))
+    }
+
     /// Access the internal refinement engine.
     pub(crate) fn refiner(&self) -> &RefinementEngine {
         &self.refiner
     }
 
+    /// Effective configuration of the client's refinement engine.
+    pub fn refinement_config(&self) -> &RefinementConfig {
+        self.refiner.config()
+    }
+
+    /// Normalize a parsed model response before deserialization.
+    ///
+    /// Shared by live requests and validated mocks so both see the same value.
+    pub(crate) fn normalize_response(
+        &self,
+        value: &mut serde_json::Value,
+        schema: &serde_json::Value,
+        field_defaults: &HashMap<String, serde_json::Value>,
+        normalize_enum_case: bool,
+    ) {
+        // Prune null fields to handle Gemini's occasional nulls for optional fields
+        crate::schema::prune_null_fields(value);
+        crate::schema::normalize_map_entry_arrays(value);
+        crate::schema::inject_defaults(value, field_defaults);
+
+        // Convert flat discriminator enums back to externally tagged format.
+        crate::schema::unflatten_externally_tagged_enums(value, schema);
+
+        if normalize_enum_case {
+            crate::schema::normalize_enum_case(value, schema);
+        }
+
+        // Coerce enum strings when the model returns close-but-invalid values.
+        crate::schema::coerce_enum_strings(value, schema);
+
+        // Recover internally-tagged enums that Gemini collapsed to strings
+        crate::schema::recover_internally_tagged_enums(value, schema);
+
+        // Apply user-provided response hook for custom transformations
+        if let Some(hook) = self.response_hook() {
+            hook(value);
+        }
+    }
+
     /// Access the response hook (if configured).
     pub(crate) fn response_hook(&self) -> Option<&ResponseHook> {
         self.response_hook.as_ref()
     }
 
-    /// Select the appropriate client based on the fallback strategy and attempt count.
+    /// Send the request built by `build` under the circuit breaker and retry policy that
+    /// structured requests use.
     ///
-    /// Returns a tuple of (client, escalated) where `escalated` is true if this is
-    /// using the fallback model.
-    pub(crate) fn select_client(&self, attempt: usize) -> (&Arc<Gemini>, bool) {
-        match &self.fallback_strategy {
-            FallbackStrategy::Escalate {
-                after_attempts,
-                target: _,
-            } if attempt > *after_attempts && self.fallback_client.is_some() => {
-                (self.fallback_client.as_ref().unwrap(), true)
+    /// Retryable failures (see [`is_retryable`](Self::is_retryable)) are retried up to
+    /// `retries` times, waiting for the API's retry delay or backing off exponentially.
+    pub(crate) async fn execute_with_retries<F>(
+        &self,
+        retries: usize,
+        build: F,
+    ) -> Result<gemini_rust::GenerationResponse>
+    where
+        F: Fn() -> ContentBuilder,
+    {
+        let mut attempt = 0usize;
+        loop {
+            // Dropping the permit on a non-backend error frees a half-open probe slot.
+            let permit = self
+                .circuit_breaker
+                .as_ref()
+                .map(|cb| cb.acquire())
+                .transpose()?;
+            let err = match build().execute().await {
+                Ok(response) => {
+                    if let Some(permit) = permit {
+                        permit.record_success();
+                    }
+                    return Ok(response);
+                }
+                Err(e) => {
+                    if let Some(permit) = permit {
+                        if crate::circuit::is_backend_failure(&e) {
+                            permit.record_failure();
+                        }
+                    }
+                    StructuredError::from_client_error(e)
+                }
+            };
+
+            if attempt >= retries || !self.is_retryable(&err) {
+                return Err(err);
             }
-            _ => (&self.client, false),
+            let delay_secs = err
+                .retry_delay()
+                .unwrap_or_else(|| 2u64.pow(attempt as u32));
+            warn!(
+                "Attempt {}/{} failed: {}. Retrying in {}s...",
+                attempt + 1,
+                retries + 1,
+                err,
+                delay_secs
+            );
+            tokio::time::sleep(Duration::from_secs(delay_secs)).await;
+            attempt += 1;
+        }
+    }
+
+    /// Whether a failed call should be retried, consulting the custom classifier first.
+    pub(crate) fn is_retryable(&self, err: &StructuredError) -> bool {
+        match &self.retry_classifier {
+            Some(classifier) => classifier(err),
+            None => err.is_retryable(),
         }
     }
 
+    /// Select the appropriate client based on the fallback strategy and attempt count.
+    ///
+    /// Returns a tuple of (client, tier) where `tier` indexes
+    /// [`FallbackStrategy::tiers`] when a fallback model is active.
+    pub(crate) fn select_client(&self, attempt: usize) -> (&Arc<Gemini>, Option<usize>) {
+        let tier = self.fallback_strategy.active_tier(attempt);
+        match tier.and_then(|index| self.fallback_clients.get(index)) {
+            Some(client) => (client, tier),
+            None => (&self.client, None),
+        }
+    }
+
+    /// Like [`select_client`](Self::select_client), but never below the first fallback
+    /// tier; used once a response has come back empty with escalation enabled.
+    pub(crate) fn select_escalated_client(&self, attempt: usize) -> (&Arc<Gemini>, Option<usize>) {
+        match (self.select_client(attempt), &self.fallback_client) {
+            ((_, None), Some(fallback)) => (fallback, Some(0)),
+            (selected, _) => selected,
+        }
+    }
+
+    /// Name of the model serving escalation tier `tier`, for logging.
+    pub(crate) fn tier_model_name(&self, tier: usize) -> String {
+        self.fallback_strategy
+            .tiers()
+            .get(tier)
+            .map(|(_, model)| model.as_str().to_string())
+            .unwrap_or_default()
+    }
+
+    /// Gemini client bound to `model` that shares this client's credentials.
+    ///
+    /// Used for per-request model overrides. The primary and fallback-tier clients are
+    /// reused, and any other model's client is built once and shared by every clone.
+    pub(crate) fn client_for_model(&self, model: Model) -> Result<Arc<Gemini>> {
+        let mut clients = self
+            .model_clients
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+        if let Some(client) = clients.get(model.as_str()) {
+            return Ok(client.clone());
+        }
+        let name = model.as_str().to_string();
+        let client = Arc::new(Gemini::with_model(&self.api_key, model)?);
+        clients.insert(name, client.clone());
+        Ok(client)
+    }
+
     /// Start building a fluent structured request.
     pub fn request<T>(&self) -> StructuredRequest<'_, T>
     where
@@ -547,11 +1317,64 @@ impl StructuredClient {
             + Sync
             + 'static,
     {
-        StructuredRequest::new(self)
+        let mut request = StructuredRequest::new(self)
             .max_parse_attempts(self.config.default_parse_attempts)
             .max_tool_steps(self.config.default_tool_steps)
             .retries(self.config.default_retries)
-            .temperature(self.config.default_temperature)
+            .temperature(self.config.default_temperature);
+        if let Some(seed) = self.config.default_seed {
+            request = request.seed(seed);
+        }
+        match &self.config.default_locale {
+            Some(locale) => request.with_locale(locale),
+            None => request,
+        }
+    }
+
+    /// `system` as a request built by [`request`](Self::request) sends it, with the
+    /// default locale instruction appended.
+    pub(crate) fn request_system_instruction(&self, system: String) -> String {
+        match &self.config.default_locale {
+            Some(locale) => crate::request::localized_system_instruction(Some(system), locale),
+            None => system,
+        }
+    }
+
+    /// Eagerly create the cached content that requests for `T` with this system prompt
+    /// and tool set will use, avoiding cold-start latency on the first request.
+    ///
+    /// Returns when the cache entry expires so callers can re-warm shortly before the TTL
+    /// lapses, or `None` when caching is disabled or the prompt is too small to cache.
+    pub async fn warm_cache<T>(
+        &self,
+        system: impl Into<String>,
+        tools: &[Tool],
+    ) -> Result<Option<SystemTime>>
+    where
+        T: GeminiStructured,
+    {
+        let system_instruction = Some(self.request_system_instruction(system.into()));
+        let (_, expiry) = self
+            .configured_builder_with_cache_expiry::<T>(
+                &self.client,
+                &[],
+                BuilderOptions {
+                    tools,
+                    config: &GenerationConfig::default(),
+                    cache_settings: &None,
+                    system_instruction: &system_instruction,
+                    safety_settings: &None,
+                    force_prompt_schema: false,
+                    model: None,
+                    schema_transforms: &[],
+                },
+            )
+            .await?;
+
+        if let Some(expires_at) = expiry {
+            info!(?expires_at, "Warmed cached content");
+        }
+        Ok(expiry)
     }
 
     /// Generate structured data using a runtime-provided JSON Schema.
@@ -575,12 +1398,31 @@ impl StructuredClient {
                 .join("\n---\n");
             let request = MockRequest {
                 target: std::any::type_name::<serde_json::Value>().to_string(),
+                model: self.model.as_str().to_string(),
                 system_instruction: system_instruction.clone(),
                 prompt_preview: preview,
+                schema: Some(json_schema.clone()),
+                tools: Vec::new(),
             };
             let raw = (mock)(request)?;
-            return serde_json::from_str(&raw)
-                .map_err(|e| StructuredError::parse_error(e, &raw));
+            let value: serde_json::Value =
+                serde_json::from_str(&raw).map_err(|e| StructuredError::parse_error(e, &raw))?;
+            if self.validate_mocks {
+                let validator = jsonschema::validator_for(&json_schema).map_err(|e| {
+                    StructuredError::Validation(format!("Failed to compile schema: {e}"))
+                })?;
+                let errors: Vec<String> = validator
+                    .iter_errors(&value)
+                    .map(|err| format!("{}: {}", err.instance_path(), err))
+                    .collect();
+                if !errors.is_empty() {
+                    return Err(StructuredError::Validation(format!(
+                        "Mock response does not match the dynamic schema: {}",
+                        errors.join("; ")
+                    )));
+                }
+            }
+            return Ok(value);
         }
 
         let mut messages = Vec::new();
@@ -630,14 +1472,11 @@ impl StructuredClient {
                 schema.depth = schema_depth,
                 "Schema depth exceeds strict-mode limit; falling back to prompt-embedded schema"
             );
-            let schema_instruction = format!(
-                "You must output valid JSON matching this schema exactly:\n{}",
-                serde_json::to_string_pretty(&cleaned_schema).unwrap_or_default()
+            final_system_instruction = self.embed_prompt_schema(
+                final_system_instruction,
+                &cleaned_schema,
+                "depth fallback",
             );
-            final_system_instruction = Some(match final_system_instruction {
-                Some(existing) => format!("{}\n\n{}", existing, schema_instruction),
-                None => schema_instruction,
-            });
             generation_config.response_json_schema = None;
             generation_config.response_schema = None;
         } else {
@@ -688,12 +1527,14 @@ impl StructuredClient {
                 .join("\n---\n");
             let request = MockRequest {
                 target: std::any::type_name::<T>().to_string(),
+                model: self.model.as_str().to_string(),
                 system_instruction: system_instruction.clone(),
                 prompt_preview: preview,
+                schema: Some(T::gemini_schema()),
+                tools: tools.iter().flat_map(function_names).collect(),
             };
             let raw = (mock)(request)?;
-            let parsed: T =
-                serde_json::from_str(&raw).map_err(|e| StructuredError::parse_error(e, &raw))?;
+            let parsed: T = self.parse_mock_response(&raw, &HashMap::new(), false)?;
             return Ok(GenerationOutcome::new(
                 parsed,
                 None,
@@ -720,11 +1561,33 @@ impl StructuredClient {
                     system_instruction: &system_instruction,
                     safety_settings: &None,
                     force_prompt_schema: false,
+                    model: None,
+                    schema_transforms: &[],
                 },
             )
             .await?;
 
-        let response = builder.execute().await?;
+        let permit = self
+            .circuit_breaker
+            .as_ref()
+            .map(|cb| cb.acquire())
+            .transpose()?;
+        let response = match builder.execute().await {
+            Ok(response) => {
+                if let Some(permit) = permit {
+                    permit.record_success();
+                }
+                response
+            }
+            Err(e) => {
+                if let Some(permit) = permit {
+                    if crate::circuit::is_backend_failure(&e) {
+                        permit.record_failure();
+                    }
+                }
+                return Err(e.into());
+            }
+        };
         let duration = start_time.elapsed();
 
         // Log result details
@@ -773,6 +1636,53 @@ impl StructuredClient {
         ))
     }
 
+    /// Append the schema instruction to a system prompt, logging its approximate token cost.
+    ///
+    /// With `dedupe_prompt_schema`, a prompt that already carries this exact schema text
+    /// is left as is; a prompt embedding a different schema still gets this one.
+    fn embed_prompt_schema(
+        &self,
+        system_instruction: Option<String>,
+        schema: &serde_json::Value,
+        strategy: &str,
+    ) -> Option<String> {
+        let compact = serde_json::to_string(schema).unwrap_or_default();
+        let pretty = serde_json::to_string_pretty(schema).unwrap_or_default();
+
+        if self.config.dedupe_prompt_schema {
+            if let Some(existing) = &system_instruction {
+                if existing.contains(&compact) || existing.contains(&pretty) {
+                    debug!(
+                        strategy,
+                        "Schema already present in system prompt; not re-embedding"
+                    );
+                    return system_instruction;
+                }
+            }
+        }
+
+        let schema_text = if self.config.compact_prompt_schema {
+            compact
+        } else {
+            pretty
+        };
+        // Rough heuristic: ~4 characters per token.
+        info!(
+            strategy,
+            compact = self.config.compact_prompt_schema,
+            schema.chars = schema_text.len(),
+            schema.approx_tokens = schema_text.len() / 4,
+            "Embedding schema into system prompt"
+        );
+        trace!(schema = %schema_text, "Prompt-embedded schema");
+
+        let schema_instruction = format!("{PROMPT_SCHEMA_PREFIX}\n{schema_text}");
+        Some(match system_instruction {
+            Some(existing) => format!("{}\n\n{}", existing, schema_instruction),
+            None => schema_instruction,
+        })
+    }
+
     pub(crate) async fn configured_builder<T>(
         &self,
         messages: &[Message],
@@ -794,6 +1704,21 @@ impl StructuredClient {
         messages: &[Message],
         opts: BuilderOptions<'_>,
     ) -> Result<ContentBuilder>
+    where
+        T: GeminiStructured,
+    {
+        self.configured_builder_with_cache_expiry::<T>(client, messages, opts)
+            .await
+            .map(|(builder, _)| builder)
+    }
+
+    /// Configure a builder, also returning the expiry of the cached content it uses (if any).
+    async fn configured_builder_with_cache_expiry<T>(
+        &self,
+        client: &Arc<Gemini>,
+        messages: &[Message],
+        opts: BuilderOptions<'_>,
+    ) -> Result<(ContentBuilder, Option<SystemTime>)>
     where
         T: GeminiStructured,
     {
@@ -804,11 +1729,16 @@ impl StructuredClient {
             system_instruction,
             safety_settings,
             force_prompt_schema,
+            model,
+            schema_transforms,
         } = opts;
         let schema = T::gemini_schema();
 
         // Create a clean copy of the schema for Gemini (without x-* fields).
         let mut gemini_schema = schema.clone();
+        for transform in schema_transforms {
+            transform.apply(&mut gemini_schema);
+        }
         crate::schema::clean_schema_for_gemini(&mut gemini_schema);
         crate::schema::apply_map_schema_mode(
             &mut gemini_schema,
@@ -818,15 +1748,11 @@ impl StructuredClient {
 
         let mut config = config.clone();
         let has_tools = !tools.is_empty();
-        let model_str = self.model.as_str();
+        let model_str = model.unwrap_or(&self.model).as_str();
         let is_gemini_3 = model_str.contains("gemini-3") || model_str.contains("gemini-experiment");
 
         let mut final_system_instruction = system_instruction.clone();
 
-        // Log the schema that will be enforced for this request and how it is applied.
-        let schema_json = serde_json::to_string_pretty(&gemini_schema)
-            .unwrap_or_else(|_| "Unable to serialize schema".to_string());
-
         crate::schema::warn_if_schema_too_deep(&gemini_schema, STRICT_SCHEMA_DEPTH_LIMIT);
         let schema_depth = crate::schema::schema_depth(&gemini_schema);
         let schema_bytes = serde_json::to_string(&gemini_schema)
@@ -862,20 +1788,16 @@ impl StructuredClient {
             } else {
                 // Legacy models: inject schema into system prompt instead of forcing mime/schema in config.
                 debug!("Embedding schema into system prompt (tools enabled or depth fallback)");
-                info!("Embedding schema into system prompt (tools enabled legacy path):\n{schema_json}");
+                // The prompt copy is the only one the model sees; keep it out of the config.
                 config.response_json_schema = None;
                 config.response_schema = None;
                 config.response_mime_type = None;
 
-                let schema_instruction = format!(
-                    "You must output valid JSON matching this schema exactly:\n{}",
-                    serde_json::to_string_pretty(&gemini_schema).unwrap_or_default()
+                final_system_instruction = self.embed_prompt_schema(
+                    final_system_instruction,
+                    &gemini_schema,
+                    "legacy tools",
                 );
-
-                final_system_instruction = Some(match final_system_instruction {
-                    Some(existing) => format!("{}\n\n{}", existing, schema_instruction),
-                    None => schema_instruction,
-                });
             }
         } else {
             if use_prompt_schema {
@@ -886,14 +1808,11 @@ impl StructuredClient {
                     schema_depth,
                     STRICT_SCHEMA_DEPTH_LIMIT
                 );
-                let schema_instruction = format!(
-                    "You must output valid JSON matching this schema exactly:\n{}",
-                    serde_json::to_string_pretty(&gemini_schema).unwrap_or_default()
+                final_system_instruction = self.embed_prompt_schema(
+                    final_system_instruction,
+                    &gemini_schema,
+                    "depth fallback",
                 );
-                final_system_instruction = Some(match final_system_instruction {
-                    Some(existing) => format!("{}\n\n{}", existing, schema_instruction),
-                    None => schema_instruction,
-                });
                 config.response_json_schema = None;
                 config.response_schema = None;
             } else {
@@ -914,19 +1833,25 @@ impl StructuredClient {
             builder = builder.with_message(msg.clone());
         }
 
+        let mut cache_expiry = None;
         if let Some(system) = final_system_instruction {
             let cache_key = cache_settings
                 .as_ref()
                 .and_then(|c| c.key.clone())
                 .unwrap_or_else(|| SchemaCache::cache_key::<T>(&system, tools));
             let ttl_override = cache_settings.as_ref().and_then(|c| c.ttl_override);
+            if cache_settings.as_ref().is_some_and(|c| c.force_refresh) {
+                debug!(cache_key = %cache_key, "Forcing cached content refresh");
+                self.cache.invalidate(&cache_key).await;
+            }
 
-            if let Some(handle) = self
+            if let Some((handle, expires_at)) = self
                 .cache
-                .get_or_create(&cache_key, &system, tools, ttl_override)
+                .get_or_create_with_expiry(&cache_key, &system, tools, ttl_override)
                 .await?
             {
                 builder = builder.with_cached_content(&handle);
+                cache_expiry = Some(expires_at);
             } else {
                 builder = builder.with_system_instruction(system.clone());
             }
@@ -940,6 +1865,28 @@ impl StructuredClient {
             builder = builder.with_safety_settings(safety.clone());
         }
 
-        Ok(builder.with_generation_config(config))
+        Ok((builder.with_generation_config(config), cache_expiry))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use serde_json::json;
+
+    use super::*;
+
+    #[test]
+    fn prompt_schema_is_embedded_once_per_distinct_schema() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let first = json!({ "type": "object", "properties": { "a": { "type": "string" } } });
+        let second = json!({ "type": "object", "properties": { "b": { "type": "number" } } });
+
+        let once = client.embed_prompt_schema(Some("Be terse.".to_string()), &first, "test");
+        let again = client.embed_prompt_schema(once.clone(), &first, "test");
+        assert_eq!(again, once);
+
+        let both = client.embed_prompt_schema(once, &second, "test").unwrap();
+        assert_eq!(both.matches(PROMPT_SCHEMA_PREFIX).count(), 2);
+        assert!(both.contains(&serde_json::to_string_pretty(&second).unwrap()));
     }
 }
@@ -1,7 +1,10 @@
 use std::sync::Arc;
 
 use gemini_rust::{Content, FileHandle, Message, Part, Role};
+use serde::Serialize;
 
+#[cfg(feature = "helpers")]
+use crate::error::StructuredError;
 use crate::{error::Result, files::FileManager};
 
 /// Builder that assembles system instructions and conversation history.
@@ -41,6 +44,30 @@ impl ContextBuilder {
         self
     }
 
+    /// Add a user message containing `value` as pretty-printed JSON under a `### label` header.
+    pub fn add_labeled_json<T: Serialize>(mut self, label: &str, value: &T) -> Result<Self> {
+        let json = serde_json::to_string_pretty(value)?;
+        self.messages.push(Message::user(format!(
+            "### {}\n\n```json\n{}\n```",
+            label, json
+        )));
+        Ok(self)
+    }
+
+    /// Add a user message containing `value` rendered as a markdown table.
+    ///
+    /// Tables are considerably more token-efficient than JSON for tabular data. The value
+    /// must serialize to a non-empty array of objects; anything else is a context error.
+    #[cfg(feature = "helpers")]
+    pub fn add_labeled_table<T: Serialize>(mut self, label: &str, value: &T) -> Result<Self> {
+        let json = serde_json::to_value(value)?;
+        let table = crate::helpers::json_array_to_markdown(&json, Some(label)).map_err(|e| {
+            StructuredError::Context(format!("Cannot render '{}' as a table: {}", label, e))
+        })?;
+        self.messages.push(Message::user(table));
+        Ok(self)
+    }
+
     /// Add a user message that includes a file handle reference.
     pub fn add_file(mut self, handle: Arc<FileHandle>, text: Option<String>) -> Result<Self> {
         let mut parts = vec![];
@@ -88,3 +115,174 @@ impl ContextBuilder {
         (self.system_instruction, contents)
     }
 }
+
+/// Builder for system prompts assembled from reusable, labeled sections.
+///
+/// Sections render in a fixed order (role, constraints, examples, output rules, then
+/// custom sections in insertion order) so prompts share one structure across a codebase.
+/// Converts into `String`, so it can be passed straight to
+/// [`StructuredRequest::system`](crate::StructuredRequest::system) or
+/// [`ContextBuilder::with_system`].
+///
+/// ```rust
+/// use gemini_structured_output::SystemPrompt;
+///
+/// let prompt = SystemPrompt::new()
+///     .role("You are a meticulous financial analyst.")
+///     .constraint("Never invent figures that are not in the source data.")
+///     .output_rules("Report amounts in NZD.")
+///     .render();
+/// assert!(prompt.starts_with("## Role"));
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct SystemPrompt {
+    role: Option<String>,
+    constraints: Vec<String>,
+    examples: Vec<String>,
+    output_rules: Vec<String>,
+    sections: Vec<(String, String)>,
+}
+
+impl SystemPrompt {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the role/persona paragraph, replacing any previous one.
+    pub fn role(mut self, role: impl Into<String>) -> Self {
+        self.role = Some(role.into());
+        self
+    }
+
+    /// Add a constraint, rendered as a bullet.
+    pub fn constraint(mut self, constraint: impl Into<String>) -> Self {
+        self.constraints.push(constraint.into());
+        self
+    }
+
+    /// Add a worked example, rendered under a numbered heading.
+    pub fn example(mut self, example: impl Into<String>) -> Self {
+        self.examples.push(example.into());
+        self
+    }
+
+    /// Add an output formatting rule, rendered as a bullet.
+    pub fn output_rules(mut self, rule: impl Into<String>) -> Self {
+        self.output_rules.push(rule.into());
+        self
+    }
+
+    /// Add a custom section after the standard ones.
+    pub fn section(mut self, title: impl Into<String>, body: impl Into<String>) -> Self {
+        self.sections.push((title.into(), body.into()));
+        self
+    }
+
+    /// Render the prompt as markdown with `##` section headings.
+    pub fn render(&self) -> String {
+        let bullets = |items: &[String]| {
+            items
+                .iter()
+                .map(|item| format!("- {}", item.trim()))
+                .collect::<Vec<_>>()
+                .join("\n")
+        };
+
+        let mut blocks = Vec::new();
+        if let Some(role) = &self.role {
+            blocks.push(format!("## Role\n{}", role.trim()));
+        }
+        if !self.constraints.is_empty() {
+            blocks.push(format!("## Constraints\n{}", bullets(&self.constraints)));
+        }
+        if !self.examples.is_empty() {
+            let examples = self
+                .examples
+                .iter()
+                .enumerate()
+                .map(|(i, example)| format!("### Example {}\n{}", i + 1, example.trim()))
+                .collect::<Vec<_>>()
+                .join("\n\n");
+            blocks.push(format!("## Examples\n{}", examples));
+        }
+        if !self.output_rules.is_empty() {
+            blocks.push(format!("## Output Rules\n{}", bullets(&self.output_rules)));
+        }
+        for (title, body) in &self.sections {
+            blocks.push(format!("## {}\n{}", title, body.trim()));
+        }
+        blocks.join("\n\n")
+    }
+}
+
+impl std::fmt::Display for SystemPrompt {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.render())
+    }
+}
+
+impl From<SystemPrompt> for String {
+    fn from(prompt: SystemPrompt) -> Self {
+        prompt.render()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    /// Text of the single user message in `builder`.
+    fn only_text(builder: ContextBuilder) -> String {
+        let (_, contents) = builder.build();
+        assert_eq!(contents.len(), 1);
+        match contents[0].parts.as_deref() {
+            Some([Part::Text { text, .. }]) => text.clone(),
+            other => panic!("expected one text part, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn labeled_json_is_fenced_under_a_header() {
+        let builder = ContextBuilder::new()
+            .add_labeled_json("Budget", &json!({ "revenue": 120 }))
+            .unwrap();
+
+        assert_eq!(
+            only_text(builder),
+            "### Budget\n\n```json\n{\n  \"revenue\": 120\n}\n```"
+        );
+    }
+
+    #[cfg(feature = "helpers")]
+    #[test]
+    fn labeled_table_renders_rows_under_a_header() {
+        let rows = json!([
+            { "month": "Jan", "revenue": 120 },
+            { "month": "Feb", "revenue": 95 },
+        ]);
+        let builder = ContextBuilder::new()
+            .add_labeled_table("Actuals", &rows)
+            .unwrap();
+
+        let text = only_text(builder);
+        assert!(text.starts_with("### Actuals\n\n"));
+        let month_header = text.find("month").unwrap();
+        assert!(month_header < text.find("Jan").unwrap());
+        assert!(text.find("Jan").unwrap() < text.find("Feb").unwrap());
+    }
+
+    #[cfg(feature = "helpers")]
+    #[test]
+    fn labeled_table_rejects_non_array_values() {
+        let result = ContextBuilder::new().add_labeled_table("Totals", &json!({ "revenue": 120 }));
+
+        match result {
+            Err(StructuredError::Context(message)) => {
+                assert!(message.starts_with("Cannot render 'Totals' as a table"))
+            }
+            Err(other) => panic!("expected a context error, got {other:?}"),
+            Ok(_) => panic!("expected a context error"),
+        }
+    }
+}
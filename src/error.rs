@@ -46,15 +46,43 @@ pub enum StructuredError {
     #[error("Tool execution failed: {tool_name} - {message}")]
     ToolExecution { tool_name: String, message: String },
 
+    /// A tool handler did not finish within its configured timeout.
+    #[error("Tool '{tool_name}' timed out after {timeout:?}")]
+    ToolTimeout {
+        tool_name: String,
+        timeout: std::time::Duration,
+    },
+
+    /// A request did not finish within its
+    /// [`timeout`](crate::StructuredRequest::timeout), including retries and tool steps.
+    #[error("Request timed out after {elapsed:?}")]
+    Timeout { elapsed: std::time::Duration },
+
+    /// A request's cancellation token fired before it finished.
+    #[error("Request was cancelled")]
+    Cancelled,
+
     #[error("Configuration error: {0}")]
     Config(String),
 
     #[error("Rate limited: retry after {retry_after_secs} seconds")]
     RateLimited { retry_after_secs: u64 },
 
+    /// A long-window (e.g. daily) quota is exhausted.
+    ///
+    /// Unlike [`StructuredError::RateLimited`], retrying will not help until the quota resets.
+    #[error("Quota exhausted: {message}")]
+    QuotaExhausted { message: String },
+
     #[error("Service unavailable: {message}. Attempted {attempts} retries.")]
     ServiceUnavailable { message: String, attempts: usize },
 
+    /// The client's circuit breaker is open after repeated backend failures.
+    ///
+    /// No request was sent; retry after the cooldown elapses.
+    #[error("Circuit breaker open: backend failing, retry after {retry_after_secs} seconds")]
+    CircuitOpen { retry_after_secs: u64 },
+
     /// Workflow checkpoint triggered for human-in-the-loop processing.
     ///
     /// This error is intentionally raised by `CheckpointStep` to pause workflow
@@ -82,6 +110,71 @@ pub enum StructuredError {
 }
 
 impl StructuredError {
+    /// Copy of this error with the same variant, or `None` when it wraps a foreign error
+    /// that cannot be reproduced.
+    pub(crate) fn try_clone(&self) -> Option<Self> {
+        Some(match self {
+            Self::Gemini(gemini_rust::ClientError::BadResponse { code, description }) => {
+                Self::Gemini(gemini_rust::ClientError::BadResponse {
+                    code: *code,
+                    description: description.clone(),
+                })
+            }
+            Self::Gemini(_) | Self::Files(_) | Self::Cache(_) | Self::Json(_) | Self::Patch(_) => {
+                return None
+            }
+            Self::Io(err) => Self::Io(std::io::Error::new(err.kind(), err.to_string())),
+            Self::InvalidPatch(message) => Self::InvalidPatch(message.clone()),
+            Self::Schema(message) => Self::Schema(message.clone()),
+            Self::Validation(message) => Self::Validation(message.clone()),
+            Self::RefinementExhausted {
+                retries,
+                last_error,
+            } => Self::RefinementExhausted {
+                retries: *retries,
+                last_error: last_error.clone(),
+            },
+            Self::Context(message) => Self::Context(message.clone()),
+            Self::ParseWithContext {
+                message,
+                raw_text,
+                suggestion,
+            } => Self::ParseWithContext {
+                message: message.clone(),
+                raw_text: raw_text.clone(),
+                suggestion: suggestion.clone(),
+            },
+            Self::ToolExecution { tool_name, message } => Self::ToolExecution {
+                tool_name: tool_name.clone(),
+                message: message.clone(),
+            },
+            Self::ToolTimeout { tool_name, timeout } => Self::ToolTimeout {
+                tool_name: tool_name.clone(),
+                timeout: *timeout,
+            },
+            Self::Timeout { elapsed } => Self::Timeout { elapsed: *elapsed },
+            Self::Cancelled => Self::Cancelled,
+            Self::Config(message) => Self::Config(message.clone()),
+            Self::RateLimited { retry_after_secs } => Self::RateLimited {
+                retry_after_secs: *retry_after_secs,
+            },
+            Self::QuotaExhausted { message } => Self::QuotaExhausted {
+                message: message.clone(),
+            },
+            Self::ServiceUnavailable { message, attempts } => Self::ServiceUnavailable {
+                message: message.clone(),
+                attempts: *attempts,
+            },
+            Self::CircuitOpen { retry_after_secs } => Self::CircuitOpen {
+                retry_after_secs: *retry_after_secs,
+            },
+            Self::Checkpoint { step_name, data } => Self::Checkpoint {
+                step_name: step_name.clone(),
+                data: data.clone(),
+            },
+        })
+    }
+
     /// Create a parse error with helpful context.
     pub fn parse_error(err: serde_json::Error, raw_text: &str) -> Self {
         let suggestion = Self::suggest_parse_fix(&err, raw_text);
@@ -100,17 +193,43 @@ impl StructuredError {
         }
     }
 
+    /// Convert a client error, classifying exhausted quotas as [`StructuredError::QuotaExhausted`].
+    pub fn from_client_error(err: gemini_rust::ClientError) -> Self {
+        match &err {
+            gemini_rust::ClientError::BadResponse {
+                code: 429,
+                description: Some(description),
+            } if is_quota_exhaustion(description) => Self::QuotaExhausted {
+                message: quota_message(description),
+            },
+            _ => Self::Gemini(err),
+        }
+    }
+
     /// Check if this error is retryable.
     pub fn is_retryable(&self) -> bool {
         match self {
             Self::RateLimited { .. } | Self::ServiceUnavailable { .. } => true,
-            Self::Gemini(gemini_rust::ClientError::BadResponse { code, .. }) => {
-                *code == 503 || *code == 429
+            Self::Gemini(gemini_rust::ClientError::BadResponse { code, description }) => {
+                *code == 503
+                    || (*code == 429 && !description.as_deref().is_some_and(is_quota_exhaustion))
             }
             _ => false,
         }
     }
 
+    /// Check if this error is an exhausted (non-recovering) quota.
+    pub fn is_quota_exhausted(&self) -> bool {
+        match self {
+            Self::QuotaExhausted { .. } => true,
+            Self::Gemini(gemini_rust::ClientError::BadResponse {
+                code: 429,
+                description: Some(description),
+            }) => is_quota_exhaustion(description),
+            _ => false,
+        }
+    }
+
     /// Check if this error is a workflow checkpoint.
     ///
     /// Checkpoints are intentional pauses for human-in-the-loop processing
@@ -133,6 +252,7 @@ impl StructuredError {
     pub fn retry_delay(&self) -> Option<u64> {
         match self {
             Self::RateLimited { retry_after_secs } => Some(*retry_after_secs),
+            Self::CircuitOpen { retry_after_secs } => Some(*retry_after_secs),
             Self::ServiceUnavailable { .. } => Some(5),
             Self::Gemini(gemini_rust::ClientError::BadResponse {
                 code: 429,
@@ -145,6 +265,52 @@ impl StructuredError {
     }
 }
 
+/// Whether a 429 body describes an exhausted long-window quota rather than a rate limit.
+///
+/// Both arrive as `RESOURCE_EXHAUSTED`; the `QuotaFailure` details name the violated
+/// quota, e.g. `GenerateRequestsPerDayPerProjectPerModel` vs `...PerMinute...`.
+pub(crate) fn is_quota_exhaustion(description: &str) -> bool {
+    if let Ok(json) = serde_json::from_str::<serde_json::Value>(description) {
+        let details = json
+            .get("error")
+            .and_then(|e| e.get("details"))
+            .and_then(|d| d.as_array());
+        if let Some(details) = details {
+            let quota_ids: Vec<String> = details
+                .iter()
+                .filter(|detail| {
+                    detail.get("@type").and_then(|t| t.as_str())
+                        == Some("type.googleapis.com/google.rpc.QuotaFailure")
+                })
+                .filter_map(|detail| detail.get("violations").and_then(|v| v.as_array()))
+                .flatten()
+                .filter_map(|violation| violation.get("quotaId").and_then(|q| q.as_str()))
+                .map(|id| id.to_lowercase())
+                .collect();
+            if !quota_ids.is_empty() {
+                return quota_ids.iter().any(|id| id.contains("perday"));
+            }
+        }
+    }
+
+    // Fallback: heuristic text search on the message.
+    let lower = description.to_lowercase();
+    lower.contains("perday") || lower.contains("per day") || lower.contains("daily quota")
+}
+
+/// Human-readable message from an error body, falling back to the raw description.
+fn quota_message(description: &str) -> String {
+    serde_json::from_str::<serde_json::Value>(description)
+        .ok()
+        .and_then(|json| {
+            json.get("error")
+                .and_then(|e| e.get("message"))
+                .and_then(|m| m.as_str())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| description.to_string())
+}
+
 /// Parse retry delay from Gemini API error response body.
 fn parse_retry_delay_from_error(description: &str) -> Option<u64> {
     // 1. Try strict JSON parsing first (most reliable)
@@ -270,6 +436,20 @@ pub type Result<T> = std::result::Result<T, StructuredError>;
 pub trait ResultExt<T> {
     /// Add context to an error.
     fn with_context(self, context: impl Into<String>) -> Result<T>;
+
+    /// Convert the error, if any, into a [`StructuredError`], log it at `warn` level and
+    /// return it; `Ok` values pass through.
+    ///
+    /// `target` is recorded as a `target` field on the event, since tracing targets
+    /// must be compile-time constants.
+    ///
+    /// ```rust,ignore
+    /// let summary = summarize(doc).await.log_err("pipeline::summary").ok();
+    /// ```
+    fn log_err(self, target: &str) -> Result<T>;
+
+    /// Like [`log_err`](Self::log_err), prefixing the logged message with `context`.
+    fn log_err_with(self, target: &str, context: impl std::fmt::Display) -> Result<T>;
 }
 
 impl<T, E: Into<StructuredError>> ResultExt<T> for std::result::Result<T, E> {
@@ -279,4 +459,98 @@ impl<T, E: Into<StructuredError>> ResultExt<T> for std::result::Result<T, E> {
             StructuredError::Context(format!("{}: {}", context.into(), base_err))
         })
     }
+
+    fn log_err(self, target: &str) -> Result<T> {
+        self.map_err(|e| {
+            let err = e.into();
+            tracing::warn!(target = target, error = %err, "Operation failed");
+            err
+        })
+    }
+
+    fn log_err_with(self, target: &str, context: impl std::fmt::Display) -> Result<T> {
+        self.map_err(|e| {
+            let err = e.into();
+            tracing::warn!(target = target, error = %err, "{context}");
+            err
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn too_many_requests(quota_id: &str) -> gemini_rust::ClientError {
+        let body = serde_json::json!({
+            "error": {
+                "code": 429,
+                "message": "You exceeded your current quota, please check your plan and billing details.",
+                "status": "RESOURCE_EXHAUSTED",
+                "details": [
+                    {
+                        "@type": "type.googleapis.com/google.rpc.QuotaFailure",
+                        "violations": [{
+                            "quotaMetric": "generativelanguage.googleapis.com/generate_content_free_tier_requests",
+                            "quotaId": quota_id
+                        }]
+                    },
+                    {
+                        "@type": "type.googleapis.com/google.rpc.RetryInfo",
+                        "retryDelay": "57s"
+                    }
+                ]
+            }
+        });
+        gemini_rust::ClientError::BadResponse {
+            code: 429,
+            description: Some(body.to_string()),
+        }
+    }
+
+    #[test]
+    fn per_minute_limit_is_retryable() {
+        let err = StructuredError::from_client_error(too_many_requests(
+            "GenerateRequestsPerMinutePerProjectPerModel-FreeTier",
+        ));
+
+        assert!(matches!(err, StructuredError::Gemini(_)));
+        assert!(err.is_retryable());
+        assert!(!err.is_quota_exhausted());
+        assert_eq!(err.retry_delay(), Some(57));
+    }
+
+    #[test]
+    fn daily_quota_fails_fast() {
+        let err = StructuredError::from_client_error(too_many_requests(
+            "GenerateRequestsPerDayPerProjectPerModel-FreeTier",
+        ));
+
+        match &err {
+            StructuredError::QuotaExhausted { message } => {
+                assert!(message.starts_with("You exceeded your current quota"));
+            }
+            other => panic!("expected quota exhaustion, got {other:?}"),
+        }
+        assert!(!err.is_retryable());
+        assert!(err.is_quota_exhausted());
+
+        let raw = StructuredError::Gemini(too_many_requests("GenerateRequestsPerDayPerProject"));
+        assert!(!raw.is_retryable());
+    }
+
+    #[test]
+    fn log_err_returns_result_unchanged() {
+        let ok: Result<u8> = Ok(7);
+        assert_eq!(ok.log_err("tests").unwrap(), 7);
+
+        let err: Result<u8> = Err(StructuredError::Context("boom".into()));
+        let err = err
+            .log_err_with("tests", "while loading config")
+            .unwrap_err();
+        assert!(matches!(err, StructuredError::Context(ref msg) if msg == "boom"));
+
+        let foreign = serde_json::from_str::<u8>("nope").log_err("tests");
+        assert!(matches!(foreign, Err(StructuredError::Json(_))));
+    }
 }
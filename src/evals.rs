@@ -1,30 +1,50 @@
 use std::fmt;
 use std::future::Future;
+use std::path::Path;
 use std::sync::Arc;
 use std::time::{Duration, Instant};
 
 use schemars::JsonSchema;
-use serde::{Deserialize, Serialize};
+use serde::{de::DeserializeOwned, Deserialize, Serialize, Serializer};
+use serde_json::Value;
 use tokio::sync::{Mutex, Semaphore};
 
 use crate::{
-    models::GenerationOutcome, schema::GeminiStructured, StructuredClient, StructuredError,
+    models::GenerationOutcome,
+    schema::{diff_report, GeminiStructured},
+    StructuredClient, StructuredError,
 };
 
 /// A single evaluation result for a test case.
-#[derive(Debug, Clone)]
+///
+/// Serializes with stable field names; `latency` is written as integer `latency_ms`.
+#[derive(Debug, Clone, Serialize)]
 pub struct EvalResult {
+    /// Name of the case, as passed to the suite.
     pub case_name: String,
+    /// Whether the case passed.
     pub passed: bool,
+    /// Score between 0.0 and 1.0, when the evaluator produced one.
     pub score: Option<f64>,
+    /// Wall-clock time for the case.
+    #[serde(rename = "latency_ms", serialize_with = "serialize_millis")]
     pub latency: Duration,
+    /// Prompt tokens reported by the API.
     pub prompt_tokens: usize,
+    /// Response tokens reported by the API.
     pub response_tokens: usize,
+    /// Network attempts, including retries.
     pub network_attempts: usize,
+    /// Parse attempts, including repair passes.
     pub parse_attempts: usize,
+    /// Failure reason for failed cases.
     pub error: Option<String>,
 }
 
+fn serialize_millis<S: Serializer>(duration: &Duration, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_u64(duration.as_millis() as u64)
+}
+
 impl EvalResult {
     pub fn fail(name: impl Into<String>, error: impl Into<String>) -> Self {
         Self {
@@ -48,10 +68,39 @@ pub struct EvalSuite {
 }
 
 /// Normalized return type for evaluator closures.
+///
+/// Build one with [`new`](Self::new) or from a tuple; new fields may be added, so
+/// struct literals are not supported outside this crate.
+#[non_exhaustive]
 pub struct EvaluatorOutcome<T> {
     pub outcome: GenerationOutcome<T>,
     pub passed: bool,
     pub message: Option<String>,
+    /// Explicit score; defaults to 1.0/0.0 from `passed` when absent.
+    pub score: Option<f64>,
+}
+
+impl<T> EvaluatorOutcome<T> {
+    pub fn new(outcome: GenerationOutcome<T>, passed: bool) -> Self {
+        Self {
+            outcome,
+            passed,
+            message: None,
+            score: None,
+        }
+    }
+
+    /// Failure message reported when the case does not pass.
+    pub fn with_message(mut self, message: impl Into<String>) -> Self {
+        self.message = Some(message.into());
+        self
+    }
+
+    /// Explicit score between 0.0 and 1.0.
+    pub fn with_score(mut self, score: f64) -> Self {
+        self.score = Some(score);
+        self
+    }
 }
 
 impl<T> From<(GenerationOutcome<T>, bool)> for EvaluatorOutcome<T> {
@@ -60,6 +109,7 @@ impl<T> From<(GenerationOutcome<T>, bool)> for EvaluatorOutcome<T> {
             outcome: value.0,
             passed: value.1,
             message: None,
+            score: None,
         }
     }
 }
@@ -70,6 +120,7 @@ impl<T> From<(GenerationOutcome<T>, bool, String)> for EvaluatorOutcome<T> {
             outcome: value.0,
             passed: value.1,
             message: Some(value.2),
+            score: None,
         }
     }
 }
@@ -80,6 +131,7 @@ impl<T> From<(GenerationOutcome<T>, bool, Option<String>)> for EvaluatorOutcome<
             outcome: value.0,
             passed: value.1,
             message: value.2,
+            score: None,
         }
     }
 }
@@ -102,6 +154,83 @@ impl EvalSuite {
     /// The `evaluator` function receives the input and should return either a `(GenerationOutcome<T>, bool)`
     /// tuple or an `(GenerationOutcome<T>, bool, Option<String>)` tuple for an optional failure message.
     pub async fn run<I, T, F, Fut, E>(&self, cases: Vec<(String, I)>, evaluator: F) -> SuiteReport
+    where
+        I: Send + Sync + 'static,
+        T: GeminiStructured + Send + Sync,
+        F: Fn(I) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<E, StructuredError>> + Send,
+        E: Into<EvaluatorOutcome<T>>,
+    {
+        self.run_inner(cases, evaluator, None).await.0
+    }
+
+    /// Run cases read from a JSON Lines golden file.
+    ///
+    /// Each non-blank line is an object with a `name` and an `input` field, the latter
+    /// deserialized into `I`:
+    ///
+    /// ```text
+    /// {"name": "simple invoice", "input": {"text": "Invoice #1 for $20"}}
+    /// ```
+    ///
+    /// Lines that fail to parse are reported as failed cases named `line N`, with the
+    /// parse error; the remaining cases run under the suite's concurrency limit. Only
+    /// failing to read the file is returned as an error.
+    pub async fn run_from_jsonl<I, T, F, Fut, E>(
+        &self,
+        path: impl AsRef<Path>,
+        evaluator: F,
+    ) -> crate::Result<SuiteReport>
+    where
+        I: DeserializeOwned + Send + Sync + 'static,
+        T: GeminiStructured + Send + Sync,
+        F: Fn(I) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<E, StructuredError>> + Send,
+        E: Into<EvaluatorOutcome<T>>,
+    {
+        let text = tokio::fs::read_to_string(path.as_ref()).await?;
+        let (cases, line_failures) = parse_jsonl_cases::<I>(&text);
+
+        let report = self.run_inner(cases, evaluator, None).await.0;
+        if line_failures.is_empty() {
+            return Ok(report);
+        }
+        let mut results = report.results;
+        results.extend(line_failures);
+        Ok(SuiteReport::new(self.name.clone(), results))
+    }
+
+    /// Like [`run`](Self::run), but also return each case's output serialized to JSON.
+    ///
+    /// Outputs are returned in case order, with the evaluator's error for cases that did
+    /// not produce an outcome, ready for offline review or export to JSONL. Every output
+    /// is held in memory until the suite finishes, so prefer `run` for large suites.
+    pub async fn run_capturing<I, T, F, Fut, E>(
+        &self,
+        cases: Vec<(String, I)>,
+        evaluator: F,
+    ) -> (SuiteReport, Vec<(String, Result<Value, StructuredError>)>)
+    where
+        I: Send + Sync + 'static,
+        T: GeminiStructured + Serialize + Send + Sync,
+        F: Fn(I) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<E, StructuredError>> + Send,
+        E: Into<EvaluatorOutcome<T>>,
+    {
+        self.run_inner(
+            cases,
+            evaluator,
+            Some(|value: &T| serde_json::to_value(value)),
+        )
+        .await
+    }
+
+    async fn run_inner<I, T, F, Fut, E>(
+        &self,
+        cases: Vec<(String, I)>,
+        evaluator: F,
+        capture: Option<fn(&T) -> serde_json::Result<Value>>,
+    ) -> (SuiteReport, Vec<(String, Result<Value, StructuredError>)>)
     where
         I: Send + Sync + 'static,
         T: GeminiStructured + Send + Sync,
@@ -110,6 +239,7 @@ impl EvalSuite {
         E: Into<EvaluatorOutcome<T>>,
     {
         let results = Arc::new(Mutex::new(Vec::new()));
+        let captured = Arc::new(Mutex::new(Vec::new()));
         let semaphore = Arc::new(Semaphore::new(self.concurrency));
         let mut handles = Vec::new();
 
@@ -120,23 +250,29 @@ impl EvalSuite {
             self.concurrency
         );
 
-        for (name, input) in cases {
+        for (index, (name, input)) in cases.into_iter().enumerate() {
             let eval_fn = evaluator.clone();
             let results = Arc::clone(&results);
+            let captured = Arc::clone(&captured);
             let semaphore = Arc::clone(&semaphore);
 
             handles.push(tokio::spawn(async move {
                 let _permit = semaphore.acquire().await.unwrap();
                 let start = Instant::now();
 
+                let mut output = None;
                 let eval_res = match eval_fn(input).await {
                     Ok(raw_outcome) => {
                         let EvaluatorOutcome {
                             outcome,
                             passed,
                             message,
+                            score,
                         } = raw_outcome.into();
                         let latency = start.elapsed();
+                        output = capture.map(|to_value| {
+                            to_value(&outcome.value).map_err(StructuredError::from)
+                        });
                         let usage = outcome.usage.as_ref();
                         let error = if passed {
                             None
@@ -151,7 +287,7 @@ impl EvalSuite {
                         EvalResult {
                             case_name: name.clone(),
                             passed,
-                            score: Some(if passed { 1.0 } else { 0.0 }),
+                            score: Some(score.unwrap_or(if passed { 1.0 } else { 0.0 })),
                             latency,
                             prompt_tokens: usage.and_then(|u| u.prompt_token_count).unwrap_or(0)
                                 as usize,
@@ -163,7 +299,13 @@ impl EvalSuite {
                             error,
                         }
                     }
-                    Err(e) => EvalResult::fail(name.clone(), format!("{e:?}")),
+                    Err(e) => {
+                        let failed = EvalResult::fail(name.clone(), format!("{e:?}"));
+                        if capture.is_some() {
+                            output = Some(Err(e));
+                        }
+                        failed
+                    }
                 };
 
                 if eval_res.passed {
@@ -175,6 +317,9 @@ impl EvalSuite {
                 let _ = std::io::stdout().flush();
 
                 results.lock().await.push(eval_res);
+                if let Some(output) = output {
+                    captured.lock().await.push((index, name, output));
+                }
             }));
         }
 
@@ -184,12 +329,133 @@ impl EvalSuite {
         println!("\nDone.");
 
         let final_results = results.lock().await.clone();
-        SuiteReport::new(self.name.clone(), final_results)
+        let mut outputs = std::mem::take(&mut *captured.lock().await);
+        outputs.sort_by_key(|(index, _, _)| *index);
+        (
+            SuiteReport::new(self.name.clone(), final_results),
+            outputs
+                .into_iter()
+                .map(|(_, name, output)| (name, output))
+                .collect(),
+        )
+    }
+
+    /// Run cases with a reference value and pass only on an exact match.
+    ///
+    /// `generate_fn` produces the outcome for each input; mismatches are reported as a
+    /// field-level diff against the expected value.
+    pub async fn run_exact<I, T, F, Fut>(
+        &self,
+        cases: Vec<(String, I, T)>,
+        generate_fn: F,
+    ) -> SuiteReport
+    where
+        I: Send + Sync + 'static,
+        T: GeminiStructured + PartialEq + Serialize + Send + Sync + 'static,
+        F: Fn(I) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<GenerationOutcome<T>, StructuredError>> + Send,
+    {
+        self.run_with_scorer(
+            cases,
+            generate_fn,
+            |expected: &T, actual: &T| if expected == actual { 1.0 } else { 0.0 },
+            1.0,
+        )
+        .await
+    }
+
+    /// Run cases with a reference value and a custom similarity score.
+    ///
+    /// `scorer(expected, actual)` should return a score in `0.0..=1.0`; cases pass when it
+    /// reaches `pass_threshold`. Failures include a field-level diff against the expected value.
+    pub async fn run_with_scorer<I, T, F, Fut, S>(
+        &self,
+        cases: Vec<(String, I, T)>,
+        generate_fn: F,
+        scorer: S,
+        pass_threshold: f64,
+    ) -> SuiteReport
+    where
+        I: Send + Sync + 'static,
+        T: GeminiStructured + Serialize + Send + Sync + 'static,
+        F: Fn(I) -> Fut + Send + Sync + Clone + 'static,
+        Fut: Future<Output = Result<GenerationOutcome<T>, StructuredError>> + Send,
+        S: Fn(&T, &T) -> f64 + Send + Sync + 'static,
+    {
+        let scorer = Arc::new(scorer);
+        let cases = cases
+            .into_iter()
+            .map(|(name, input, expected)| (name, (input, expected)))
+            .collect();
+
+        self.run(cases, move |(input, expected): (I, T)| {
+            let generate_fn = generate_fn.clone();
+            let scorer = Arc::clone(&scorer);
+            async move {
+                let outcome = generate_fn(input).await?;
+                let score = scorer(&expected, &outcome.value);
+                let passed = score >= pass_threshold;
+                let message = (!passed).then(|| mismatch_message(&expected, &outcome.value, score));
+                Ok::<_, StructuredError>(EvaluatorOutcome {
+                    outcome,
+                    passed,
+                    message,
+                    score: Some(score),
+                })
+            }
+        })
+        .await
+    }
+}
+
+fn mismatch_message<T: Serialize>(expected: &T, actual: &T, score: f64) -> String {
+    let diff = match diff_report(expected, actual) {
+        Ok(changes) if changes.is_empty() => "no field differences".to_string(),
+        Ok(changes) => changes
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; "),
+        Err(err) => format!("diff unavailable: {err}"),
+    };
+    format!("score {score:.2} (expected -> actual): {diff}")
+}
+
+/// One line of a golden file read by [`EvalSuite::run_from_jsonl`].
+#[derive(Deserialize)]
+struct JsonlCase<I> {
+    name: String,
+    input: I,
+}
+
+/// Split a JSONL golden file into runnable cases and failures for unparseable lines.
+fn parse_jsonl_cases<I: DeserializeOwned>(text: &str) -> (Vec<(String, I)>, Vec<EvalResult>) {
+    let mut cases = Vec::new();
+    let mut failures = Vec::new();
+    for (index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        match serde_json::from_str::<JsonlCase<I>>(line) {
+            Ok(case) => cases.push((case.name, case.input)),
+            Err(err) => {
+                let line_number = index + 1;
+                failures.push(EvalResult::fail(
+                    format!("line {line_number}"),
+                    format!("Invalid eval case on line {line_number}: {err}"),
+                ));
+            }
+        }
     }
+    (cases, failures)
 }
 
 /// Aggregated report of the suite execution.
-#[derive(Debug, Clone)]
+///
+/// Use [`to_json`](Self::to_json) or [`to_csv`](Self::to_csv) for machine-readable
+/// output; the field names of this struct and [`EvalResult`] are the stable JSON keys.
+#[derive(Debug, Clone, Serialize)]
 pub struct SuiteReport {
     pub suite_name: String,
     pub total_cases: usize,
@@ -261,6 +527,255 @@ impl SuiteReport {
     }
 }
 
+impl SuiteReport {
+    /// Column header written by [`to_csv`](Self::to_csv).
+    pub const CSV_HEADER: &'static str = "case_name,passed,score,latency_ms,prompt_tokens,\
+        response_tokens,network_attempts,parse_attempts,error";
+
+    /// The full report, including every case, as JSON.
+    pub fn to_json(&self) -> Value {
+        serde_json::to_value(self).unwrap_or(Value::Null)
+    }
+
+    /// One CSV row per case under [`CSV_HEADER`](Self::CSV_HEADER).
+    ///
+    /// Empty `score` and `error` cells mean the value was absent. Text cells are quoted
+    /// when they contain commas, quotes or newlines.
+    pub fn to_csv(&self) -> String {
+        let mut out = String::from(Self::CSV_HEADER);
+        out.push('\n');
+        for r in &self.results {
+            let score = r.score.map(|s| s.to_string()).unwrap_or_default();
+            let error = r.error.as_deref().map(csv_field).unwrap_or_default();
+            out.push_str(&format!(
+                "{},{},{},{},{},{},{},{},{}\n",
+                csv_field(&r.case_name),
+                r.passed,
+                score,
+                r.latency.as_millis(),
+                r.prompt_tokens,
+                r.response_tokens,
+                r.network_attempts,
+                r.parse_attempts,
+                error
+            ));
+        }
+        out
+    }
+}
+
+impl SuiteReport {
+    /// Fraction of cases that passed, between 0.0 and 1.0.
+    pub fn pass_rate(&self) -> f64 {
+        if self.total_cases == 0 {
+            0.0
+        } else {
+            self.passed as f64 / self.total_cases as f64
+        }
+    }
+
+    /// Compare this run against `baseline`, matching cases by `case_name`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let diff = report.compare(&baseline);
+    /// println!("{diff}");
+    /// if diff.has_regressions() {
+    ///     std::process::exit(1);
+    /// }
+    /// ```
+    pub fn compare(&self, baseline: &SuiteReport) -> SuiteDiff {
+        let mut cases: Vec<CaseDiff> = self
+            .results
+            .iter()
+            .map(|current| {
+                let previous = baseline
+                    .results
+                    .iter()
+                    .find(|r| r.case_name == current.case_name);
+                let transition = match previous {
+                    None => CaseTransition::Added,
+                    Some(prev) => match (prev.passed, current.passed) {
+                        (true, false) => CaseTransition::Regressed,
+                        (false, true) => CaseTransition::Fixed,
+                        (true, true) => CaseTransition::StillPassing,
+                        (false, false) => CaseTransition::StillFailing,
+                    },
+                };
+                CaseDiff {
+                    case_name: current.case_name.clone(),
+                    transition,
+                    latency_delta_ms: previous.map(|prev| {
+                        current.latency.as_millis() as i64 - prev.latency.as_millis() as i64
+                    }),
+                    token_delta: previous.map(|prev| {
+                        (current.prompt_tokens + current.response_tokens) as i64
+                            - (prev.prompt_tokens + prev.response_tokens) as i64
+                    }),
+                    error: current.error.clone(),
+                }
+            })
+            .collect();
+
+        cases.extend(
+            baseline
+                .results
+                .iter()
+                .filter(|prev| !self.results.iter().any(|r| r.case_name == prev.case_name))
+                .map(|prev| CaseDiff {
+                    case_name: prev.case_name.clone(),
+                    transition: CaseTransition::Removed,
+                    latency_delta_ms: None,
+                    token_delta: None,
+                    error: None,
+                }),
+        );
+        cases.sort_by(|a, b| a.case_name.cmp(&b.case_name));
+
+        SuiteDiff {
+            suite_name: self.suite_name.clone(),
+            baseline_pass_rate: baseline.pass_rate(),
+            pass_rate: self.pass_rate(),
+            avg_latency_delta_ms: self.avg_latency_ms as i64 - baseline.avg_latency_ms as i64,
+            avg_prompt_tokens_delta: self.avg_prompt_tokens - baseline.avg_prompt_tokens,
+            avg_response_tokens_delta: self.avg_response_tokens - baseline.avg_response_tokens,
+            cases,
+        }
+    }
+}
+
+/// How a case's outcome changed between a baseline run and the current run.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum CaseTransition {
+    /// Passed in the baseline, fails now.
+    Regressed,
+    /// Failed in the baseline, passes now.
+    Fixed,
+    /// Passed in both runs.
+    StillPassing,
+    /// Failed in both runs.
+    StillFailing,
+    /// Only present in the current run.
+    Added,
+    /// Only present in the baseline.
+    Removed,
+}
+
+/// Per-case comparison produced by [`SuiteReport::compare`].
+#[derive(Debug, Clone, Serialize)]
+pub struct CaseDiff {
+    pub case_name: String,
+    pub transition: CaseTransition,
+    /// Current minus baseline latency; `None` unless the case is in both runs.
+    pub latency_delta_ms: Option<i64>,
+    /// Current minus baseline prompt + response tokens; `None` unless the case is in both runs.
+    pub token_delta: Option<i64>,
+    /// Current failure reason, if the case fails now.
+    pub error: Option<String>,
+}
+
+/// Regression report between two suite runs, produced by [`SuiteReport::compare`].
+///
+/// Deltas are current minus baseline, so a positive latency delta is slower.
+#[derive(Debug, Clone, Serialize)]
+pub struct SuiteDiff {
+    pub suite_name: String,
+    pub baseline_pass_rate: f64,
+    pub pass_rate: f64,
+    pub avg_latency_delta_ms: i64,
+    pub avg_prompt_tokens_delta: f64,
+    pub avg_response_tokens_delta: f64,
+    /// Every case from either run, sorted by name.
+    pub cases: Vec<CaseDiff>,
+}
+
+impl SuiteDiff {
+    /// Change in pass rate (current minus baseline).
+    pub fn pass_rate_delta(&self) -> f64 {
+        self.pass_rate - self.baseline_pass_rate
+    }
+
+    /// Cases that passed in the baseline and fail now.
+    pub fn regressions(&self) -> impl Iterator<Item = &CaseDiff> {
+        self.cases_with(CaseTransition::Regressed)
+    }
+
+    /// Cases that failed in the baseline and pass now.
+    pub fn fixes(&self) -> impl Iterator<Item = &CaseDiff> {
+        self.cases_with(CaseTransition::Fixed)
+    }
+
+    /// Whether any case regressed; useful as a CI gate.
+    pub fn has_regressions(&self) -> bool {
+        self.regressions().next().is_some()
+    }
+
+    fn cases_with(&self, transition: CaseTransition) -> impl Iterator<Item = &CaseDiff> {
+        self.cases
+            .iter()
+            .filter(move |case| case.transition == transition)
+    }
+}
+
+impl fmt::Display for SuiteDiff {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "\n=== Regression Report: {} ===", self.suite_name)?;
+        writeln!(
+            f,
+            "Pass rate: {:.1}% -> {:.1}% ({:+.1} pts)",
+            self.baseline_pass_rate * 100.0,
+            self.pass_rate * 100.0,
+            self.pass_rate_delta() * 100.0
+        )?;
+        writeln!(
+            f,
+            "Avg latency: {:+}ms | Avg tokens: Prompt {:+.0} | Response {:+.0}",
+            self.avg_latency_delta_ms, self.avg_prompt_tokens_delta, self.avg_response_tokens_delta
+        )?;
+
+        let regressions: Vec<&CaseDiff> = self.regressions().collect();
+        if regressions.is_empty() {
+            writeln!(f, "No regressions.")?;
+        } else {
+            writeln!(f, "\n!!! REGRESSIONS ({}) !!!", regressions.len())?;
+            for case in regressions {
+                writeln!(
+                    f,
+                    "[{}] PASS -> FAIL: {}",
+                    case.case_name,
+                    case.error.as_deref().unwrap_or("Unknown")
+                )?;
+            }
+        }
+
+        let fixes: Vec<&CaseDiff> = self.fixes().collect();
+        if !fixes.is_empty() {
+            writeln!(f, "\n--- Fixed ({}) ---", fixes.len())?;
+            for case in fixes {
+                writeln!(f, "[{}] FAIL -> PASS", case.case_name)?;
+            }
+        }
+
+        for case in &self.cases {
+            match case.transition {
+                CaseTransition::Added => writeln!(f, "[{}] new case", case.case_name)?,
+                CaseTransition::Removed => writeln!(f, "[{}] removed", case.case_name)?,
+                _ => {}
+            }
+        }
+        Ok(())
+    }
+}
+
+fn csv_field(text: &str) -> String {
+    if text.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", text.replace('"', "\"\""))
+    } else {
+        text.to_string()
+    }
+}
+
 impl fmt::Display for SuiteReport {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         writeln!(f, "\n=== Benchmark Report: {} ===", self.suite_name)?;
@@ -315,11 +830,73 @@ pub struct EvaluationVerdict {
     pub reasoning: String,
 }
 
+/// Aggregate of several judge runs, produced by [`LLMJudge::evaluate_ensemble`].
+#[derive(Debug, Clone, Serialize)]
+pub struct EnsembleVerdict {
+    /// Mean score, majority-vote pass flag and the majority's reasoning.
+    pub verdict: EvaluationVerdict,
+    /// Every successful judge run, in call order.
+    pub verdicts: Vec<EvaluationVerdict>,
+}
+
+impl EnsembleVerdict {
+    /// Fraction of judges that agreed with the majority pass/fail decision.
+    pub fn agreement(&self) -> f64 {
+        if self.verdicts.is_empty() {
+            return 0.0;
+        }
+        let agreeing = self
+            .verdicts
+            .iter()
+            .filter(|v| v.pass == self.verdict.pass)
+            .count();
+        agreeing as f64 / self.verdicts.len() as f64
+    }
+
+    fn aggregate(verdicts: Vec<EvaluationVerdict>) -> Self {
+        let total = verdicts.len();
+        let score = verdicts.iter().map(|v| v.score).sum::<f64>() / total as f64;
+        // Ties fail, so a split panel never passes a case.
+        let pass = verdicts.iter().filter(|v| v.pass).count() * 2 > total;
+
+        let mut majority: Vec<(usize, &EvaluationVerdict)> = verdicts
+            .iter()
+            .enumerate()
+            .filter(|(_, v)| v.pass == pass)
+            .collect();
+        // Most confident first: highest scores for a pass, lowest for a fail.
+        majority.sort_by(|(_, a), (_, b)| {
+            let order = a.score.total_cmp(&b.score);
+            if pass {
+                order.reverse()
+            } else {
+                order
+            }
+        });
+        let reasoning = majority
+            .iter()
+            .take(3)
+            .map(|(i, v)| format!("[judge {} | score {:.2}] {}", i + 1, v.score, v.reasoning))
+            .collect::<Vec<_>>()
+            .join("\n");
+
+        Self {
+            verdict: EvaluationVerdict {
+                score,
+                pass,
+                reasoning,
+            },
+            verdicts,
+        }
+    }
+}
+
 /// A helper for running LLM-based evaluations.
 #[derive(Clone)]
 pub struct LLMJudge {
     client: StructuredClient,
     rubric: String,
+    ensemble_temperature: Option<f32>,
 }
 
 impl LLMJudge {
@@ -327,9 +904,19 @@ impl LLMJudge {
         Self {
             client,
             rubric: rubric.into(),
+            ensemble_temperature: None,
         }
     }
 
+    /// Sampling temperature for [`evaluate_ensemble`](Self::evaluate_ensemble) runs.
+    ///
+    /// A higher temperature makes the judges more independent. Defaults to the client's
+    /// temperature.
+    pub fn with_ensemble_temperature(mut self, temperature: f32) -> Self {
+        self.ensemble_temperature = Some(temperature);
+        self
+    }
+
     /// Evaluate an outcome.
     ///
     /// - `input`: The original context provided to the agent.
@@ -341,6 +928,57 @@ impl LLMJudge {
         config: &C,
         simulation_result: Option<&R>,
     ) -> crate::Result<EvaluationVerdict>
+    where
+        I: Serialize,
+        C: Serialize,
+        R: Serialize,
+    {
+        let prompt = self.judge_prompt(input, config, simulation_result)?;
+        self.judge(prompt, None).await
+    }
+
+    /// Evaluate an outcome with `n` concurrent judge runs and aggregate the verdicts.
+    ///
+    /// The score is averaged, `pass` is decided by strict majority (ties fail) and the
+    /// reasoning combines the most confident majority verdicts. Failed runs are
+    /// dropped; an error is returned only if every run fails. The individual verdicts
+    /// are kept in [`EnsembleVerdict::verdicts`] for inspecting disagreement.
+    pub async fn evaluate_ensemble<I, C, R>(
+        &self,
+        input: &I,
+        config: &C,
+        simulation_result: Option<&R>,
+        n: usize,
+    ) -> crate::Result<EnsembleVerdict>
+    where
+        I: Serialize,
+        C: Serialize,
+        R: Serialize,
+    {
+        let prompt = self.judge_prompt(input, config, simulation_result)?;
+        let runs = (0..n.max(1)).map(|_| self.judge(prompt.clone(), self.ensemble_temperature));
+        let results = futures::future::join_all(runs).await;
+
+        let mut verdicts = Vec::with_capacity(results.len());
+        let mut last_error = None;
+        for result in results {
+            match result {
+                Ok(verdict) => verdicts.push(verdict),
+                Err(err) => last_error = Some(err),
+            }
+        }
+        if verdicts.is_empty() {
+            return Err(last_error.expect("at least one judge run"));
+        }
+        Ok(EnsembleVerdict::aggregate(verdicts))
+    }
+
+    fn judge_prompt<I, C, R>(
+        &self,
+        input: &I,
+        config: &C,
+        simulation_result: Option<&R>,
+    ) -> crate::Result<String>
     where
         I: Serialize,
         C: Serialize,
@@ -359,7 +997,7 @@ impl LLMJudge {
             "### COMPUTED SIMULATION RESULT: (not provided)\n".to_string()
         };
 
-        let prompt = format!(
+        Ok(format!(
             "### TASK: Evaluate the AI's performance based on the Rubric.\n\
              Focus primarily on whether the COMPUTED SIMULATION RESULT satisfies the INPUT requirements.\n\
              The 'Generated Configuration' is the means to the end; if the result is correct, valid configurations vary.\n\n\
@@ -369,15 +1007,23 @@ impl LLMJudge {
              {}\n\
              Provide a score (0.0-1.0), pass/fail, and reasoning.",
             self.rubric, input_json, config_json, result_section
-        );
+        ))
+    }
 
-        let outcome = self
+    async fn judge(
+        &self,
+        prompt: String,
+        temperature: Option<f32>,
+    ) -> crate::Result<EvaluationVerdict> {
+        let mut request = self
             .client
             .request::<EvaluationVerdict>()
             .system("You are an expert impartial judge. You evaluate technical outcomes.")
-            .user_text(prompt)
-            .execute()
-            .await?;
+            .user_text(prompt);
+        if let Some(temperature) = temperature {
+            request = request.temperature(temperature);
+        }
+        let outcome = request.execute().await?;
 
         Ok(outcome.value)
     }
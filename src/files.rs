@@ -6,6 +6,55 @@ use tokio::time::{sleep, Duration};
 
 use crate::error::{Result, StructuredError};
 
+/// An uploaded file paired with a label so the model can tell documents apart.
+///
+/// Each bundle is rendered as a `=== Document: <label> ===` text part followed by the file.
+pub struct DocumentBundle {
+    pub handle: FileHandle,
+    /// Human-readable name, e.g. `"2023 Actuals"`.
+    pub label: String,
+    /// Optional note on how the document should be used, e.g. `"source of truth for revenue"`.
+    pub role_hint: Option<String>,
+}
+
+impl DocumentBundle {
+    pub fn new(handle: FileHandle, label: impl Into<String>) -> Self {
+        Self {
+            handle,
+            label: label.into(),
+            role_hint: None,
+        }
+    }
+
+    /// Describe how the model should use this document.
+    pub fn with_role_hint(mut self, hint: impl Into<String>) -> Self {
+        self.role_hint = Some(hint.into());
+        self
+    }
+
+    /// Label text part followed by the file part.
+    pub(crate) fn to_parts(&self) -> Result<Vec<Part>> {
+        let file = FileManager::as_part(&self.handle)?;
+        Ok(labeled_parts(&self.label, self.role_hint.as_deref(), file))
+    }
+}
+
+fn labeled_parts(label: &str, role_hint: Option<&str>, file: Part) -> Vec<Part> {
+    let mut text = format!("=== Document: {label} ===");
+    if let Some(hint) = role_hint {
+        text.push('\n');
+        text.push_str(hint);
+    }
+    vec![
+        Part::Text {
+            text,
+            thought: None,
+            thought_signature: None,
+        },
+        file,
+    ]
+}
+
 /// Helper for working with Gemini file handles.
 #[derive(Clone)]
 pub struct FileManager {
@@ -57,6 +106,23 @@ impl FileManager {
         Ok(handle)
     }
 
+    /// Re-fetch a file's current metadata (name, uri, MIME type, state) from the backend.
+    ///
+    /// Handles returned straight from an upload can lack `uri` or `mime_type` until the
+    /// backend has processed the file; refreshing fills them in. Fails if the backend
+    /// still reports incomplete metadata.
+    pub async fn refresh(&self, handle: &FileHandle) -> Result<FileHandle> {
+        let name = handle.name().to_string();
+        let latest = self.client.get_file(&name).await?;
+        let meta = latest.get_file_meta();
+        if meta.mime_type.is_none() || meta.uri.is_none() {
+            return Err(StructuredError::Context(format!(
+                "file {name} still has incomplete metadata after refresh"
+            )));
+        }
+        Ok(latest)
+    }
+
     /// Convert a handle into a `Part::FileData` usable by `ContextBuilder`.
     pub fn as_part(handle: &FileHandle) -> Result<Part> {
         let meta = handle.get_file_meta();
@@ -119,3 +185,49 @@ impl FileManager {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file_part(uri: &str) -> Part {
+        Part::FileData {
+            file_data: FileData {
+                mime_type: "application/pdf".to_string(),
+                file_uri: uri.to_string(),
+            },
+        }
+    }
+
+    #[test]
+    fn label_and_hint_precede_the_file_part() {
+        let parts = labeled_parts(
+            "2023 Actuals",
+            Some("Source of truth for revenue"),
+            file_part("files/actuals"),
+        );
+
+        assert_eq!(parts.len(), 2);
+        match &parts[0] {
+            Part::Text { text, .. } => assert_eq!(
+                text,
+                "=== Document: 2023 Actuals ===\nSource of truth for revenue"
+            ),
+            other => panic!("expected the label first, got {other:?}"),
+        }
+        assert!(matches!(
+            &parts[1],
+            Part::FileData { file_data } if file_data.file_uri == "files/actuals"
+        ));
+    }
+
+    #[test]
+    fn label_without_hint_is_a_single_line() {
+        let parts = labeled_parts("Budget", None, file_part("files/budget"));
+
+        assert!(matches!(
+            &parts[0],
+            Part::Text { text, .. } if text == "=== Document: Budget ==="
+        ));
+    }
+}
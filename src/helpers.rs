@@ -1,7 +1,8 @@
 //! Formatting helpers for preparing prompt inputs.
 //!
 //! This module provides utilities for converting various data formats
-//! into LLM-friendly representations (typically markdown).
+//! into LLM-friendly representations (typically markdown), and for reading
+//! markdown tables written by a model back into CSV or JSON.
 //!
 //! Enable with the `helpers` feature flag.
 
@@ -293,6 +294,138 @@ pub enum JsonTableError {
     Empty,
 }
 
+/// Markdown table parsing error.
+#[derive(Debug, thiserror::Error)]
+pub enum MarkdownTableError {
+    #[error("No markdown table found")]
+    NoTable,
+    #[error("Missing header separator row (e.g. `| --- | --- |`) on line {line}")]
+    MissingSeparator { line: usize },
+}
+
+/// Convert the first markdown table in `md` to CSV.
+///
+/// The header separator row is skipped and `\|` is read as a literal pipe. Rows with
+/// fewer cells than the header are padded with empty cells; extra cells are dropped.
+/// Cells containing commas, quotes or newlines are quoted.
+///
+/// # Example
+/// ```
+/// use gemini_structured_output::helpers::markdown_table_to_csv;
+///
+/// let md = "| Name | Age |\n| ---- | --: |\n| Alice | 30 |\n| Bob | 25 |";
+/// assert_eq!(markdown_table_to_csv(md).unwrap(), "Name,Age\nAlice,30\nBob,25\n");
+/// ```
+pub fn markdown_table_to_csv(md: &str) -> Result<String, MarkdownTableError> {
+    let (header, rows) = parse_markdown_table(md)?;
+    let mut output = String::new();
+    for row in std::iter::once(&header).chain(&rows) {
+        let cells: Vec<String> = row.iter().map(|cell| csv_cell(cell)).collect();
+        writeln!(output, "{}", cells.join(",")).unwrap();
+    }
+    Ok(output)
+}
+
+/// Convert the first markdown table in `md` to a JSON array of objects keyed by header.
+///
+/// Cells that are valid JSON numbers or booleans become those types, empty cells
+/// become `null`, and everything else stays a string, mirroring how
+/// [`json_array_to_markdown`] renders values.
+pub fn markdown_table_to_json(md: &str) -> Result<serde_json::Value, MarkdownTableError> {
+    let (header, rows) = parse_markdown_table(md)?;
+    let objects = rows
+        .into_iter()
+        .map(|row| {
+            let object: serde_json::Map<String, serde_json::Value> = header
+                .iter()
+                .cloned()
+                .zip(row.iter().map(|cell| cell_to_value(cell)))
+                .collect();
+            serde_json::Value::Object(object)
+        })
+        .collect();
+    Ok(serde_json::Value::Array(objects))
+}
+
+/// Parse the first pipe table in `md` into a header and rows padded to its width.
+fn parse_markdown_table(md: &str) -> Result<(Vec<String>, Vec<Vec<String>>), MarkdownTableError> {
+    let mut lines = md
+        .lines()
+        .enumerate()
+        .skip_while(|(_, line)| !line.trim_start().starts_with('|'))
+        .take_while(|(_, line)| line.trim_start().starts_with('|'));
+
+    let (_, header_line) = lines.next().ok_or(MarkdownTableError::NoTable)?;
+    let header = split_table_row(header_line);
+
+    match lines.next() {
+        Some((_, line)) if is_separator_row(line) => {}
+        Some((index, _)) => return Err(MarkdownTableError::MissingSeparator { line: index + 1 }),
+        None => return Err(MarkdownTableError::NoTable),
+    }
+
+    let rows = lines
+        .map(|(_, line)| {
+            let mut row = split_table_row(line);
+            row.resize(header.len(), String::new());
+            row
+        })
+        .collect();
+    Ok((header, rows))
+}
+
+/// Split `| a | b \| c |` into trimmed cells, unescaping `\|`.
+fn split_table_row(line: &str) -> Vec<String> {
+    let line = line.trim();
+    let line = line.strip_prefix('|').unwrap_or(line);
+
+    let mut cells = Vec::new();
+    let mut cell = String::new();
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\\' if chars.peek() == Some(&'|') => {
+                cell.push('|');
+                chars.next();
+            }
+            '|' => cells.push(std::mem::take(&mut cell).trim().to_string()),
+            _ => cell.push(c),
+        }
+    }
+    // A row without a closing pipe still ends with a cell.
+    if !cell.trim().is_empty() {
+        cells.push(cell.trim().to_string());
+    }
+    cells
+}
+
+fn is_separator_row(line: &str) -> bool {
+    let cells = split_table_row(line);
+    !cells.is_empty()
+        && cells.iter().all(|cell| {
+            let dashes = cell.trim_start_matches(':').trim_end_matches(':');
+            !dashes.is_empty() && dashes.chars().all(|c| c == '-')
+        })
+}
+
+fn csv_cell(cell: &str) -> String {
+    if cell.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", cell.replace('"', "\"\""))
+    } else {
+        cell.to_string()
+    }
+}
+
+fn cell_to_value(cell: &str) -> serde_json::Value {
+    if cell.is_empty() {
+        return serde_json::Value::Null;
+    }
+    match serde_json::from_str::<serde_json::Value>(cell) {
+        Ok(value @ (serde_json::Value::Number(_) | serde_json::Value::Bool(_))) => value,
+        _ => serde_json::Value::String(cell.to_string()),
+    }
+}
+
 /// Format numbers with thousands separators.
 pub fn format_number(n: f64, decimals: usize) -> String {
     let formatted = format!("{:.prec$}", n, prec = decimals);
@@ -335,14 +468,29 @@ pub fn format_currency(amount: f64, currency: &str, decimals: usize) -> String {
 }
 
 /// Truncate text with ellipsis.
+///
+/// `max_len` is in bytes. The cut is moved back to the nearest character boundary, so
+/// multi-byte UTF-8 text is never split inside a character.
 pub fn truncate_text(text: &str, max_len: usize) -> String {
     if text.len() <= max_len {
         text.to_string()
     } else if max_len <= 3 {
-        text.chars().take(max_len).collect()
+        text[..floor_char_boundary(text, max_len)].to_string()
     } else {
-        format!("{}...", &text[..max_len - 3])
+        format!("{}...", &text[..floor_char_boundary(text, max_len - 3)])
+    }
+}
+
+/// Largest character boundary in `text` that is `<= index`.
+fn floor_char_boundary(text: &str, index: usize) -> usize {
+    if index >= text.len() {
+        return text.len();
     }
+    text.char_indices()
+        .map(|(i, _)| i)
+        .take_while(|&i| i <= index)
+        .last()
+        .unwrap_or(0)
 }
 
 /// Create a bullet list from items.
@@ -446,6 +594,67 @@ mod tests {
         assert_eq!(truncate_text("Hi", 10), "Hi");
     }
 
+    #[test]
+    fn test_markdown_table_round_trips_aligned_table() {
+        let csv = "Name,Age,City\nAlice,30,NYC\nBob,25,LA\n";
+        let opts = CsvOptions {
+            alignment: TableAlignment::Center,
+            ..Default::default()
+        };
+        let md = csv_to_markdown_with_options(csv, Some("People"), opts).unwrap();
+
+        assert_eq!(markdown_table_to_csv(&md).unwrap(), csv);
+        let json = markdown_table_to_json(&md).unwrap();
+        assert_eq!(json[1]["Name"], "Bob");
+        assert_eq!(json[1]["Age"], 25);
+    }
+
+    #[test]
+    fn test_markdown_table_ragged_rows_and_escaped_pipes() {
+        let md = "Here you go:\n\n\
+                  | Item | Note | Done |\n\
+                  |:-----|------|:----:|\n\
+                  | a \\| b | x, y | true |\n\
+                  | c |\n\
+                  | d | e | false | extra |\n\
+                  \nLet me know!";
+
+        assert_eq!(
+            markdown_table_to_csv(md).unwrap(),
+            "Item,Note,Done\na | b,\"x, y\",true\nc,,\nd,e,false\n"
+        );
+        let json = markdown_table_to_json(md).unwrap();
+        assert_eq!(json.as_array().unwrap().len(), 3);
+        assert_eq!(json[0]["Item"], "a | b");
+        assert_eq!(json[0]["Done"], true);
+        assert!(json[1]["Note"].is_null());
+    }
+
+    #[test]
+    fn test_markdown_table_errors() {
+        assert!(matches!(
+            markdown_table_to_csv("no table here"),
+            Err(MarkdownTableError::NoTable)
+        ));
+        assert!(matches!(
+            markdown_table_to_csv("| a | b |\n| 1 | 2 |"),
+            Err(MarkdownTableError::MissingSeparator { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_truncate_text_multibyte() {
+        // "é" is two bytes; a cut at byte 4 would land inside it.
+        assert_eq!(truncate_text("café au lait", 7), "caf...");
+        assert_eq!(truncate_text("café", 3), "caf");
+        // Three bytes per character.
+        assert_eq!(truncate_text("日本語テキスト", 10), "日本...");
+        assert_eq!(truncate_text("日本語テキスト", 2), "");
+        // Four-byte emoji.
+        assert_eq!(truncate_text("🎉🎉🎉 party", 9), "🎉...");
+        assert_eq!(truncate_text("🎉🎉🎉 party", 3), "");
+    }
+
     #[test]
     fn test_bullet_list() {
         let list = bullet_list(["Apple", "Banana"]);
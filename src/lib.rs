@@ -33,10 +33,12 @@
 //!
 //! - **`helpers`**: Enable formatting utilities (CSV to markdown, etc.)
 //! - **`macros`**: Enable procedural macros (`#[gemini_tool]`, `#[derive(GeminiValidated)]`)
+//! - **`testing`**: Enable test helpers such as `assert_schema_unchanged!`
 
 pub mod adapter;
 pub mod agent;
 pub mod caching;
+pub mod circuit;
 pub mod client;
 pub mod context;
 pub mod error;
@@ -48,6 +50,7 @@ pub mod generator;
 pub mod helpers;
 pub mod models;
 pub mod patching;
+mod redaction;
 pub mod request;
 pub mod schema;
 pub mod session;
@@ -56,33 +59,50 @@ pub mod workflow;
 
 pub use caching::CachePolicy;
 pub use caching::CacheSettings;
+pub use caching::CacheStats;
+pub use caching::IdempotencyConfig;
+pub use circuit::{CircuitBreaker, CircuitBreakerConfig, CircuitPermit, CircuitState};
 pub use client::{
-    ClientConfig, FallbackStrategy, MockHandler, MockRequest, ResponseHook, StructuredClient,
-    StructuredClientBuilder,
+    ClientConfig, FallbackStrategy, MockHandler, MockRequest, ResponseHook, RetryClassifier,
+    StructuredClient, StructuredClientBuilder,
 };
-pub use context::ContextBuilder;
+pub use context::{ContextBuilder, SystemPrompt};
 pub use error::{Result, ResultExt, StructuredError};
 #[cfg(feature = "evals")]
 pub use evals::{
-    EvalResult, EvalSuite, EvaluationVerdict, EvaluatorOutcome, LLMJudge, SuiteReport,
+    CaseDiff, CaseTransition, EnsembleVerdict, EvalResult, EvalSuite, EvaluationVerdict,
+    EvaluatorOutcome, LLMJudge, SuiteDiff, SuiteReport,
 };
-pub use files::FileManager;
+pub use files::{DocumentBundle, FileManager};
 pub use generator::{GeminiGenerator, TextGenerator};
-pub use models::{GenerationOutcome, RefinementAttempt, RefinementOutcome};
+pub use models::{
+    GenerationOutcome, JsonlLineError, JsonlOutcome, ListEnvelope, MediaPart, Pair, Quad,
+    RefinementAttempt, RefinementFailureKind, RefinementOutcome, ToolInvocation, Triple,
+};
 pub use patching::{
-    ArrayPatchStrategy, AsyncCustomValidator, BoxFuture, CustomValidator, PatchStrategy,
-    RefinementConfig, RefinementEngine, RefinementRequest, ValidationFailureStrategy,
+    ArrayPatchStrategy, AsyncCustomValidator, AttemptCallback, BoxFuture, CustomValidator,
+    PatchFormat, PatchStrategy, RefinementConfig, RefinementEngine, RefinementMode,
+    RefinementRequest, ValidationFailureStrategy,
+};
+pub use request::{ArrayStreamEvent, StreamEvent, StructuredRequest};
+pub use schema::{
+    CloseObjects, CollapseEnums, DescribeFields, FieldChange, GeminiStructured, GeminiValidator,
+    InlineRefs, LimitDepth, MapSchemaMode, SchemaBundle, SchemaTransform, StructuredValidator,
+    ValidatorFn,
 };
-pub use request::{StreamEvent, StructuredRequest};
-pub use schema::{GeminiStructured, GeminiValidator, MapSchemaMode, StructuredValidator};
-pub use session::{ChangeEffect, EntryKind, InteractiveSession, PendingChange, SessionEntry};
-pub use tools::ToolRegistry;
+pub use session::{
+    ChangeEffect, EntryKind, HistoryCompaction, InteractiveSession, PendingChange, SessionEntry,
+};
+pub use tools::{ToolContext, ToolHandler, ToolRegistry};
 pub use workflow::{
-    BatchStep, BoxedStepExt, ChainStep, ChainTupleStep, CheckpointStep, ConditionalCheckpointStep,
-    ConfiguredReduceStep, ExecutionContext, InstrumentedStep, LambdaStateStep, LambdaStep, MapStep,
-    ParallelMapBuilder, ParallelMapStep, ReduceStep, ReduceStepBuilder, ReviewStep, RouterStep,
-    SingleItemAdapter, StateStep, StateWorkflow, Step, StepAdapter, TapStep, TraceEntry,
-    WindowedContextStep, Workflow, WorkflowEvent, WorkflowMetrics, WorkflowStep,
+    AlternateStep, ArtifactMap, BatchStep, BoxedStepExt, BranchStep, ChainStep, ChainTupleStep,
+    CheckpointStep, ConditionalCheckpointStep, ConfiguredReduceStep, ExecutionContext,
+    FallbackStep, FinalizedStateWorkflow, InstrumentedStep, LambdaStateStep, LambdaStep, LoopStep,
+    MapStep, MeteredStep, ParallelCollectStep, ParallelMapBuilder, ParallelMapStep,
+    ProvenanceReduceStep, RecoverStep, ReduceStep, ReduceStepBuilder, ResumableWorkflow, RetryStep,
+    ReviewStep, RouterStep, SingleItemAdapter, StateStep, StateWorkflow, Step, StepAdapter,
+    StepFailure, TapStep, TimeoutStep, TraceEntry, ValidatedStep, WindowedContextStep,
+    WithArtifactsStep, Workflow, WorkflowEvent, WorkflowGraph, WorkflowMetrics, WorkflowStep,
 };
 
 /// Prelude module for convenient imports.
@@ -91,35 +111,43 @@ pub use workflow::{
 /// use gemini_structured_output::prelude::*;
 /// ```
 pub mod prelude {
-    pub use crate::caching::{CachePolicy, CacheSettings};
+    pub use crate::caching::{CachePolicy, CacheSettings, CacheStats};
+    pub use crate::circuit::{CircuitBreakerConfig, CircuitState};
     pub use crate::client::{
         FallbackStrategy, MockHandler, MockRequest, ResponseHook, StructuredClient,
         StructuredClientBuilder,
     };
-    pub use crate::context::ContextBuilder;
+    pub use crate::context::{ContextBuilder, SystemPrompt};
     pub use crate::error::{Result, ResultExt, StructuredError};
     #[cfg(feature = "evals")]
     pub use crate::evals::{
-        EvalResult, EvalSuite, EvaluationVerdict, EvaluatorOutcome, LLMJudge, SuiteReport,
+        CaseDiff, CaseTransition, EnsembleVerdict, EvalResult, EvalSuite, EvaluationVerdict,
+        EvaluatorOutcome, LLMJudge, SuiteDiff, SuiteReport,
     };
     pub use crate::generator::{GeminiGenerator, TextGenerator};
-    pub use crate::models::{GenerationOutcome, RefinementOutcome};
+    pub use crate::models::{GenerationOutcome, MediaPart, RefinementOutcome};
     pub use crate::patching::{
-        ArrayPatchStrategy, AsyncCustomValidator, BoxFuture, CustomValidator, PatchStrategy,
-        RefinementConfig, RefinementEngine, RefinementRequest, ValidationFailureStrategy,
+        ArrayPatchStrategy, AsyncCustomValidator, AttemptCallback, BoxFuture, CustomValidator,
+        PatchFormat, PatchStrategy, RefinementConfig, RefinementEngine, RefinementMode,
+        RefinementRequest, ValidationFailureStrategy,
+    };
+    pub use crate::request::{ArrayStreamEvent, StreamEvent, StructuredRequest};
+    pub use crate::schema::{
+        GeminiStructured, GeminiValidator, MapSchemaMode, StructuredValidator,
     };
-    pub use crate::request::{StreamEvent, StructuredRequest};
-    pub use crate::schema::{GeminiStructured, GeminiValidator, MapSchemaMode, StructuredValidator};
     pub use crate::session::{
-        ChangeEffect, EntryKind, InteractiveSession, PendingChange, SessionEntry,
+        ChangeEffect, EntryKind, HistoryCompaction, InteractiveSession, PendingChange, SessionEntry,
     };
-    pub use crate::tools::ToolRegistry;
+    pub use crate::tools::{ToolContext, ToolHandler, ToolRegistry};
     pub use crate::workflow::{
-        BatchStep, BoxedStepExt, ChainStep, ChainTupleStep, CheckpointStep,
-        ConditionalCheckpointStep, ConfiguredReduceStep, ExecutionContext, InstrumentedStep,
-        LambdaStateStep, LambdaStep, MapStep, ParallelMapBuilder, ParallelMapStep, ReduceStep,
-        ReduceStepBuilder, ReviewStep, RouterStep, SingleItemAdapter, StateStep, StateWorkflow,
-        Step, StepAdapter, TapStep, TraceEntry, WindowedContextStep, Workflow, WorkflowEvent,
+        AlternateStep, ArtifactMap, BatchStep, BoxedStepExt, BranchStep, ChainStep, ChainTupleStep,
+        CheckpointStep, ConditionalCheckpointStep, ConfiguredReduceStep, ExecutionContext,
+        FallbackStep, FinalizedStateWorkflow, InstrumentedStep, LambdaStateStep, LambdaStep,
+        LoopStep, MapStep, MeteredStep, ParallelCollectStep, ParallelMapBuilder, ParallelMapStep,
+        ProvenanceReduceStep, RecoverStep, ReduceStep, ReduceStepBuilder, ResumableWorkflow,
+        RetryStep, ReviewStep, RouterStep, SingleItemAdapter, StateStep, StateWorkflow, Step,
+        StepAdapter, StepFailure, TapStep, TimeoutStep, TraceEntry, ValidatedStep,
+        WindowedContextStep, WithArtifactsStep, Workflow, WorkflowEvent, WorkflowGraph,
         WorkflowMetrics, WorkflowStep,
     };
 
@@ -138,7 +166,8 @@ pub mod prelude {
 pub use helpers::{
     bullet_list, code_block, collapsible, csv_to_markdown, csv_to_markdown_with_options,
     format_currency, format_number, json_array_to_markdown, key_value, key_value_block,
-    numbered_list, truncate_text, CsvError, CsvOptions, JsonTableError, TableAlignment,
+    markdown_table_to_csv, markdown_table_to_json, numbered_list, truncate_text, CsvError,
+    CsvOptions, JsonTableError, MarkdownTableError, TableAlignment,
 };
 
 #[cfg(feature = "macros")]
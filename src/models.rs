@@ -1,9 +1,31 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+/// Validation layer that rejected a refinement attempt.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub enum RefinementFailureKind {
+    /// The model's response was not a parseable JSON Patch.
+    PatchParse,
+    /// One or more patch operations could not be applied.
+    PatchApply,
+    /// The patched value violated the JSON Schema.
+    Schema,
+    /// The type's own `validate()` rejected the value.
+    Logic,
+    /// The synchronous custom validator rejected the value.
+    Custom,
+    /// The async custom validator rejected the value.
+    Async,
+}
+
 /// Information about an individual refinement attempt.
 #[derive(Debug, Clone)]
 pub struct RefinementAttempt {
     pub patch: String,
     pub success: bool,
     pub error: Option<String>,
+    /// Which validation layer rejected the attempt, when known.
+    pub failure_kind: Option<RefinementFailureKind>,
 }
 
 impl RefinementAttempt {
@@ -12,6 +34,7 @@ impl RefinementAttempt {
             patch,
             success: true,
             error: None,
+            failure_kind: None,
         }
     }
 
@@ -20,8 +43,15 @@ impl RefinementAttempt {
             patch,
             success: false,
             error: Some(error.into()),
+            failure_kind: None,
         }
     }
+
+    /// Record which validation layer rejected this attempt.
+    pub fn with_failure_kind(mut self, kind: RefinementFailureKind) -> Self {
+        self.failure_kind = Some(kind);
+        self
+    }
 }
 
 /// Outcome of the refinement loop including the final value and patch trace.
@@ -31,6 +61,11 @@ pub struct RefinementOutcome<T> {
     pub attempts: Vec<RefinementAttempt>,
     /// Parsed JSON Patch that produced the final value, when available.
     pub patch: Option<json_patch::Patch>,
+    /// True when refinement was exhausted and `value` is the last schema-valid
+    /// state rather than a fully validated result.
+    pub best_effort: bool,
+    /// Validation errors still present in a best-effort `value`.
+    pub remaining_errors: Vec<String>,
 }
 
 impl<T> RefinementOutcome<T> {
@@ -39,6 +74,8 @@ impl<T> RefinementOutcome<T> {
             value,
             attempts,
             patch: None,
+            best_effort: false,
+            remaining_errors: Vec::new(),
         }
     }
 
@@ -51,10 +88,62 @@ impl<T> RefinementOutcome<T> {
             value,
             attempts,
             patch,
+            best_effort: false,
+            remaining_errors: Vec::new(),
+        }
+    }
+
+    /// Count of failed attempts per validation layer.
+    pub fn failure_counts(&self) -> std::collections::HashMap<RefinementFailureKind, usize> {
+        let mut counts = std::collections::HashMap::new();
+        for kind in self.attempts.iter().filter_map(|a| a.failure_kind) {
+            *counts.entry(kind).or_insert(0) += 1;
+        }
+        counts
+    }
+
+    /// Outcome for an exhausted refinement that fell back to its last schema-valid value.
+    pub fn best_effort(
+        value: T,
+        attempts: Vec<RefinementAttempt>,
+        remaining_errors: Vec<String>,
+    ) -> Self {
+        Self {
+            value,
+            attempts,
+            patch: None,
+            best_effort: true,
+            remaining_errors,
         }
     }
 }
 
+/// A tool call made while answering a request.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ToolInvocation {
+    pub name: String,
+    pub args: serde_json::Value,
+    /// Value returned to the model, populated when
+    /// [`capture_tool_results`](crate::StructuredRequest::capture_tool_results) is enabled.
+    pub result: Option<serde_json::Value>,
+}
+
+/// Non-text content (e.g. a generated image) returned alongside the structured output.
+#[derive(Debug, Clone)]
+pub struct MediaPart {
+    pub mime_type: String,
+    /// Base64-encoded payload as returned by the API.
+    pub data: String,
+}
+
+impl MediaPart {
+    /// Decode the base64 payload into raw bytes.
+    pub fn bytes(&self) -> Result<Vec<u8>, base64::DecodeError> {
+        use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
+        BASE64.decode(&self.data)
+    }
+}
+
 /// Structured generation result with additional metadata.
 #[derive(Debug, Clone)]
 pub struct GenerationOutcome<T> {
@@ -67,6 +156,18 @@ pub struct GenerationOutcome<T> {
     pub parse_attempts: usize,
     /// How many network calls (including retries) were made.
     pub network_attempts: usize,
+    /// Inline media parts from the final response, populated when
+    /// [`capture_media`](crate::StructuredRequest::capture_media) is enabled.
+    pub media: Vec<MediaPart>,
+    /// Raw model text, populated when
+    /// [`capture_raw`](crate::StructuredRequest::capture_raw) is enabled.
+    pub raw_text: Option<String>,
+    /// Text actually parsed after stripping code fences, populated alongside `raw_text`.
+    pub cleaned_text: Option<String>,
+    /// Correlation ID recorded on the request's tracing span, set by `execute`.
+    pub correlation_id: Option<String>,
+    /// Every tool call executed during the request's tool loop, in call order.
+    pub tool_invocations: Vec<ToolInvocation>,
 }
 
 impl<T> GenerationOutcome<T> {
@@ -87,6 +188,210 @@ impl<T> GenerationOutcome<T> {
             response_id,
             parse_attempts,
             network_attempts,
+            media: Vec::new(),
+            raw_text: None,
+            cleaned_text: None,
+            correlation_id: None,
+            tool_invocations: Vec::new(),
+        }
+    }
+
+    /// Attach captured media parts.
+    pub fn with_media(mut self, media: Vec<MediaPart>) -> Self {
+        self.media = media;
+        self
+    }
+
+    /// Attach the correlation ID of the request that produced this outcome.
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// Attach the tool calls made while producing this outcome.
+    pub fn with_tool_invocations(mut self, invocations: Vec<ToolInvocation>) -> Self {
+        self.tool_invocations = invocations;
+        self
+    }
+
+    /// Attach the raw and cleaned response text.
+    pub fn with_raw_text(mut self, raw_text: Option<String>, cleaned_text: Option<String>) -> Self {
+        self.raw_text = raw_text;
+        self.cleaned_text = cleaned_text;
+        self
+    }
+}
+
+/// Generate a wrapper type that combines several structured outputs into one schema.
+macro_rules! multi_output {
+    ($(#[$meta:meta])* $name:ident { $($field:ident: $ty:ident => $desc:literal),+ }) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+        pub struct $name<$($ty),+> {
+            $(
+                #[schemars(description = $desc)]
+                pub $field: $ty,
+            )+
+        }
+
+        impl<$($ty),+> $name<$($ty),+> {
+            /// Split into a tuple in declaration order.
+            pub fn into_tuple(self) -> ($($ty),+) {
+                ($(self.$field),+)
+            }
+        }
+    };
+}
+
+multi_output!(
+    /// Two structured outputs extracted in a single call.
+    Pair {
+        first: A => "The first requested output.",
+        second: B => "The second requested output."
+    }
+);
+
+multi_output!(
+    /// Three structured outputs extracted in a single call.
+    Triple {
+        first: A => "The first requested output.",
+        second: B => "The second requested output.",
+        third: C => "The third requested output."
+    }
+);
+
+multi_output!(
+    /// Four structured outputs extracted in a single call.
+    Quad {
+        first: A => "The first requested output.",
+        second: B => "The second requested output.",
+        third: C => "The third requested output.",
+        fourth: D => "The fourth requested output."
+    }
+);
+
+/// Object envelope for extracting a list, since the response schema must be an object.
+///
+/// Deserializes from either `{ "items": [...] }` or a bare array, so responses where
+/// the model ignores the envelope still parse.
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ListEnvelope<T> {
+    /// Every item found in the input.
+    pub items: Vec<T>,
+}
+
+impl<'de, T: Deserialize<'de>> Deserialize<'de> for ListEnvelope<T> {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: serde::Deserializer<'de>,
+    {
+        use serde::de::{self, IgnoredAny, MapAccess, SeqAccess, Visitor};
+
+        // Hand-rolled rather than `#[serde(untagged)]` so an item that fails to
+        // deserialize reports its own error instead of "did not match any variant".
+        struct EnvelopeVisitor<T>(std::marker::PhantomData<T>);
+
+        impl<'de, T: Deserialize<'de>> Visitor<'de> for EnvelopeVisitor<T> {
+            type Value = ListEnvelope<T>;
+
+            fn expecting(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
+                f.write_str("an array or an object with an `items` array")
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut items = Vec::with_capacity(seq.size_hint().unwrap_or(0));
+                while let Some(item) = seq.next_element()? {
+                    items.push(item);
+                }
+                Ok(ListEnvelope { items })
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let mut items = None;
+                while let Some(key) = map.next_key::<String>()? {
+                    if key != "items" {
+                        map.next_value::<IgnoredAny>()?;
+                    } else if items.is_some() {
+                        return Err(de::Error::duplicate_field("items"));
+                    } else {
+                        items = Some(map.next_value()?);
+                    }
+                }
+                let items = items.ok_or_else(|| de::Error::missing_field("items"))?;
+                Ok(ListEnvelope { items })
+            }
+        }
+
+        deserializer.deserialize_any(EnvelopeVisitor(std::marker::PhantomData))
+    }
+}
+
+/// A JSON Lines line that could not be parsed into the target type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct JsonlLineError {
+    /// 1-based line number in the raw response.
+    pub line: usize,
+    /// The line as the model wrote it.
+    pub text: String,
+    /// Why it failed to parse.
+    pub error: String,
+}
+
+impl std::fmt::Display for JsonlLineError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "line {}: {}", self.line, self.error)
+    }
+}
+
+/// Items parsed from a JSON Lines response, plus the lines that failed.
+#[derive(Debug, Clone)]
+pub struct JsonlOutcome<T> {
+    /// Successfully parsed items, in line order.
+    pub items: Vec<T>,
+    /// Lines that were not valid JSON for `T`.
+    pub failed_lines: Vec<JsonlLineError>,
+}
+
+impl<T: serde::de::DeserializeOwned> JsonlOutcome<T> {
+    /// Parse each non-empty line of `text` independently.
+    ///
+    /// Blank lines and code-fence markers are ignored; every other line goes through
+    /// the same fence and prose stripping as a single-document response.
+    pub fn parse(text: &str) -> Self {
+        Self::parse_with_hook(text, None)
+    }
+
+    /// Like [`parse`](Self::parse), running `hook` on each line's JSON before it is
+    /// deserialized.
+    pub(crate) fn parse_with_hook(text: &str, hook: Option<&crate::ResponseHook>) -> Self {
+        let mut items = Vec::new();
+        let mut failed_lines = Vec::new();
+        for (index, line) in text.lines().enumerate() {
+            let trimmed = line.trim();
+            if trimmed.is_empty() || trimmed.starts_with("```") {
+                continue;
+            }
+            let parsed = serde_json::from_str::<serde_json::Value>(
+                &crate::request::clean_json_text(trimmed),
+            )
+            .and_then(|mut value| {
+                if let Some(hook) = hook {
+                    hook(&mut value);
+                }
+                serde_json::from_value(value)
+            });
+            match parsed {
+                Ok(item) => items.push(item),
+                Err(err) => failed_lines.push(JsonlLineError {
+                    line: index + 1,
+                    text: line.to_string(),
+                    error: err.to_string(),
+                }),
+            }
+        }
+        Self {
+            items,
+            failed_lines,
         }
     }
 }
@@ -1,24 +1,32 @@
+use std::collections::HashMap;
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, Mutex};
+use tokio::sync::mpsc;
 use tokio::time::{sleep, Duration};
 
-use gemini_rust::{Content, FileHandle, Gemini, GenerationConfig, Message, Part, Role};
+use futures::StreamExt;
+use gemini_rust::{
+    generation::builder::ContentBuilder, Content, FileHandle, Gemini, GenerationConfig, Message,
+    Part, Role,
+};
 use schemars::JsonSchema;
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
 use serde_json::Value;
 use tracing::{debug, info, instrument, trace, warn};
 
 use crate::{
-    client::FallbackStrategy,
+    client::{FallbackStrategy, RetryClassifier},
     error::{Result, StructuredError},
-    files::FileManager,
+    files::{DocumentBundle, FileManager},
     generator::TextGenerator,
-    models::{RefinementAttempt, RefinementOutcome},
+    models::{RefinementAttempt, RefinementFailureKind, RefinementOutcome},
+    redaction::{redact_patch_text, redacted_value},
     schema::{
         clean_schema_for_gemini, coerce_enum_strings, compile_validator, prune_null_fields,
-        recover_internally_tagged_enums, strip_x_fields, unflatten_externally_tagged_enums,
-        warn_if_schema_too_deep, GeminiStructured, StructuredValidator,
+        recover_internally_tagged_enums, schema_hash, strip_x_fields,
+        unflatten_externally_tagged_enums, warn_if_schema_too_deep, GeminiStructured,
+        StructuredValidator,
     },
     StructuredClient,
 };
@@ -33,6 +41,9 @@ pub type BoxFuture<'a, T> = Pin<Box<dyn Future<Output = T> + Send + 'a>>;
 pub type AsyncCustomValidator<T> =
     Box<dyn Fn(&T) -> BoxFuture<'static, Option<String>> + Send + Sync>;
 
+/// Callback invoked after each refinement attempt with the attempt and its 1-based index.
+pub type AttemptCallback = Arc<dyn Fn(&RefinementAttempt, usize) + Send + Sync>;
+
 /// Strategy for handling validation failures during refinement.
 #[derive(Clone, Debug, Default)]
 pub enum ValidationFailureStrategy {
@@ -67,9 +78,14 @@ pub struct RefinementRequest<'a, T> {
     current: T,
     instruction: String,
     files: Vec<FileHandle>,
+    labeled_documents: Vec<DocumentBundle>,
     context_generator: Option<ContextGenerator<T>>,
     custom_validator: Option<CustomValidator<T>>,
     async_custom_validator: Option<AsyncCustomValidator<T>>,
+    cache_async_validation: bool,
+    appends: Vec<(String, Vec<Value>)>,
+    patch_progress: Option<mpsc::Sender<String>>,
+    on_attempt: Option<AttemptCallback>,
 }
 
 impl<'a, T> RefinementRequest<'a, T>
@@ -89,18 +105,48 @@ where
             current,
             instruction,
             files: Vec::new(),
+            labeled_documents: Vec::new(),
             context_generator: None,
             custom_validator: None,
             async_custom_validator: None,
+            cache_async_validation: false,
+            appends: Vec::new(),
+            patch_progress: None,
+            on_attempt: None,
         }
     }
 
+    /// Forward raw patch text deltas to `sender` while the model responds.
+    ///
+    /// See [`RefinementEngine::with_patch_progress`].
+    pub fn with_patch_progress(mut self, sender: mpsc::Sender<String>) -> Self {
+        self.patch_progress = Some(sender);
+        self
+    }
+
+    /// Call `f` after every attempt, successful or not, with the attempt and its index.
+    ///
+    /// See [`RefinementEngine::on_attempt`].
+    pub fn on_attempt<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RefinementAttempt, usize) + Send + Sync + 'static,
+    {
+        self.on_attempt = Some(Arc::new(f));
+        self
+    }
+
     /// Attach file handles (PDFs/images) to the refinement context.
     pub fn with_documents(mut self, documents: Vec<FileHandle>) -> Self {
         self.files = documents;
         self
     }
 
+    /// Attach labeled documents so the model can distinguish between multiple sources.
+    pub fn with_labeled_documents(mut self, documents: Vec<DocumentBundle>) -> Self {
+        self.labeled_documents = documents;
+        self
+    }
+
     /// Register a context-aware validator executed after schema and internal validation.
     ///
     /// Return `Some(error_message)` to signal invalid data; the message is fed back to the model.
@@ -127,6 +173,34 @@ where
         self
     }
 
+    /// Memoize async validation results by value hash (default: false).
+    ///
+    /// Candidates identical to one already validated reuse the earlier result instead of
+    /// re-running the async validator; any change to the value is a cache miss.
+    pub fn cache_async_validation(mut self, enabled: bool) -> Self {
+        self.cache_async_validation = enabled;
+        self
+    }
+
+    /// Append `items` to the array at `json_pointer` without asking the model.
+    ///
+    /// The patch is built locally (a whole-array `replace` under
+    /// [`ArrayPatchStrategy::ReplaceWhole`], `add` at `/-` otherwise) and the result runs
+    /// through the same validation layers as a model patch. With an empty instruction the
+    /// model is never called; otherwise refinement continues from the appended value.
+    ///
+    /// ```rust,ignore
+    /// let outcome = client
+    ///     .refine(invoice, "")
+    ///     .append_to("/line_items", vec![json!({"sku": "A-1", "qty": 2})])
+    ///     .execute()
+    ///     .await?;
+    /// ```
+    pub fn append_to(mut self, json_pointer: impl Into<String>, items: Vec<Value>) -> Self {
+        self.appends.push((json_pointer.into(), items));
+        self
+    }
+
     /// Inject dynamic context built from the current value on each iteration.
     pub fn with_context_generator<F>(mut self, f: F) -> Self
     where
@@ -140,7 +214,7 @@ where
     pub async fn execute(self) -> Result<RefinementOutcome<T>> {
         let mut initial_history = Vec::new();
 
-        if !self.files.is_empty() {
+        if !self.files.is_empty() || !self.labeled_documents.is_empty() {
             let mut parts: Vec<Part> = Vec::new();
             parts.push(Part::Text {
                 text: "Reference documents for this refinement task:".to_string(),
@@ -153,6 +227,10 @@ where
                 parts.push(part);
             }
 
+            for document in &self.labeled_documents {
+                parts.extend(document.to_parts()?);
+            }
+
             let content = Content {
                 parts: Some(parts),
                 role: Some(Role::User),
@@ -163,20 +241,91 @@ where
             });
         }
 
-        self.client
-            .refiner()
+        let async_validator = match self.async_custom_validator {
+            Some(validator) if self.cache_async_validation => Some(memoize_async(validator)),
+            validator => validator,
+        };
+
+        let mut current = self.current;
+        if !self.appends.is_empty() {
+            let (appended, patch) = self
+                .client
+                .refiner()
+                .apply_appends(
+                    &current,
+                    &self.appends,
+                    self.custom_validator.as_ref(),
+                    async_validator.as_ref(),
+                )
+                .await?;
+            if self.instruction.trim().is_empty() {
+                let patch_text = serde_json::to_string(&patch)?;
+                return Ok(RefinementOutcome::with_patch(
+                    appended,
+                    vec![RefinementAttempt::success(patch_text)],
+                    Some(patch),
+                ));
+            }
+            current = appended;
+        }
+
+        let observed_engine;
+        let engine = if self.patch_progress.is_none() && self.on_attempt.is_none() {
+            self.client.refiner()
+        } else {
+            let mut engine = self.client.refiner().clone();
+            if let Some(sender) = self.patch_progress {
+                engine = engine.with_patch_progress(sender);
+            }
+            if let Some(callback) = self.on_attempt {
+                engine.on_attempt = Some(callback);
+            }
+            observed_engine = engine;
+            &observed_engine
+        };
+        engine
             .execute_refinement(
-                self.current,
+                current,
                 self.instruction,
                 initial_history,
                 self.context_generator.as_ref(),
                 self.custom_validator.as_ref(),
-                self.async_custom_validator.as_ref(),
+                async_validator.as_ref(),
             )
             .await
     }
 }
 
+/// Wrap an async validator so results are reused for identical (by hash) values.
+fn memoize_async<T>(validator: AsyncCustomValidator<T>) -> AsyncCustomValidator<T>
+where
+    T: Serialize + Send + Sync + 'static,
+{
+    let validator = Arc::new(validator);
+    let cache: Arc<Mutex<HashMap<String, Option<String>>>> = Arc::new(Mutex::new(HashMap::new()));
+
+    Box::new(move |value: &T| {
+        let key = serde_json::to_value(value).ok().map(|v| schema_hash(&v));
+        if let Some(cached) = key
+            .as_ref()
+            .and_then(|k| cache.lock().unwrap().get(k).cloned())
+        {
+            debug!("Async validation cache hit; skipping validator");
+            return Box::pin(async move { cached });
+        }
+
+        let pending = validator(value);
+        let cache = Arc::clone(&cache);
+        Box::pin(async move {
+            let result = pending.await;
+            if let Some(key) = key {
+                cache.lock().unwrap().insert(key, result.clone());
+            }
+            result
+        })
+    })
+}
+
 /// Configuration for the refinement engine.
 #[derive(Clone, Debug)]
 pub struct RefinementConfig {
@@ -194,6 +343,16 @@ pub struct RefinementConfig {
     pub fallback_strategy: FallbackStrategy,
     /// Strategy for handling validation failures (iterate or rollback).
     pub validation_failure_strategy: ValidationFailureStrategy,
+    /// On exhaustion, return the last schema-valid value instead of erroring (default: false).
+    ///
+    /// The outcome is flagged `best_effort` and lists the validation errors it still has.
+    pub return_best_effort: bool,
+    /// JSON pointers redacted from logged patches and candidates (default: empty).
+    pub redact_paths: Vec<String>,
+    /// What the model is asked to return each attempt (default: [`RefinementMode::Patch`]).
+    pub mode: RefinementMode,
+    /// Patch format requested in [`RefinementMode::Patch`] (default: [`PatchFormat::JsonPatch`]).
+    pub patch_format: PatchFormat,
 }
 
 impl Default for RefinementConfig {
@@ -206,10 +365,38 @@ impl Default for RefinementConfig {
             network_retries: 3,
             fallback_strategy: FallbackStrategy::default(),
             validation_failure_strategy: ValidationFailureStrategy::default(),
+            return_best_effort: false,
+            redact_paths: Vec::new(),
+            mode: RefinementMode::default(),
+            patch_format: PatchFormat::default(),
         }
     }
 }
 
+/// What the refinement model returns on each attempt.
+#[derive(Clone, Debug, Default)]
+pub enum RefinementMode {
+    /// An RFC 6902 JSON Patch against the current value (default).
+    #[default]
+    Patch,
+    /// The complete corrected document. It is diffed locally against the current value,
+    /// so outcomes still carry a patch, and patch parsing and index shifts cannot fail.
+    FullDocument,
+}
+
+/// Patch format the model returns in [`RefinementMode::Patch`].
+#[derive(Clone, Debug, Default)]
+pub enum PatchFormat {
+    /// An RFC 6902 array of operations (default).
+    #[default]
+    JsonPatch,
+    /// An RFC 7386 merge document: changed fields only, `null` deletes a field. Easier
+    /// for the model when a few leaves change, but arrays can only be replaced whole.
+    /// It is applied with [`json_patch::merge`] and diffed back into a JSON Patch for the
+    /// outcome.
+    MergePatch,
+}
+
 /// Strategy for handling array modifications in patches.
 #[derive(Clone, Debug, Default)]
 pub enum ArrayPatchStrategy {
@@ -243,10 +430,14 @@ pub enum PatchStrategy {
 #[derive(Clone)]
 pub struct RefinementEngine {
     primary_client: Arc<Gemini>,
-    fallback_client: Option<Arc<Gemini>>,
+    /// One client per [`FallbackStrategy::tiers`] entry.
+    fallback_clients: Vec<Arc<Gemini>>,
     primary_generator: Option<Arc<dyn TextGenerator>>,
     fallback_generator: Option<Arc<dyn TextGenerator>>,
     config: RefinementConfig,
+    retry_classifier: Option<RetryClassifier>,
+    patch_progress: Option<mpsc::Sender<String>>,
+    on_attempt: Option<AttemptCallback>,
 }
 
 impl RefinementEngine {
@@ -256,10 +447,13 @@ impl RefinementEngine {
     pub fn new(primary_client: Arc<Gemini>, fallback_client: Option<Arc<Gemini>>) -> Self {
         Self {
             primary_client,
-            fallback_client,
+            fallback_clients: fallback_client.into_iter().collect(),
             primary_generator: None,
             fallback_generator: None,
             config: RefinementConfig::default(),
+            retry_classifier: None,
+            patch_progress: None,
+            on_attempt: None,
         }
     }
 
@@ -273,10 +467,13 @@ impl RefinementEngine {
     ) -> Self {
         Self {
             primary_client: Arc::new(Gemini::new("unused").expect("Unused client")),
-            fallback_client: None,
+            fallback_clients: Vec::new(),
             primary_generator: Some(primary),
             fallback_generator: fallback,
             config: RefinementConfig::default(),
+            retry_classifier: None,
+            patch_progress: None,
+            on_attempt: None,
         }
     }
 
@@ -290,6 +487,58 @@ impl RefinementEngine {
         self
     }
 
+    /// The configuration this engine runs with.
+    pub fn config(&self) -> &RefinementConfig {
+        &self.config
+    }
+
+    /// Use one client per escalation tier of a [`FallbackStrategy::Chain`], in
+    /// [`FallbackStrategy::tiers`] order.
+    pub fn with_fallback_clients(mut self, clients: Vec<Arc<Gemini>>) -> Self {
+        self.fallback_clients = clients;
+        self
+    }
+
+    /// Decide which network errors are retried; see [`RetryClassifier`].
+    pub fn with_retry_classifier(mut self, classifier: RetryClassifier) -> Self {
+        self.retry_classifier = Some(classifier);
+        self
+    }
+
+    /// Stream each patch response, forwarding raw text deltas to `sender` as they arrive.
+    ///
+    /// Purely for progress display: the full response is still buffered before it is
+    /// parsed, applied and validated. Deltas are dropped (with a trace log) when the
+    /// channel is full, and a retried request streams its deltas again. Generator-backed
+    /// engines send each response as a single delta.
+    pub fn with_patch_progress(mut self, sender: mpsc::Sender<String>) -> Self {
+        self.patch_progress = Some(sender);
+        self
+    }
+
+    /// Call `f` after every attempt, successful or not, with the attempt and its 1-based
+    /// index, e.g. to show "fixing validation error, attempt 2/3" while refinement runs.
+    pub fn on_attempt<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&RefinementAttempt, usize) + Send + Sync + 'static,
+    {
+        self.on_attempt = Some(Arc::new(f));
+        self
+    }
+
+    /// Record an attempt and report it to the `on_attempt` callback.
+    fn record_attempt(
+        &self,
+        attempts: &mut Vec<RefinementAttempt>,
+        attempt: RefinementAttempt,
+        attempt_idx: usize,
+    ) {
+        if let Some(callback) = &self.on_attempt {
+            callback(&attempt, attempt_idx);
+        }
+        attempts.push(attempt);
+    }
+
     pub fn with_max_retries(mut self, max_retries: usize) -> Self {
         self.config.max_retries = max_retries.max(1);
         self
@@ -300,11 +549,26 @@ impl RefinementEngine {
         self
     }
 
+    pub fn with_mode(mut self, mode: RefinementMode) -> Self {
+        self.config.mode = mode;
+        self
+    }
+
     pub fn with_array_strategy(mut self, strategy: ArrayPatchStrategy) -> Self {
         self.config.array_strategy = strategy;
         self
     }
 
+    pub fn with_patch_format(mut self, format: PatchFormat) -> Self {
+        self.config.patch_format = format;
+        self
+    }
+
+    fn uses_merge_patch(&self) -> bool {
+        matches!(self.config.mode, RefinementMode::Patch)
+            && matches!(self.config.patch_format, PatchFormat::MergePatch)
+    }
+
     /// Refine an existing value into a new one using JSON Patch (compat wrapper).
     pub async fn refine<T>(&self, current: &T, instruction: &str) -> Result<RefinementOutcome<T>>
     where
@@ -344,14 +608,24 @@ impl RefinementEngine {
         let original_instruction = instruction.clone();
         let mut attempts = Vec::new();
         let mut conversation: Vec<Message> = initial_history;
-        let mut escalated = false;
+        let mut escalated_tier: Option<usize> = None;
         let use_generator = self.uses_generators();
+        // Last value that passed schema validation but failed a logic/custom check.
+        let mut best_effort: Option<(T, String)> = None;
 
         let system_prompt = self.build_system_prompt();
-        let mut patch_schema = PatchResult::gemini_schema();
-        clean_schema_for_gemini(&mut patch_schema);
-        strip_x_fields(&mut patch_schema);
-        warn_if_schema_too_deep(&patch_schema, crate::schema::STRICT_SCHEMA_DEPTH_LIMIT);
+        // Merge documents are partial, so they get no response schema.
+        let patch_schema = match self.config.mode {
+            _ if self.uses_merge_patch() => None,
+            RefinementMode::Patch => Some(PatchResult::gemini_schema()),
+            RefinementMode::FullDocument => Some(schema.clone()),
+        };
+        let patch_schema = patch_schema.map(|mut patch_schema| {
+            clean_schema_for_gemini(&mut patch_schema);
+            strip_x_fields(&mut patch_schema);
+            warn_if_schema_too_deep(&patch_schema, crate::schema::STRICT_SCHEMA_DEPTH_LIMIT);
+            patch_schema
+        });
 
         debug!(
             "Starting refinement loop with {:?}",
@@ -367,7 +641,7 @@ impl RefinementEngine {
                 .unwrap_or_default();
 
             let prompt = format!(
-                "Current JSON:\n{}\n\nTarget schema:\n{}\n\n{}Instruction:\n{}\n\nReturn a JSON object with a 'patch' array:",
+                "Current JSON:\n{}\n\nTarget schema:\n{}\n\n{}Instruction:\n{}\n\n{}",
                 serde_json::to_string_pretty(&working)?,
                 serde_json::to_string_pretty(&schema)?,
                 if dynamic_context.is_empty() {
@@ -375,36 +649,45 @@ impl RefinementEngine {
                 } else {
                     format!("Additional context:\n{}\n\n", dynamic_context)
                 },
-                instruction
+                instruction,
+                match self.config.mode {
+                    _ if self.uses_merge_patch() => "Return a JSON Merge Patch object:",
+                    RefinementMode::Patch => "Return a JSON object with a 'patch' array:",
+                    RefinementMode::FullDocument => "Return the complete corrected JSON document:",
+                }
             );
 
             let patch_text: String = if use_generator {
                 let generator = self
-                    .select_generator(attempt_idx, &mut escalated)
+                    .select_generator(attempt_idx, &mut escalated_tier)
                     .ok_or_else(|| {
                         StructuredError::Config("No generator configured".to_string())
                     })?;
 
-                generator
+                let text = generator
                     .generate_text(
                         Some(&system_prompt),
                         &prompt,
                         GenerationConfig {
                             response_mime_type: Some("application/json".to_string()),
-                            response_json_schema: Some(patch_schema.clone()),
+                            response_json_schema: patch_schema.clone(),
                             response_schema: None,
                             temperature: Some(self.config.temperature),
                             ..Default::default()
                         },
                     )
-                    .await?
+                    .await?;
+                if let Some(progress) = &self.patch_progress {
+                    send_progress(progress, text.clone());
+                }
+                text
             } else {
                 // Determine which client to use based on escalation strategy
-                let active_client = self.select_client(attempt_idx, &mut escalated);
+                let active_client = self.select_client(attempt_idx, &mut escalated_tier);
 
-                let response = {
+                let patch_text = {
                     let mut last_err: Option<StructuredError> = None;
-                    let mut captured: Option<gemini_rust::GenerationResponse> = None;
+                    let mut captured: Option<String> = None;
 
                     for net_try in 0..=self.config.network_retries {
                         let mut builder = active_client
@@ -412,7 +695,7 @@ impl RefinementEngine {
                             .with_system_instruction(&system_prompt)
                             .with_generation_config(GenerationConfig {
                                 response_mime_type: Some("application/json".to_string()),
-                                response_json_schema: Some(patch_schema.clone()),
+                                response_json_schema: patch_schema.clone(),
                                 response_schema: None,
                                 temperature: Some(self.config.temperature),
                                 ..Default::default()
@@ -427,17 +710,23 @@ impl RefinementEngine {
                             content: Content::text(prompt.clone()).with_role(Role::User),
                         });
 
-                        match builder.execute().await {
-                            Ok(resp) => {
-                                captured = Some(resp);
+                        let result = match &self.patch_progress {
+                            Some(progress) => stream_patch_text(builder, progress).await,
+                            None => builder.execute().await.map(|resp| resp.text()),
+                        };
+                        match result {
+                            Ok(text) => {
+                                captured = Some(text);
                                 last_err = None;
                                 break;
                             }
                             Err(err) => {
-                                let structured = StructuredError::Gemini(err);
-                                if structured.is_retryable()
-                                    && net_try < self.config.network_retries
-                                {
+                                let structured = StructuredError::from_client_error(err);
+                                let retryable = match &self.retry_classifier {
+                                    Some(classifier) => classifier(&structured),
+                                    None => structured.is_retryable(),
+                                };
+                                if retryable && net_try < self.config.network_retries {
                                     // Use API-provided retry delay if available, otherwise exponential backoff
                                     let delay = structured
                                         .retry_delay()
@@ -471,9 +760,10 @@ impl RefinementEngine {
                     })?
                 };
 
-                let patch_text = response.text();
-
-                trace!(patch = %patch_text, "Received patch from model");
+                trace!(
+                    patch = %redact_patch_text(&patch_text, &self.config.redact_paths),
+                    "Received patch from model"
+                );
 
                 conversation.push(Message::model(patch_text.clone()));
 
@@ -488,41 +778,108 @@ impl RefinementEngine {
                 "Refinement iteration generated"
             );
 
-            let cleaned_patch = clean_patch_text(&patch_text);
-            let patch_result: PatchResult = match serde_json::from_str(cleaned_patch) {
-                Ok(p) => p,
-                Err(e) => {
-                    if let Ok(raw_ops) =
-                        serde_json::from_str::<Vec<PatchOperationSchema>>(cleaned_patch)
-                    {
-                        PatchResult { patch: raw_ops }
-                    } else {
-                        let msg = format!(
-                            "Model response was not valid JSON Patch: {e}; body={cleaned_patch}"
-                        );
-                        warn!(attempt = attempt_idx, error = %msg, "Invalid JSON Patch from model");
-                        attempts.push(RefinementAttempt::failure(patch_text.clone(), msg.clone()));
-                        conversation.push(Message::user(format!(
-                            "The patch could not be parsed: {msg}. Return a JSON object {{\"patch\": [...]}}.\n\n\
-                             REMINDER - Original Instruction: {original_instruction}\n\
-                             Fix the errors while ensuring the original instruction is still met."
-                        )));
-                        continue;
+            let (patch, next_value, patch_errors) = match self.config.mode {
+                _ if self.uses_merge_patch() => {
+                    match serde_json::from_str::<Value>(clean_patch_text(&patch_text)) {
+                        Ok(merge) if merge.is_object() => {
+                            let (patch, next_value) = apply_merge_patch(&working, &merge);
+                            (patch, next_value, Vec::new())
+                        }
+                        parsed => {
+                            let msg = match parsed {
+                                Ok(_) => "Merge patch must be a JSON object".to_string(),
+                                Err(e) => format!("Model response was not valid JSON: {e}"),
+                            };
+                            warn!(attempt = attempt_idx, error = %msg, "Invalid merge patch from model");
+                            self.record_attempt(
+                                &mut attempts,
+                                RefinementAttempt::failure(patch_text.clone(), msg.clone())
+                                    .with_failure_kind(RefinementFailureKind::PatchParse),
+                                attempt_idx,
+                            );
+                            conversation.push(Message::user(format!(
+                                "The merge patch could not be parsed: {msg}. Return a JSON object containing only the changed fields.\n\n\
+                                 REMINDER - Original Instruction: {original_instruction}"
+                            )));
+                            continue;
+                        }
                     }
                 }
-            };
+                RefinementMode::Patch => {
+                    let cleaned_patch = clean_patch_text(&patch_text);
+                    let patch_result: PatchResult = match serde_json::from_str(cleaned_patch) {
+                        Ok(p) => p,
+                        Err(e) => {
+                            if let Ok(raw_ops) =
+                                serde_json::from_str::<Vec<PatchOperationSchema>>(cleaned_patch)
+                            {
+                                PatchResult { patch: raw_ops }
+                            } else {
+                                let error = format!("Model response was not valid JSON Patch: {e}");
+                                // The body can carry sensitive values: log only its size at warn.
+                                warn!(
+                                    attempt = attempt_idx,
+                                    error = %error,
+                                    body_len = cleaned_patch.len(),
+                                    "Invalid JSON Patch from model"
+                                );
+                                trace!(
+                                    body = %redact_patch_text(cleaned_patch, &self.config.redact_paths),
+                                    "Unparseable JSON Patch body"
+                                );
+                                let msg = format!("{error}; body={cleaned_patch}");
+                                self.record_attempt(
+                                    &mut attempts,
+                                    RefinementAttempt::failure(patch_text.clone(), msg.clone())
+                                        .with_failure_kind(RefinementFailureKind::PatchParse),
+                                    attempt_idx,
+                                );
+                                conversation.push(Message::user(format!(
+                                    "The patch could not be parsed: {msg}. Return a JSON object {{\"patch\": [...]}}.\n\n\
+                                     REMINDER - Original Instruction: {original_instruction}\n\
+                                     Fix the errors while ensuring the original instruction is still met."
+                                )));
+                                continue;
+                            }
+                        }
+                    };
 
-            let ops_value = serde_json::to_value(patch_result.patch)?;
-            let mut patch: json_patch::Patch = serde_json::from_value(ops_value)?;
+                    let ops_value = serde_json::to_value(patch_result.patch)?;
+                    let mut patch: json_patch::Patch = serde_json::from_value(ops_value)?;
 
-            if matches!(
-                self.config.array_strategy,
-                ArrayPatchStrategy::ReorderRemovals
-            ) {
-                patch = self.reorder_removals(patch);
-            }
+                    if matches!(
+                        self.config.array_strategy,
+                        ArrayPatchStrategy::ReorderRemovals
+                    ) {
+                        patch = self.reorder_removals(patch);
+                    }
 
-            let (next_value, patch_errors) = self.apply_patches(&working, &patch);
+                    let (next_value, patch_errors) = self.apply_patches(&working, &patch);
+                    (patch, next_value, patch_errors)
+                }
+                RefinementMode::FullDocument => {
+                    match serde_json::from_str::<Value>(clean_patch_text(&patch_text)) {
+                        Ok(document) => {
+                            (json_patch::diff(&working, &document), document, Vec::new())
+                        }
+                        Err(e) => {
+                            let msg = format!("Model response was not valid JSON: {e}");
+                            warn!(attempt = attempt_idx, error = %msg, "Invalid JSON document from model");
+                            self.record_attempt(
+                                &mut attempts,
+                                RefinementAttempt::failure(patch_text.clone(), msg.clone())
+                                    .with_failure_kind(RefinementFailureKind::PatchParse),
+                                attempt_idx,
+                            );
+                            conversation.push(Message::user(format!(
+                                "The document could not be parsed: {msg}. Return the complete corrected JSON document.\n\n\
+                                 REMINDER - Original Instruction: {original_instruction}"
+                            )));
+                            continue;
+                        }
+                    }
+                }
+            };
 
             if !patch_errors.is_empty() {
                 // Log detailed patch errors to help debug invalid models
@@ -533,7 +890,12 @@ impl RefinementEngine {
                     errors = ?patch_errors,
                     "Patch application failed"
                 );
-                attempts.push(RefinementAttempt::failure(patch_text.clone(), msg.clone()));
+                self.record_attempt(
+                    &mut attempts,
+                    RefinementAttempt::failure(patch_text.clone(), msg.clone())
+                        .with_failure_kind(RefinementFailureKind::PatchApply),
+                    attempt_idx,
+                );
                 conversation.push(Message::user(format!(
                     "Some patch operations failed: {msg}.\n\n\
                      REMINDER - Original Instruction: {original_instruction}\n\
@@ -549,26 +911,30 @@ impl RefinementEngine {
             let mut candidate = next_value;
             Self::normalize_candidate_for_schema(&mut candidate, &schema);
 
-            if !validator.is_valid(&candidate) && !validator.is_valid(&candidate) {
-                let msg = validator
-                    .iter_errors(&candidate)
-                    .map(|e| e.to_string())
-                    .collect::<Vec<_>>()
-                    .join("; ");
-
+            if let Some(msg) = schema_violations(&validator, &candidate) {
                 warn!(
                     attempt = attempt_idx,
                     error = %msg,
-                    patch_text = %patch_text,
-                    candidate_json = %candidate,
+                    patch_text = %redact_patch_text(&patch_text, &self.config.redact_paths),
+                    candidate_json = %redacted_value(&candidate, &self.config.redact_paths),
                     "Patch resulted in invalid JSON schema"
                 );
 
-                attempts.push(RefinementAttempt::failure(patch_text.clone(), msg.clone()));
+                self.record_attempt(
+                    &mut attempts,
+                    RefinementAttempt::failure(patch_text.clone(), msg.clone())
+                        .with_failure_kind(RefinementFailureKind::Schema),
+                    attempt_idx,
+                );
                 conversation.push(Message::user(format!(
                     "Patch failed validation: {msg}.\n\n\
                      REMINDER - Original Instruction: {original_instruction}\n\
-                     Return a corrected JSON Patch while keeping the instruction in mind."
+                     {} while keeping the instruction in mind.",
+                    match self.config.mode {
+                        _ if self.uses_merge_patch() => "Return a corrected merge patch",
+                        RefinementMode::Patch => "Return a corrected JSON Patch",
+                        RefinementMode::FullDocument => "Return the corrected full document",
+                    }
                 )));
                 match self.config.validation_failure_strategy {
                     ValidationFailureStrategy::IterateForward => working = candidate,
@@ -589,11 +955,14 @@ impl RefinementEngine {
                     error = %logic_err,
                     "Patch passed schema but failed logic validation"
                 );
+                best_effort = Some((value.clone(), logic_err.clone()));
 
-                attempts.push(RefinementAttempt::failure(
-                    patch_text.clone(),
-                    logic_err.clone(),
-                ));
+                self.record_attempt(
+                    &mut attempts,
+                    RefinementAttempt::failure(patch_text.clone(), logic_err.clone())
+                        .with_failure_kind(RefinementFailureKind::Logic),
+                    attempt_idx,
+                );
                 conversation.push(Message::user(format!(
                     "JSON is valid, but logic failed: {logic_err}.\n\n\
                      REMINDER - Original Instruction: {original_instruction}\n\
@@ -620,11 +989,14 @@ impl RefinementEngine {
                         error = %ctx_err,
                         "Context validation failed"
                     );
+                    best_effort = Some((value.clone(), ctx_err.clone()));
 
-                    attempts.push(RefinementAttempt::failure(
-                        patch_text.clone(),
-                        ctx_err.clone(),
-                    ));
+                    self.record_attempt(
+                        &mut attempts,
+                        RefinementAttempt::failure(patch_text.clone(), ctx_err.clone())
+                            .with_failure_kind(RefinementFailureKind::Custom),
+                        attempt_idx,
+                    );
                     conversation.push(Message::user(format!(
                         "The data structure is valid, but it violates external constraints: {ctx_err}.\n\n\
                          REMINDER - Original Instruction: {original_instruction}\n\
@@ -652,11 +1024,14 @@ impl RefinementEngine {
                         error = %async_err,
                         "Async context validation failed"
                     );
+                    best_effort = Some((value.clone(), async_err.clone()));
 
-                    attempts.push(RefinementAttempt::failure(
-                        patch_text.clone(),
-                        async_err.clone(),
-                    ));
+                    self.record_attempt(
+                        &mut attempts,
+                        RefinementAttempt::failure(patch_text.clone(), async_err.clone())
+                            .with_failure_kind(RefinementFailureKind::Async),
+                        attempt_idx,
+                    );
                     conversation.push(Message::user(format!(
                         "The configuration structure is valid, but the simulation/async check failed: {async_err}.\n\n\
                          REMINDER - Original Instruction: {original_instruction}\n\
@@ -678,7 +1053,11 @@ impl RefinementEngine {
             }
 
             debug!("Refinement successful on attempt {}", attempt_idx);
-            attempts.push(RefinementAttempt::success(patch_text));
+            self.record_attempt(
+                &mut attempts,
+                RefinementAttempt::success(patch_text),
+                attempt_idx,
+            );
             let applied_patch = patch.clone();
             info!(
                 target: "gemini_refine",
@@ -693,12 +1072,39 @@ impl RefinementEngine {
             ));
         }
 
+        let last_error = attempts
+            .last()
+            .and_then(|a| a.error.clone())
+            .unwrap_or_else(|| "unknown error".to_string());
+
+        if self.config.return_best_effort {
+            if let Some((value, error)) = best_effort {
+                warn!(
+                    target: "gemini_refine",
+                    retries = self.config.max_retries,
+                    "Refinement exhausted; returning best-effort value"
+                );
+                // Later attempts may have failed for other reasons on other candidates, so
+                // re-check the returned value itself.
+                let mut remaining_errors: Vec<String> = value.validate().into_iter().collect();
+                remaining_errors.extend(custom_validator.and_then(|validator| validator(&value)));
+                if let Some(validator) = async_custom_validator {
+                    remaining_errors.extend(validator(&value).await);
+                }
+                if remaining_errors.is_empty() {
+                    remaining_errors.push(error);
+                }
+                return Ok(RefinementOutcome::best_effort(
+                    value,
+                    attempts,
+                    remaining_errors,
+                ));
+            }
+        }
+
         Err(StructuredError::RefinementExhausted {
             retries: self.config.max_retries,
-            last_error: attempts
-                .last()
-                .and_then(|a| a.error.clone())
-                .unwrap_or_else(|| "unknown error".to_string()),
+            last_error,
         })
     }
 
@@ -709,6 +1115,89 @@ impl RefinementEngine {
         recover_internally_tagged_enums(candidate, schema);
     }
 
+    /// Append items to arrays with a locally built patch, then validate the result.
+    pub(crate) async fn apply_appends<T>(
+        &self,
+        current: &T,
+        appends: &[(String, Vec<Value>)],
+        custom_validator: Option<&CustomValidator<T>>,
+        async_custom_validator: Option<&AsyncCustomValidator<T>>,
+    ) -> Result<(T, json_patch::Patch)>
+    where
+        T: GeminiStructured + StructuredValidator + Serialize + DeserializeOwned,
+    {
+        let mut working = serde_json::to_value(current)?;
+        let mut ops = Vec::new();
+
+        for (pointer, items) in appends {
+            let existing = match working.pointer(pointer) {
+                Some(Value::Array(existing)) => existing,
+                Some(_) => {
+                    return Err(StructuredError::Validation(format!(
+                        "append target {pointer} is not an array"
+                    )))
+                }
+                None => {
+                    return Err(StructuredError::Validation(format!(
+                        "append target {pointer} does not exist"
+                    )))
+                }
+            };
+
+            let step_ops: Vec<Value> = match self.config.array_strategy {
+                ArrayPatchStrategy::ReplaceWhole => {
+                    let mut combined = existing.clone();
+                    combined.extend(items.iter().cloned());
+                    vec![serde_json::json!({"op": "replace", "path": pointer, "value": combined})]
+                }
+                _ => items
+                    .iter()
+                    .map(|item| {
+                        serde_json::json!({
+                            "op": "add",
+                            "path": format!("{pointer}/-"),
+                            "value": item,
+                        })
+                    })
+                    .collect(),
+            };
+            let step: json_patch::Patch = serde_json::from_value(Value::Array(step_ops))?;
+            json_patch::patch(&mut working, &step).map_err(|e| {
+                StructuredError::Validation(format!("failed to append to {pointer}: {e}"))
+            })?;
+            ops.extend(step.0);
+        }
+
+        let validator = compile_validator::<T>()?;
+        if !validator.is_valid(&working) {
+            let msg = validator
+                .iter_errors(&working)
+                .map(|e| e.to_string())
+                .collect::<Vec<_>>()
+                .join("; ");
+            return Err(StructuredError::Validation(format!(
+                "appended value does not match schema: {msg}"
+            )));
+        }
+
+        let value: T = serde_json::from_value(working)?;
+        let error = match value.validate() {
+            Some(err) => Some(err),
+            None => custom_validator.and_then(|validator| validator(&value)),
+        };
+        let error = match (error, async_custom_validator) {
+            (None, Some(validator)) => validator(&value).await,
+            (error, _) => error,
+        };
+        if let Some(err) = error {
+            return Err(StructuredError::Validation(format!(
+                "appended value failed validation: {err}"
+            )));
+        }
+
+        Ok((value, json_patch::Patch(ops)))
+    }
+
     fn apply_patches(&self, original: &Value, patch: &json_patch::Patch) -> (Value, Vec<String>) {
         match self.config.patch_strategy {
             PatchStrategy::Atomic => {
@@ -750,6 +1239,24 @@ impl RefinementEngine {
     }
 
     fn build_system_prompt(&self) -> String {
+        if self.uses_merge_patch() {
+            return "You are a JSON Merge Patch generator. Given the current JSON value and the \
+                    target schema, return an RFC 7386 merge patch object that transforms the current \
+                    value to satisfy the instruction and schema.\n\n\
+                    CRITICAL RULES:\n\
+                    1. Do not wrap output in code fences (```json).\n\
+                    2. Include only fields that change; nest objects to reach nested fields.\n\
+                    3. Set a field to null to delete it.\n\
+                    4. Arrays are replaced whole: return the complete new array."
+                .to_string();
+        }
+        if matches!(self.config.mode, RefinementMode::FullDocument) {
+            return "You are a JSON editor. Given the current JSON value and the target schema, \
+                    return the complete JSON document after applying the instruction, including \
+                    every field that does not change. Do not wrap output in code fences (```json)."
+                .to_string();
+        }
+
         let base = "You are a JSON Patch generator. Given the current JSON value and the target schema, \
                     return a JSON object with a 'patch' key containing an array of valid RFC6902 \
                     operations that transforms the current value to satisfy the instruction and schema.\n\n\
@@ -776,24 +1283,31 @@ impl RefinementEngine {
     }
 
     /// Select the appropriate client based on the escalation strategy.
-    fn select_client(&self, attempt_idx: usize, escalated: &mut bool) -> &Arc<Gemini> {
-        match &self.config.fallback_strategy {
-            FallbackStrategy::Escalate {
-                after_attempts,
-                target: _,
-            } if attempt_idx > *after_attempts && self.fallback_client.is_some() => {
-                if !*escalated {
-                    info!(
-                        attempt = attempt_idx,
-                        after_attempts = after_attempts,
-                        "Escalating refinement to fallback model"
-                    );
-                    *escalated = true;
-                }
-                self.fallback_client.as_ref().unwrap()
-            }
-            _ => &self.primary_client,
+    ///
+    /// Picks the highest tier whose threshold `attempt_idx` exceeds, logging each step up.
+    fn select_client(
+        &self,
+        attempt_idx: usize,
+        escalated_tier: &mut Option<usize>,
+    ) -> &Arc<Gemini> {
+        let Some(tier) = self.config.fallback_strategy.active_tier(attempt_idx) else {
+            return &self.primary_client;
+        };
+        let Some(client) = self.fallback_clients.get(tier) else {
+            return &self.primary_client;
+        };
+        if Some(tier) > *escalated_tier {
+            let (after_attempts, model) = &self.config.fallback_strategy.tiers()[tier];
+            info!(
+                attempt = attempt_idx,
+                after_attempts = after_attempts,
+                tier = tier + 1,
+                model = model.as_str(),
+                "Escalating refinement to fallback model"
+            );
+            *escalated_tier = Some(tier);
         }
+        client
     }
 
     /// Select the appropriate generator based on the escalation strategy.
@@ -802,22 +1316,20 @@ impl RefinementEngine {
     fn select_generator(
         &self,
         attempt_idx: usize,
-        escalated: &mut bool,
+        escalated_tier: &mut Option<usize>,
     ) -> Option<&Arc<dyn TextGenerator>> {
         let primary = self.primary_generator.as_ref()?;
 
-        match &self.config.fallback_strategy {
-            FallbackStrategy::Escalate {
-                after_attempts,
-                target: _,
-            } if attempt_idx > *after_attempts && self.fallback_generator.is_some() => {
-                if !*escalated {
+        // Generators have a single fallback, shared by every tier.
+        match self.config.fallback_strategy.active_tier(attempt_idx) {
+            Some(tier) if self.fallback_generator.is_some() => {
+                if Some(tier) > *escalated_tier {
                     info!(
                         attempt = attempt_idx,
-                        after_attempts = after_attempts,
+                        tier = tier + 1,
                         "Escalating refinement to fallback generator"
                     );
-                    *escalated = true;
+                    *escalated_tier = Some(tier);
                 }
                 self.fallback_generator.as_ref()
             }
@@ -853,6 +1365,53 @@ impl RefinementEngine {
     }
 }
 
+/// Stream a response, forwarding each text delta to `progress`, and return the full text.
+async fn stream_patch_text(
+    builder: ContentBuilder,
+    progress: &mpsc::Sender<String>,
+) -> std::result::Result<String, gemini_rust::ClientError> {
+    let mut stream = builder.execute_stream().await?;
+    let mut text = String::new();
+    while let Some(chunk) = stream.next().await {
+        let delta = chunk?.text();
+        if !delta.is_empty() {
+            text.push_str(&delta);
+            send_progress(progress, delta);
+        }
+    }
+    Ok(text)
+}
+
+/// Schema check applied to each refinement candidate.
+trait CandidateSchema {
+    fn errors(&self, candidate: &Value) -> Vec<String>;
+}
+
+impl CandidateSchema for jsonschema::Validator {
+    fn errors(&self, candidate: &Value) -> Vec<String> {
+        self.iter_errors(candidate).map(|e| e.to_string()).collect()
+    }
+}
+
+/// Validate `candidate` in a single pass, returning the joined errors when it is invalid.
+fn schema_violations(schema: &impl CandidateSchema, candidate: &Value) -> Option<String> {
+    let errors = schema.errors(candidate);
+    (!errors.is_empty()).then(|| errors.join("; "))
+}
+
+/// Apply an RFC 7386 merge patch, returning the equivalent JSON Patch and the result.
+fn apply_merge_patch(original: &Value, merge: &Value) -> (json_patch::Patch, Value) {
+    let mut merged = original.clone();
+    json_patch::merge(&mut merged, merge);
+    (json_patch::diff(original, &merged), merged)
+}
+
+fn send_progress(progress: &mpsc::Sender<String>, delta: String) {
+    if progress.try_send(delta).is_err() {
+        trace!("Dropped refinement progress delta");
+    }
+}
+
 fn clean_patch_text(patch_text: &str) -> &str {
     let trimmed = patch_text.trim();
     if let Some(start) = trimmed.find('{') {
@@ -1127,4 +1686,334 @@ mod tests {
             }
         );
     }
+
+    #[tokio::test]
+    async fn memoized_async_validator_skips_unchanged_values() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let validator: AsyncCustomValidator<TestItem> = Box::new(move |item: &TestItem| {
+            counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+            let negative = item.value < 0.0;
+            Box::pin(async move { negative.then(|| "value must be non-negative".to_string()) })
+        });
+        let cached = memoize_async(validator);
+
+        let item = TestItem {
+            id: 1,
+            name: "a".to_string(),
+            value: -1.0,
+        };
+        assert!(cached(&item).await.is_some());
+        assert!(cached(&item.clone()).await.is_some());
+
+        let fixed = TestItem { value: 2.0, ..item };
+        assert!(cached(&fixed).await.is_none());
+
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 2);
+    }
+
+    #[tokio::test]
+    async fn test_apply_appends_builds_patch_locally() {
+        let engine = RefinementEngine::new(Arc::new(Gemini::new("test").unwrap()), None);
+        let container = TestContainer {
+            items: vec![TestItem {
+                id: 1,
+                name: "first".to_string(),
+                value: 1.0,
+            }],
+            total: 1.0,
+        };
+
+        let (appended, patch) = engine
+            .apply_appends(
+                &container,
+                &[(
+                    "/items".to_string(),
+                    vec![json!({"id": 2, "name": "second", "value": 2.0})],
+                )],
+                None,
+                None,
+            )
+            .await
+            .unwrap();
+
+        assert_eq!(appended.items.len(), 2);
+        assert_eq!(appended.items[1].name, "second");
+        assert_eq!(patch.0.len(), 1);
+        assert!(matches!(patch.0[0], json_patch::PatchOperation::Replace(_)));
+
+        let err = engine
+            .apply_appends(
+                &container,
+                &[("/total".to_string(), vec![json!(1)])],
+                None,
+                None,
+            )
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("not an array"));
+    }
+
+    struct DocumentGenerator;
+
+    #[async_trait::async_trait]
+    impl TextGenerator for DocumentGenerator {
+        async fn generate_text(
+            &self,
+            system: Option<&str>,
+            prompt: &str,
+            _config: GenerationConfig,
+        ) -> Result<String> {
+            assert!(system
+                .unwrap_or_default()
+                .contains("complete JSON document"));
+            assert!(prompt.ends_with("Return the complete corrected JSON document:"));
+            Ok(r#"```json
+{"items": [{"id": 1, "name": "first", "value": 2.5}], "total": 2.5}
+```"#
+                .to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn full_document_mode_diffs_returned_document_into_patch() {
+        let engine = RefinementEngine::from_generators(Arc::new(DocumentGenerator), None)
+            .with_mode(RefinementMode::FullDocument);
+        let container = TestContainer {
+            items: vec![TestItem {
+                id: 1,
+                name: "first".to_string(),
+                value: 1.0,
+            }],
+            total: 1.0,
+        };
+
+        let outcome = engine
+            .refine(&container, "Set the first value to 2.5")
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.value.total, 2.5);
+        assert_eq!(outcome.value.items[0].value, 2.5);
+        let patch = outcome.patch.expect("patch diffed from document");
+        assert_eq!(patch.0.len(), 2);
+        assert!(patch
+            .0
+            .iter()
+            .all(|op| matches!(op, json_patch::PatchOperation::Replace(_))));
+    }
+
+    #[tokio::test]
+    async fn patch_progress_receives_generated_text() {
+        let (sender, mut receiver) = mpsc::channel(8);
+        let engine = RefinementEngine::from_generators(Arc::new(DocumentGenerator), None)
+            .with_mode(RefinementMode::FullDocument)
+            .with_patch_progress(sender);
+        let container = TestContainer {
+            items: vec![TestItem {
+                id: 1,
+                name: "first".to_string(),
+                value: 1.0,
+            }],
+            total: 1.0,
+        };
+
+        engine
+            .refine(&container, "Set the first value to 2.5")
+            .await
+            .unwrap();
+
+        let delta = receiver.try_recv().unwrap();
+        assert!(delta.contains("\"total\": 2.5"));
+        assert!(receiver.try_recv().is_err());
+    }
+
+    #[test]
+    fn schema_violations_checks_each_candidate_once() {
+        struct Counting {
+            inner: jsonschema::Validator,
+            calls: std::cell::Cell<usize>,
+        }
+
+        impl CandidateSchema for Counting {
+            fn errors(&self, candidate: &Value) -> Vec<String> {
+                self.calls.set(self.calls.get() + 1);
+                self.inner.errors(candidate)
+            }
+        }
+
+        let schema = Counting {
+            inner: jsonschema::validator_for(&json!({
+                "type": "object",
+                "properties": { "count": { "type": "integer" } },
+                "required": ["count"]
+            }))
+            .unwrap(),
+            calls: std::cell::Cell::new(0),
+        };
+
+        assert!(schema_violations(&schema, &json!({"count": 3})).is_none());
+        assert_eq!(schema.calls.get(), 1);
+
+        let msg = schema_violations(&schema, &json!({"count": "three"})).unwrap();
+        assert!(msg.contains("integer"));
+        assert_eq!(schema.calls.get(), 2);
+    }
+
+    struct ScriptedGenerator(Mutex<Vec<&'static str>>);
+
+    #[async_trait::async_trait]
+    impl TextGenerator for ScriptedGenerator {
+        async fn generate_text(
+            &self,
+            _system: Option<&str>,
+            _prompt: &str,
+            _config: GenerationConfig,
+        ) -> Result<String> {
+            Ok(self.0.lock().unwrap().remove(0).to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn on_attempt_reports_failures_and_success_in_order() {
+        let seen = Arc::new(Mutex::new(Vec::new()));
+        let recorder = Arc::clone(&seen);
+        let generator = ScriptedGenerator(Mutex::new(vec![
+            "not a patch",
+            r#"{"patch": [{"op": "replace", "path": "/total", "value": 3.0}]}"#,
+        ]));
+        let record = move |attempt: &RefinementAttempt, index: usize| {
+            recorder.lock().unwrap().push((index, attempt.success));
+        };
+        let engine =
+            RefinementEngine::from_generators(Arc::new(generator), None).on_attempt(record);
+        let container = TestContainer {
+            items: Vec::new(),
+            total: 1.0,
+        };
+
+        let outcome = engine.refine(&container, "Set total to 3").await.unwrap();
+
+        assert_eq!(outcome.value.total, 3.0);
+        assert_eq!(*seen.lock().unwrap(), vec![(1, false), (2, true)]);
+    }
+
+    const RAISE_TO_FIVE: &str = r#"{"patch": [{"op": "replace", "path": "/total", "value": 5.0}]}"#;
+    const RAISE_TO_SIX: &str = r#"{"patch": [{"op": "replace", "path": "/total", "value": 6.0}]}"#;
+
+    /// Refine `total` with two scripted responses under a `total <= 4` constraint.
+    async fn refine_past_constraint(
+        best_effort: bool,
+        responses: [&'static str; 2],
+    ) -> Result<RefinementOutcome<TestContainer>> {
+        let generator = ScriptedGenerator(Mutex::new(responses.to_vec()));
+        let engine = RefinementEngine::from_generators(Arc::new(generator), None).with_config(
+            RefinementConfig {
+                max_retries: 2,
+                return_best_effort: best_effort,
+                ..Default::default()
+            },
+        );
+        let container = TestContainer {
+            items: Vec::new(),
+            total: 1.0,
+        };
+        let at_most_four: CustomValidator<TestContainer> = Box::new(|value: &TestContainer| {
+            (value.total > 4.0).then(|| "total must be at most 4".to_string())
+        });
+
+        engine
+            .execute_refinement(
+                container,
+                "Raise the total".to_string(),
+                Vec::new(),
+                None,
+                Some(&at_most_four),
+                None,
+            )
+            .await
+    }
+
+    #[tokio::test]
+    async fn exhausted_refinement_returns_best_effort_value() {
+        let outcome = refine_past_constraint(true, [RAISE_TO_FIVE, RAISE_TO_SIX])
+            .await
+            .unwrap();
+
+        assert!(outcome.best_effort);
+        assert_eq!(outcome.value.total, 6.0);
+        assert_eq!(outcome.remaining_errors, vec!["total must be at most 4"]);
+        assert_eq!(outcome.attempts.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn best_effort_errors_ignore_later_rejected_candidates() {
+        let outcome = refine_past_constraint(true, [RAISE_TO_FIVE, "not a patch"])
+            .await
+            .unwrap();
+
+        assert!(outcome.best_effort);
+        assert_eq!(outcome.value.total, 5.0);
+        assert_eq!(outcome.remaining_errors, vec!["total must be at most 4"]);
+    }
+
+    #[tokio::test]
+    async fn exhausted_refinement_errors_without_best_effort() {
+        let err = refine_past_constraint(false, [RAISE_TO_FIVE, RAISE_TO_SIX])
+            .await
+            .unwrap_err();
+
+        assert!(matches!(
+            err,
+            StructuredError::RefinementExhausted { retries: 2, .. }
+        ));
+    }
+
+    #[test]
+    fn merge_patch_merges_nested_objects_and_deletes_nulls() {
+        let original = json!({
+            "customer": {"name": "Acme", "address": {"city": "Oslo", "zip": "0150"}},
+            "notes": "call back",
+            "tags": ["a", "b"]
+        });
+        let merge = json!({
+            "customer": {"address": {"city": "Bergen"}},
+            "notes": null,
+            "tags": ["c"]
+        });
+
+        let (patch, merged) = apply_merge_patch(&original, &merge);
+
+        assert_eq!(
+            merged,
+            json!({
+                "customer": {"name": "Acme", "address": {"city": "Bergen", "zip": "0150"}},
+                "tags": ["c"]
+            })
+        );
+        let mut replayed = original.clone();
+        json_patch::patch(&mut replayed, &patch).unwrap();
+        assert_eq!(replayed, merged);
+    }
+
+    #[tokio::test]
+    async fn merge_patch_format_refines_changed_fields_only() {
+        let generator = ScriptedGenerator(Mutex::new(vec![r#"{"total": 4.5}"#]));
+        let engine = RefinementEngine::from_generators(Arc::new(generator), None)
+            .with_patch_format(PatchFormat::MergePatch);
+        let container = TestContainer {
+            items: vec![TestItem {
+                id: 1,
+                name: "first".to_string(),
+                value: 1.0,
+            }],
+            total: 1.0,
+        };
+
+        let outcome = engine.refine(&container, "Set total to 4.5").await.unwrap();
+
+        assert_eq!(outcome.value.total, 4.5);
+        assert_eq!(outcome.value.items, container.items);
+        assert_eq!(outcome.patch.unwrap().0.len(), 1);
+    }
 }
@@ -0,0 +1,183 @@
+//! Redaction of sensitive JSON fields before they reach log output.
+//!
+//! Paths are JSON pointers (`/contact/email`). A `*` segment matches every key or
+//! array element at that level, so `/contacts/*/ssn` covers each contact.
+
+use serde_json::Value;
+
+/// Placeholder written in place of redacted values.
+pub(crate) const REDACTED: &str = "[REDACTED]";
+
+/// Redact `paths` in a model response or other JSON text for logging.
+///
+/// Text that cannot be parsed as JSON is replaced entirely when any paths are set,
+/// since there is no way to tell which parts are sensitive.
+pub(crate) fn redact_json_text(text: &str, paths: &[String]) -> String {
+    if paths.is_empty() {
+        return text.to_string();
+    }
+    match parse_lenient(text) {
+        Some(mut value) => {
+            redact_value(&mut value, paths);
+            value.to_string()
+        }
+        None => unparseable(text),
+    }
+}
+
+/// Redact a JSON value in place.
+pub(crate) fn redact_value(value: &mut Value, paths: &[String]) {
+    for path in paths {
+        redact_segments(value, &segments(path));
+    }
+}
+
+/// Redact a copy of `value`, leaving the original untouched.
+pub(crate) fn redacted_value(value: &Value, paths: &[String]) -> Value {
+    let mut copy = value.clone();
+    redact_value(&mut copy, paths);
+    copy
+}
+
+/// Redact the `value` of JSON Patch operations that write to a sensitive path.
+///
+/// Operations on a parent of a sensitive path have the matching field inside their
+/// value redacted; operations at or below a sensitive path lose their value entirely.
+pub(crate) fn redact_patch_text(text: &str, paths: &[String]) -> String {
+    if paths.is_empty() {
+        return text.to_string();
+    }
+    let Some(mut doc) = parse_lenient(text) else {
+        return unparseable(text);
+    };
+    let ops = match &mut doc {
+        Value::Object(map) => map.get_mut("patch").and_then(Value::as_array_mut),
+        Value::Array(ops) => Some(ops),
+        _ => None,
+    };
+    for op in ops.into_iter().flatten() {
+        let Some(op_path) = op.get("path").and_then(Value::as_str).map(segments) else {
+            continue;
+        };
+        let Some(op_value) = op.get_mut("value") else {
+            continue;
+        };
+        for path in paths {
+            let pattern = segments(path);
+            if pattern.len() <= op_path.len() && prefix_matches(&pattern, &op_path) {
+                *op_value = Value::String(REDACTED.to_string());
+                break;
+            }
+            if prefix_matches(&op_path, &pattern) {
+                redact_segments(op_value, &pattern[op_path.len()..]);
+            }
+        }
+    }
+    doc.to_string()
+}
+
+fn parse_lenient(text: &str) -> Option<Value> {
+    serde_json::from_str(text)
+        .ok()
+        .or_else(|| serde_json::from_str(&crate::request::clean_json_text(text)).ok())
+}
+
+fn unparseable(text: &str) -> String {
+    format!("{REDACTED} ({} bytes of unparseable text)", text.len())
+}
+
+fn segments(pointer: &str) -> Vec<String> {
+    pointer
+        .split('/')
+        .skip(1)
+        .map(|segment| segment.replace("~1", "/").replace("~0", "~"))
+        .collect()
+}
+
+/// Whether `prefix` matches the start of `path`, treating `*` in either as a wildcard.
+fn prefix_matches(prefix: &[String], path: &[String]) -> bool {
+    prefix.len() <= path.len()
+        && prefix
+            .iter()
+            .zip(path)
+            .all(|(a, b)| a == "*" || b == "*" || a == b)
+}
+
+fn redact_segments(value: &mut Value, segments: &[String]) {
+    let Some((head, rest)) = segments.split_first() else {
+        *value = Value::String(REDACTED.to_string());
+        return;
+    };
+    match value {
+        Value::Object(map) if head == "*" => {
+            for child in map.values_mut() {
+                redact_segments(child, rest);
+            }
+        }
+        Value::Object(map) => {
+            if let Some(child) = map.get_mut(head) {
+                redact_segments(child, rest);
+            }
+        }
+        Value::Array(items) if head == "*" => {
+            for child in items {
+                redact_segments(child, rest);
+            }
+        }
+        Value::Array(items) => {
+            if let Some(child) = head.parse::<usize>().ok().and_then(|i| items.get_mut(i)) {
+                redact_segments(child, rest);
+            }
+        }
+        _ => {}
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn paths(list: &[&str]) -> Vec<String> {
+        list.iter().map(|p| p.to_string()).collect()
+    }
+
+    #[test]
+    fn redacts_pointers_and_wildcards() {
+        let text = r#"```json
+{"name": "Ada", "ssn": "123-45-6789", "contacts": [{"email": "a@x.io"}, {"email": "b@x.io"}]}
+```"#;
+
+        let redacted = redact_json_text(text, &paths(&["/ssn", "/contacts/*/email"]));
+        let value: Value = serde_json::from_str(&redacted).unwrap();
+
+        assert_eq!(value["name"], "Ada");
+        assert_eq!(value["ssn"], REDACTED);
+        assert_eq!(value["contacts"][0]["email"], REDACTED);
+        assert_eq!(value["contacts"][1]["email"], REDACTED);
+        assert_eq!(
+            redact_json_text("not json", &paths(&["/ssn"])),
+            unparseable("not json")
+        );
+    }
+
+    #[test]
+    fn redacts_patch_values_touching_sensitive_paths() {
+        let patch = json!({
+            "patch": [
+                {"op": "replace", "path": "/ssn", "value": "987-65-4321"},
+                {"op": "add", "path": "/contacts/0", "value": {"email": "c@x.io", "name": "C"}},
+                {"op": "replace", "path": "/name", "value": "Grace"}
+            ]
+        })
+        .to_string();
+
+        let redacted = redact_patch_text(&patch, &paths(&["/ssn", "/contacts/*/email"]));
+        let value: Value = serde_json::from_str(&redacted).unwrap();
+
+        assert_eq!(value["patch"][0]["value"], REDACTED);
+        assert_eq!(value["patch"][1]["value"]["email"], REDACTED);
+        assert_eq!(value["patch"][1]["value"]["name"], "C");
+        assert_eq!(value["patch"][2]["value"], "Grace");
+    }
+}
@@ -1,30 +1,40 @@
+use std::collections::{BTreeMap, HashMap};
 use std::marker::PhantomData;
 use std::path::Path;
+use std::sync::Arc;
 use std::time::Duration;
 
+use base64::{engine::general_purpose::STANDARD as BASE64, Engine};
 use futures::{
     stream::{self, BoxStream},
     StreamExt,
 };
 use gemini_rust::{
-    generation::model::UsageMetadata, Content, FileData, FileHandle, GenerationConfig, Message,
-    Part, Role, SafetySetting, Tool,
+    generation::model::UsageMetadata, Blob, Content, FileData, FileHandle, Gemini,
+    GenerationConfig, Message, Model, Part, Role, SafetySetting, Tool,
 };
 use serde::de::DeserializeOwned;
 use serde::Serialize;
 use serde_json::Value;
+use sha2::{Digest, Sha256};
+use tokio_util::sync::CancellationToken;
 use tracing::{debug, info, instrument, trace, warn};
 
 use crate::{
-    caching::CacheSettings,
-    client::{BuilderOptions, MockRequest, ResponseHook},
+    caching::{CacheSettings, Flight},
+    client::{BuilderOptions, MockHandler, MockRequest, ResponseHook},
     error::StructuredError,
-    models::GenerationOutcome,
-    schema::{compile_validator, GeminiStructured},
-    tools::ToolRegistry,
+    files::DocumentBundle,
+    models::{GenerationOutcome, MediaPart, ToolInvocation},
+    redaction::redact_json_text,
+    schema::{compile_validator, GeminiStructured, SchemaTransform},
+    tools::{function_names, ToolContext, ToolRegistry},
     Result, StructuredClient, StructuredValidator,
 };
 
+/// Normalization applied to a parsed value before refinement.
+type PostProcess<T> = Arc<dyn Fn(T) -> T + Send + Sync>;
+
 /// Fluent builder for structured requests targeting a specific output type.
 pub struct StructuredRequest<'a, T> {
     client: &'a StructuredClient,
@@ -32,13 +42,37 @@ pub struct StructuredRequest<'a, T> {
     system_instruction: Option<String>,
     tools: Vec<Tool>,
     tool_registry: Option<ToolRegistry>,
+    tool_context: Option<ToolContext>,
+    use_default_tools: bool,
     config: GenerationConfig,
     cache_settings: Option<CacheSettings>,
     safety_settings: Option<Vec<SafetySetting>>,
     refinement_instruction: Option<String>,
+    post_process: Option<PostProcess<T>>,
     max_tool_steps: usize,
+    max_tool_concurrency: usize,
+    keep_tools_on_retry: bool,
     max_parse_attempts: usize,
     retry_count: usize,
+    model_override: Option<Model>,
+    capture_media: bool,
+    capture_raw: bool,
+    capture_tool_results: bool,
+    normalize_enum_case: bool,
+    field_defaults: HashMap<String, Value>,
+    reject_empty_strings: bool,
+    whitespace_is_empty: bool,
+    max_continuations: usize,
+    empty_response_prompt: Option<String>,
+    escalate_on_empty: bool,
+    temperature_ramp: Option<(f32, f32)>,
+    idempotency_key: Option<String>,
+    correlation_id: Option<String>,
+    locale: Option<String>,
+    timeout: Option<Duration>,
+    cancellation: Option<CancellationToken>,
+    partial_parsing: bool,
+    schema_transforms: Vec<Box<dyn SchemaTransform>>,
     _marker: PhantomData<T>,
 }
 
@@ -47,10 +81,25 @@ pub struct StructuredRequest<'a, T> {
 pub enum StreamEvent<T> {
     /// A raw text chunk from the model (not yet parsed or validated).
     Chunk(String),
+    /// Best-effort parse of the text received so far (see
+    /// [`StructuredRequest::with_partial_parsing`]).
+    Partial(T),
+    /// The streamed value parsed and is now being refined (see
+    /// [`StructuredRequest::refine_with`]); `Complete` follows with the refined value.
+    Refining,
     /// Final structured output once streaming has completed.
     Complete(GenerationOutcome<T>),
 }
 
+/// Events emitted by [`StructuredRequest::stream_array_items`].
+#[derive(Debug)]
+pub enum ArrayStreamEvent<T, I> {
+    /// A complete element of the streamed top-level array.
+    Item(I),
+    /// An event from the underlying [`stream`](StructuredRequest::stream).
+    Stream(StreamEvent<T>),
+}
+
 impl<'a, T> StructuredRequest<'a, T>
 where
     T: GeminiStructured
@@ -69,13 +118,37 @@ where
             system_instruction: None,
             tools: Vec::new(),
             tool_registry: None,
+            tool_context: None,
+            use_default_tools: true,
             config: GenerationConfig::default(),
             cache_settings: None,
             safety_settings: None,
             refinement_instruction: None,
+            post_process: None,
             max_tool_steps: 5,
+            max_tool_concurrency: usize::MAX,
+            keep_tools_on_retry: false,
             max_parse_attempts: 3,
             retry_count: 3,
+            model_override: None,
+            capture_media: false,
+            capture_raw: false,
+            capture_tool_results: false,
+            normalize_enum_case: false,
+            field_defaults: HashMap::new(),
+            reject_empty_strings: false,
+            whitespace_is_empty: true,
+            max_continuations: 0,
+            empty_response_prompt: None,
+            escalate_on_empty: false,
+            temperature_ramp: None,
+            idempotency_key: None,
+            correlation_id: None,
+            locale: None,
+            timeout: None,
+            cancellation: None,
+            partial_parsing: false,
+            schema_transforms: Vec::new(),
             _marker: PhantomData,
         }
     }
@@ -86,6 +159,16 @@ where
         self
     }
 
+    /// Ask for every natural-language field value in `locale` (e.g. `"de-DE"`).
+    ///
+    /// The instruction is appended to the system instruction when the request runs, so
+    /// it composes with [`system`](Self::system) regardless of call order. Overrides the
+    /// client's [`default_locale`](crate::ClientConfig::default_locale).
+    pub fn with_locale(mut self, locale: &str) -> Self {
+        self.locale = Some(locale.to_string());
+        self
+    }
+
     /// Add a user text message.
     pub fn user_text(mut self, text: impl Into<String>) -> Self {
         self.contents
@@ -106,7 +189,7 @@ where
         }
         if !missing_fields.is_empty() {
             return Err(StructuredError::Context(format!(
-                "incomplete file handle, missing {:?}",
+                "incomplete file handle, missing {:?} (try FileManager::refresh)",
                 missing_fields
             )));
         }
@@ -135,6 +218,64 @@ where
         Ok(self)
     }
 
+    /// Add a user message with an image (or PDF) sent inline as base64.
+    ///
+    /// Skips the upload round-trip of [`add_file_path`](Self::add_file_path), so it suits
+    /// small payloads; it can be mixed freely with uploaded files in the same request.
+    pub fn user_image_bytes(
+        mut self,
+        text: impl Into<String>,
+        bytes: &[u8],
+        mime_type: &str,
+    ) -> Result<Self> {
+        if !mime_type.starts_with("image/") && mime_type != "application/pdf" {
+            return Err(StructuredError::Context(format!(
+                "unsupported inline MIME type '{mime_type}' (expected image/* or application/pdf)"
+            )));
+        }
+
+        let content = Content {
+            parts: Some(vec![
+                Part::Text {
+                    text: text.into(),
+                    thought: None,
+                    thought_signature: None,
+                },
+                Part::InlineData {
+                    inline_data: Blob {
+                        mime_type: mime_type.to_string(),
+                        data: BASE64.encode(bytes),
+                    },
+                },
+            ]),
+            role: Some(Role::User),
+        };
+
+        self.contents.push(content);
+        Ok(self)
+    }
+
+    /// Add a user message containing several labeled documents.
+    ///
+    /// Each file is preceded by a `=== Document: <label> ===` marker so the model can
+    /// reference sources by name.
+    pub fn with_labeled_documents(mut self, documents: Vec<DocumentBundle>) -> Result<Self> {
+        if documents.is_empty() {
+            return Ok(self);
+        }
+
+        let mut parts = Vec::new();
+        for document in &documents {
+            parts.extend(document.to_parts()?);
+        }
+
+        self.contents.push(Content {
+            parts: Some(parts),
+            role: Some(Role::User),
+        });
+        Ok(self)
+    }
+
     /// Upload a file from a local path and attach it as a user message.
     pub async fn add_file_path(self, path: impl AsRef<Path>) -> Result<Self> {
         let handle = self.client.file_manager.upload_path(path).await?;
@@ -164,6 +305,59 @@ where
         self
     }
 
+    /// Share a request-scoped value (auth token, tenant id, ...) with tool handlers
+    /// registered through [`ToolRegistry::register_tool_with_ctx`].
+    pub fn with_tool_context(mut self, ctx: ToolContext) -> Self {
+        self.tool_context = Some(ctx);
+        self
+    }
+
+    /// Skip the client's default tools (see
+    /// [`StructuredClientBuilder::with_default_tools`](crate::StructuredClientBuilder::with_default_tools)).
+    pub fn without_default_tools(mut self) -> Self {
+        self.use_default_tools = false;
+        self
+    }
+
+    fn apply_post_process(&self, value: T) -> T {
+        match &self.post_process {
+            Some(transform) => transform(value),
+            None => value,
+        }
+    }
+
+    /// Append the locale instruction to the system instruction.
+    fn apply_locale(&mut self) {
+        let Some(locale) = self.locale.take() else {
+            return;
+        };
+        self.system_instruction = Some(localized_system_instruction(
+            self.system_instruction.take(),
+            &locale,
+        ));
+    }
+
+    /// Union the client's default tools with this request's, request tools winning.
+    fn apply_default_tools(&mut self) {
+        if !self.use_default_tools {
+            return;
+        }
+        let Some(defaults) = self.client.default_tools.clone() else {
+            return;
+        };
+        let registry = match self.tool_registry.take() {
+            Some(registry) => {
+                self.tools.extend(defaults.tools_not_shadowed_by(&registry));
+                defaults.merge(registry)
+            }
+            None => {
+                self.tools.extend(defaults.definitions());
+                defaults
+            }
+        };
+        self.tool_registry = Some(registry);
+    }
+
     /// Enable Google Search grounding.
     pub fn with_google_search(self) -> Self {
         self.with_tool(Tool::google_search())
@@ -175,12 +369,61 @@ where
         self
     }
 
+    /// Raise the temperature by `step` on each parse-failure retry, capped at `max`.
+    ///
+    /// Helps when the model keeps returning the same invalid output at low temperature.
+    /// Ramping adds variation even when a fixed seed is configured, so retries are no
+    /// longer bit-for-bit reproducible.
+    pub fn temperature_ramp(mut self, step: f32, max: f32) -> Self {
+        self.temperature_ramp = Some((step, max));
+        self
+    }
+
     /// Set top_p.
     pub fn top_p(mut self, top_p: f32) -> Self {
         self.config.top_p = Some(top_p);
         self
     }
 
+    /// Set top_k.
+    ///
+    /// Overrides `top_k` from an earlier [`with_generation_config`](Self::with_generation_config);
+    /// a later one replaces the whole config, including this value.
+    pub fn top_k(mut self, top_k: i32) -> Self {
+        self.config.top_k = Some(top_k);
+        self
+    }
+
+    /// Set the sampler seed.
+    ///
+    /// The same prompt, schema, seed and temperature should produce stable output, which
+    /// makes tests and eval runs reproducible. Stability is best-effort on the service
+    /// side and does not survive a [`temperature_ramp`](Self::temperature_ramp) retry or a
+    /// model fallback.
+    pub fn seed(mut self, seed: i64) -> Self {
+        self.config.seed = Some(seed);
+        self
+    }
+
+    /// Stop generation when the model emits any of `sequences`.
+    ///
+    /// Mostly useful with prompt-embedded schemas (tools on older models, deep schemas or
+    /// continuations), where the model may add prose after the JSON; strict JSON mode
+    /// rarely needs it. Pick markers that cannot occur inside the JSON itself, since a
+    /// match truncates the response. Overrides stop sequences from an earlier
+    /// [`with_generation_config`](Self::with_generation_config); a later one replaces the
+    /// whole config, including these.
+    pub fn with_stop_sequences(mut self, sequences: Vec<String>) -> Self {
+        self.config.stop_sequences = Some(sequences);
+        self
+    }
+
+    /// Alias for [`with_stop_sequences`](Self::with_stop_sequences), named like
+    /// [`temperature`](Self::temperature) and [`top_p`](Self::top_p).
+    pub fn stop_sequences(self, sequences: Vec<String>) -> Self {
+        self.with_stop_sequences(sequences)
+    }
+
     /// Enable Gemini thinking mode with a given budget.
     pub fn with_thinking(mut self, budget: i32, include_thoughts: bool) -> Self {
         self.config.thinking_config = Some(gemini_rust::ThinkingConfig {
@@ -210,17 +453,78 @@ where
     }
 
     /// Automatically refine the result using this instruction after generation.
+    ///
+    /// When streaming, a [`StreamEvent::Refining`] event marks the start of refinement and
+    /// the final `Complete` event carries the refined value.
     pub fn refine_with(mut self, instruction: impl Into<String>) -> Self {
         self.refinement_instruction = Some(instruction.into());
         self
     }
 
+    /// Normalize the parsed value before it is returned.
+    ///
+    /// Runs once the response has parsed and passed the empty-string check, and before
+    /// any [`refine_with`](Self::refine_with) step, so refinement starts from the
+    /// normalized value. Applies to both [`execute`](Self::execute) and the final
+    /// [`StreamEvent::Complete`] of [`stream`](Self::stream).
+    pub fn post_process<F>(mut self, transform: F) -> Self
+    where
+        F: Fn(T) -> T + Send + Sync + 'static,
+    {
+        self.post_process = Some(Arc::new(transform));
+        self
+    }
+
     /// Maximum tool-calling steps to prevent infinite loops.
+    ///
+    /// A step is one model round; a single round may request several tool calls
+    /// (see [`max_tool_concurrency`](Self::max_tool_concurrency)).
     pub fn max_tool_steps(mut self, steps: usize) -> Self {
         self.max_tool_steps = steps.max(1);
         self
     }
 
+    /// Maximum tool calls executed concurrently within a single round (default: unlimited).
+    ///
+    /// Unlike [`max_tool_steps`](Self::max_tool_steps), which bounds the number of
+    /// rounds, this caps parallelism when the model requests several calls at once,
+    /// e.g. to protect rate-limited external APIs. Results are always returned to the
+    /// model in call order, and the first failing call's error is returned. Set it to 1
+    /// to execute calls sequentially.
+    pub fn max_tool_concurrency(mut self, n: usize) -> Self {
+        self.max_tool_concurrency = n.max(1);
+        self
+    }
+
+    /// Bound the whole request, including network retries and tool steps.
+    ///
+    /// When the deadline passes, [`execute`](Self::execute) fails with
+    /// [`StructuredError::Timeout`] and [`stream`](Self::stream) ends with that error.
+    pub fn timeout(mut self, limit: Duration) -> Self {
+        self.timeout = Some(limit);
+        self
+    }
+
+    /// Abort the request when `token` is cancelled.
+    ///
+    /// The in-flight call is dropped rather than awaited, so cancellation takes effect
+    /// mid round-trip as well as between retries and tool steps. [`execute`](Self::execute)
+    /// fails with [`StructuredError::Cancelled`]; [`stream`](Self::stream) yields that
+    /// error and ends.
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.cancellation = Some(token);
+        self
+    }
+
+    /// Keep tools available on parse-correction retries (default: false).
+    ///
+    /// By default a retry after a parse failure drops tools to force strict JSON mode.
+    /// Enable this for tool-dependent tasks where the fix may need another tool call.
+    pub fn keep_tools_on_retry(mut self, enabled: bool) -> Self {
+        self.keep_tools_on_retry = enabled;
+        self
+    }
+
     /// Maximum parse retries when the model returns invalid/empty JSON.
     pub fn max_parse_attempts(mut self, attempts: usize) -> Self {
         self.max_parse_attempts = attempts.max(1);
@@ -233,34 +537,521 @@ where
         self
     }
 
+    /// Run this request against a different model than the client's primary one.
+    ///
+    /// The override also bypasses fallback escalation for this request.
+    pub fn with_model(mut self, model: Model) -> Self {
+        self.model_override = Some(model);
+        self
+    }
+
+    /// Capture inline media (e.g. generated images) from the response into
+    /// [`GenerationOutcome::media`].
+    ///
+    /// Parsing still targets the text part; media is collected alongside it. Models only
+    /// return images when asked to, e.g. via `response_modalities` in a custom
+    /// [`GenerationConfig`].
+    pub fn capture_media(mut self, enabled: bool) -> Self {
+        self.capture_media = enabled;
+        self
+    }
+
+    /// Keep the raw and cleaned model text on the outcome (default: false).
+    ///
+    /// Populates [`GenerationOutcome::raw_text`] and [`GenerationOutcome::cleaned_text`],
+    /// so a value flagged later can be traced back to what the model actually returned
+    /// without re-running the request. Streaming captures the full buffer.
+    pub fn capture_raw(mut self, enabled: bool) -> Self {
+        self.capture_raw = enabled;
+        self
+    }
+
+    /// Emit [`StreamEvent::Partial`] from [`stream`](Self::stream) whenever the text
+    /// received so far parses into `T` (default: false).
+    ///
+    /// Truncated JSON is completed by closing any open string, array and object, so
+    /// partial values can hold cut-off strings or numbers and miss later fields. Only the
+    /// final `Complete` value is validated and passed through the client's response hook;
+    /// treat partials as display hints.
+    pub fn with_partial_parsing(mut self) -> Self {
+        self.partial_parsing = true;
+        self
+    }
+
+    /// Keep each tool's result on [`GenerationOutcome::tool_invocations`] (default: false).
+    ///
+    /// Tool names and arguments are always recorded; results are opt-in because they can
+    /// be large (search results, documents).
+    pub fn capture_tool_results(mut self, enabled: bool) -> Self {
+        self.capture_tool_results = enabled;
+        self
+    }
+
+    /// Fix the casing of enum values before deserializing (default: false).
+    ///
+    /// Runs [`normalize_enum_case`](crate::schema::normalize_enum_case), so `"auto"`
+    /// becomes `"Auto"` and `"LAST_VALUE"` becomes `"LastValue"` when exactly one
+    /// declared variant matches.
+    pub fn normalize_enum_case(mut self, enabled: bool) -> Self {
+        self.normalize_enum_case = enabled;
+        self
+    }
+
+    /// Fill fields the model omitted with known-safe defaults before deserializing.
+    ///
+    /// Keys are top-level field names or JSON pointers such as `"/totals/currency"`.
+    /// Defaults only fill absent (or null) fields and never overwrite returned values.
+    /// See [`inject_defaults`](crate::schema::inject_defaults).
+    pub fn with_field_defaults(mut self, defaults: HashMap<String, Value>) -> Self {
+        self.field_defaults = defaults;
+        self
+    }
+
+    /// Retry when required string fields come back empty (default: false).
+    ///
+    /// Uses [`assert_non_empty_strings`](crate::schema::assert_non_empty_strings); empty
+    /// fields count as a parse failure and the model is asked to fill them in, within the
+    /// usual parse-attempt budget. Applies to `execute` only.
+    pub fn reject_empty_required_strings(mut self, enabled: bool) -> Self {
+        self.reject_empty_strings = enabled;
+        self
+    }
+
+    /// Whether whitespace-only strings count as empty for
+    /// [`reject_empty_required_strings`](Self::reject_empty_required_strings) (default: true).
+    pub fn treat_whitespace_as_empty(mut self, enabled: bool) -> Self {
+        self.whitespace_is_empty = enabled;
+        self
+    }
+
+    /// Continue responses cut off at the output token limit (default: 0, disabled).
+    ///
+    /// When a response stops with `MAX_TOKENS`, the partial text is sent back as a model
+    /// turn followed by a request to continue the JSON where it left off, up to
+    /// `max_continuations` times. Fragments are concatenated (minus code fences) before
+    /// parsing. Continuation turns embed the schema in the prompt and request plain
+    /// text, since strict JSON mode would restart the document. With a mock handler, an
+    /// unparseable response stands in for a truncated one so fragments can be scripted.
+    pub fn with_continuation(mut self, max_continuations: usize) -> Self {
+        self.max_continuations = max_continuations;
+        self
+    }
+
+    /// Replace the follow-up sent when the model returns an empty response.
+    pub fn empty_response_prompt(mut self, prompt: impl Into<String>) -> Self {
+        self.empty_response_prompt = Some(prompt.into());
+        self
+    }
+
+    /// Switch to the fallback model as soon as a response comes back empty
+    /// (default: false).
+    ///
+    /// Empty responses often mean the current model is overloaded. Requires a client with
+    /// a [`FallbackStrategy`](crate::FallbackStrategy): the request moves to at least the
+    /// first tier, and a chain still advances to later tiers as attempts accumulate. Has
+    /// no effect when a per-request model override is set.
+    pub fn escalate_on_empty_response(mut self, enabled: bool) -> Self {
+        self.escalate_on_empty = enabled;
+        self
+    }
+
+    /// Tag every log line from this request with a correlation ID.
+    ///
+    /// The ID is recorded as the `correlation_id` field on the `execute` span, which
+    /// encloses retries, tool calls and refinement, and is returned on
+    /// [`GenerationOutcome::correlation_id`]. A random UUID is used when none is set.
+    pub fn with_correlation_id(mut self, id: impl Into<String>) -> Self {
+        self.correlation_id = Some(id.into());
+        self
+    }
+
+    /// Deduplicate retried submissions of the same logical request.
+    ///
+    /// A successful outcome is remembered on the client under `key`; executing another
+    /// request with the same key within the TTL (see
+    /// [`IdempotencyConfig`](crate::caching::IdempotencyConfig)) returns the stored
+    /// outcome without calling the model. Concurrent in-flight duplicates are not merged.
+    pub fn idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(key.into());
+        self
+    }
+
+    /// Post-process the response schema with the given transforms, applied in order.
+    ///
+    /// Built-ins: [`CollapseEnums`](crate::schema::CollapseEnums),
+    /// [`InlineRefs`](crate::schema::InlineRefs), [`CloseObjects`](crate::schema::CloseObjects),
+    /// [`DescribeFields`](crate::schema::DescribeFields) and
+    /// [`LimitDepth`](crate::schema::LimitDepth). Closures over `&mut Value` also implement
+    /// [`SchemaTransform`]. Replaces any transforms set earlier, including one added by
+    /// [`max_schema_depth`](Self::max_schema_depth). Parsed output is still validated
+    /// against the untransformed schema.
+    pub fn with_schema_transforms(mut self, transforms: Vec<Box<dyn SchemaTransform>>) -> Self {
+        self.schema_transforms = transforms;
+        self
+    }
+
+    /// Append one transform after those already set.
+    pub fn add_schema_transform(mut self, transform: impl SchemaTransform + 'static) -> Self {
+        self.schema_transforms.push(Box::new(transform));
+        self
+    }
+
+    /// Prune the schema sent to the model beyond `depth` levels of nesting.
+    ///
+    /// Deeper sub-schemas become permissive `object`/`array` schemas, which helps smaller
+    /// models finish deeply nested types. The server no longer enforces the pruned levels,
+    /// but the response is still deserialized into the full type and run through the
+    /// usual recovery passes, so client-side checks cover the whole structure.
+    /// See [`LimitDepth`](crate::schema::LimitDepth).
+    pub fn max_schema_depth(self, depth: usize) -> Self {
+        self.add_schema_transform(crate::schema::LimitDepth::new(depth))
+    }
+
+    /// Tools to offer on the given parse attempt.
+    fn tools_for_attempt(&self, parse_attempts: usize) -> &[Tool] {
+        if parse_attempts > 0 && !self.keep_tools_on_retry {
+            debug!("Disabling tools to force strict JSON mode for retry");
+            &[]
+        } else {
+            &self.tools
+        }
+    }
+
+    /// Generation config for the given parse attempt, with any temperature ramp applied.
+    fn config_for_attempt(&self, parse_attempts: usize) -> GenerationConfig {
+        let mut config = self.config.clone();
+        if let Some((step, max)) = self.temperature_ramp {
+            if parse_attempts > 0 {
+                let base = config.temperature.unwrap_or(0.0);
+                let ramped = (base + step * parse_attempts as f32).min(max);
+                config.temperature = Some(ramped.max(base));
+                debug!(temperature = ramped, "Ramping temperature for parse retry");
+            }
+        }
+        config
+    }
+
+    /// Record a completed tool call, keeping the result only when `capture_tool_results` is set.
+    fn tool_invocation(
+        &self,
+        call: &gemini_rust::tools::FunctionCall,
+        result: &Value,
+    ) -> ToolInvocation {
+        ToolInvocation {
+            name: call.name.clone(),
+            args: call.args.clone(),
+            result: self.capture_tool_results.then(|| result.clone()),
+        }
+    }
+
+    /// Raw and cleaned text to attach to the outcome, when `capture_raw` is enabled.
+    fn raw_capture(&self, raw: &str, cleaned: &str) -> (Option<String>, Option<String>) {
+        if self.capture_raw {
+            (Some(raw.to_string()), Some(cleaned.to_string()))
+        } else {
+            (None, None)
+        }
+    }
+
     /// Execute the request and return parsed value plus metadata.
-    #[instrument(skip_all, fields(target = std::any::type_name::<T>()))]
+    #[instrument(
+        skip_all,
+        fields(
+            target = std::any::type_name::<T>(),
+            correlation_id = tracing::field::Empty
+        )
+    )]
     pub async fn execute(mut self) -> Result<GenerationOutcome<T>> {
-        if let Some(mock) = &self.client.mock_handler {
-            let prompt_preview = self
-                .contents
+        self.apply_locale();
+        let correlation_id = self
+            .correlation_id
+            .get_or_insert_with(|| uuid::Uuid::new_v4().to_string())
+            .clone();
+        tracing::Span::current().record("correlation_id", correlation_id.as_str());
+
+        let cancellation = self.cancellation.clone();
+        let limit = self.timeout;
+        let run = async move {
+            match limit {
+                Some(limit) => tokio::time::timeout(limit, self.execute_idempotent(correlation_id))
+                    .await
+                    .map_err(|_| StructuredError::Timeout { elapsed: limit })?,
+                None => self.execute_idempotent(correlation_id).await,
+            }
+        };
+        match cancellation {
+            Some(token) => tokio::select! {
+                biased;
+                _ = token.cancelled() => Err(StructuredError::Cancelled),
+                result = run => result,
+            },
+            None => run.await,
+        }
+    }
+
+    /// Serve a keyed request from the idempotency cache, or run it and store the outcome.
+    async fn execute_idempotent(self, correlation_id: String) -> Result<GenerationOutcome<T>> {
+        let Some(key) = self.idempotency_key.clone() else {
+            return Ok(self
+                .execute_deduplicated()
+                .await?
+                .with_correlation_id(correlation_id));
+        };
+
+        let cache = self.client.idempotency.clone();
+        if let Some(outcome) = cache.get::<T>(&key) {
+            info!(idempotency_key = %key, "Returning stored outcome for idempotency key");
+            return Ok(outcome.with_correlation_id(correlation_id));
+        }
+
+        let outcome = self
+            .execute_deduplicated()
+            .await?
+            .with_correlation_id(correlation_id);
+        cache.insert(key, &outcome);
+        Ok(outcome)
+    }
+
+    /// Run the request, sharing the call with identical in-flight requests when the
+    /// client has single-flight enabled.
+    async fn execute_deduplicated(self) -> Result<GenerationOutcome<T>> {
+        let Some(flights) = self.client.single_flight.clone() else {
+            return self.execute_uncached().await;
+        };
+
+        let Some(key) = self.single_flight_key() else {
+            return self.execute_uncached().await;
+        };
+
+        match flights.join(&key) {
+            Flight::Leader(guard) => match self.execute_uncached().await {
+                Ok(outcome) => {
+                    guard.finish(Ok(Arc::new(outcome.clone())));
+                    Ok(outcome)
+                }
+                Err(err) => {
+                    // Dropping the guard without a result lets followers run the request
+                    // themselves when the error cannot be copied.
+                    if let Some(copy) = err.try_clone() {
+                        guard.finish(Err(Arc::new(copy)));
+                    }
+                    Err(err)
+                }
+            },
+            Flight::Follower(waiter) => match waiter.await {
+                Some(Ok(shared)) => {
+                    debug!("Reusing outcome of identical in-flight request");
+                    shared
+                        .downcast_ref::<GenerationOutcome<T>>()
+                        .cloned()
+                        .ok_or_else(|| {
+                            StructuredError::Context(
+                                "single-flight outcome had an unexpected type".to_string(),
+                            )
+                        })
+                }
+                Some(Err(shared)) => match shared.try_clone() {
+                    Some(err) => Err(err),
+                    None => self.execute_uncached().await,
+                },
+                None => self.execute_uncached().await,
+            },
+        }
+    }
+
+    /// Hash of everything that shapes the response, or `None` when the request carries
+    /// closures (post-processors, tool handlers, schema transforms) that cannot be
+    /// compared, in which case it is never merged with another request.
+    fn single_flight_key(&self) -> Option<String> {
+        if self.post_process.is_some()
+            || self.tool_registry.is_some()
+            || self.tool_context.is_some()
+            || !self.schema_transforms.is_empty()
+        {
+            return None;
+        }
+
+        let field_defaults: BTreeMap<_, _> = self.field_defaults.iter().collect();
+        let mut hasher = Sha256::new();
+        hasher.update(std::any::type_name::<T>());
+        hasher.update(format!(
+            "{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}|{:?}",
+            self.model_override.as_ref().map(Model::as_str),
+            self.system_instruction,
+            self.contents,
+            self.tools,
+            self.use_default_tools,
+            self.config,
+            self.safety_settings,
+            self.refinement_instruction,
+            self.locale,
+            field_defaults,
+            self.empty_response_prompt,
+        ));
+        hasher.update(format!(
+            "{:?}",
+            (
+                self.max_tool_steps,
+                self.keep_tools_on_retry,
+                self.max_parse_attempts,
+                self.retry_count,
+                self.normalize_enum_case,
+                self.reject_empty_strings,
+                self.whitespace_is_empty,
+                self.max_continuations,
+                self.escalate_on_empty,
+                self.temperature_ramp,
+                (
+                    self.capture_media,
+                    self.capture_raw,
+                    self.capture_tool_results
+                ),
+            )
+        ));
+        Some(
+            hasher
+                .finalize()
                 .iter()
-                .map(|c| format!("{c:?}"))
-                .collect::<Vec<_>>()
-                .join("\n---\n");
+                .map(|b| format!("{b:02x}"))
+                .collect(),
+        )
+    }
+
+    /// Run the request against the client's mock handler.
+    ///
+    /// Mirrors the live loop's recovery: empty and unparseable responses are retried up
+    /// to `max_parse_attempts` times with the same follow-up, which is appended to the
+    /// next call's `prompt_preview`, tools are withheld on retries unless
+    /// [`keep_tools_on_retry`](Self::keep_tools_on_retry) is set, and
+    /// [`MockRequest::model`] names the fallback model once escalation kicks in.
+    async fn execute_mock(&self, mock: &MockHandler) -> Result<GenerationOutcome<T>> {
+        let mut prompt_preview = self
+            .contents
+            .iter()
+            .map(|c| format!("{c:?}"))
+            .collect::<Vec<_>>()
+            .join("\n---\n");
+        let mut parse_attempts = 0usize;
+        let mut empty_escalation = false;
+
+        loop {
             let request = MockRequest {
                 target: std::any::type_name::<T>().to_string(),
+                model: self.mock_model_name(parse_attempts, empty_escalation),
                 system_instruction: self.system_instruction.clone(),
-                prompt_preview,
+                prompt_preview: prompt_preview.clone(),
+                schema: Some(T::gemini_schema()),
+                tools: self
+                    .tools_for_attempt(parse_attempts)
+                    .iter()
+                    .flat_map(function_names)
+                    .collect(),
             };
-            let raw = (mock)(request)?;
-            let parsed: T =
-                serde_json::from_str(&raw).map_err(|e| StructuredError::parse_error(e, &raw))?;
+            let raw = self.call_mock_with_continuations(mock, request)?;
+            // Yield where the live loop would await the network, so a slow mock still
+            // trips the request timeout.
+            tokio::task::yield_now().await;
+
+            if raw.trim().is_empty() {
+                empty_escalation |= self.escalate_on_empty;
+                parse_attempts += 1;
+                if parse_attempts >= self.max_parse_attempts {
+                    return Err(StructuredError::Context(
+                        "Failed to get non-empty response".to_string(),
+                    ));
+                }
+                let follow_up = self
+                    .empty_response_prompt
+                    .as_deref()
+                    .unwrap_or(EMPTY_RESPONSE_PROMPT);
+                prompt_preview.push_str(&format!("\n---\n{raw}\n---\n{follow_up}"));
+                continue;
+            }
 
-            return Ok(GenerationOutcome::new(
-                parsed,
-                None,
-                vec![],
-                None,
-                None,
-                0,
-                0,
-            ));
+            let parsed = self.client.parse_mock_response::<T>(
+                &raw,
+                &self.field_defaults,
+                self.normalize_enum_case,
+            );
+            let follow_up = match parsed {
+                Ok(parsed) => {
+                    let parsed = self.apply_post_process(parsed);
+                    let (raw_text, cleaned_text) = self.raw_capture(&raw, &raw);
+                    return Ok(GenerationOutcome::new(
+                        parsed,
+                        None,
+                        vec![],
+                        None,
+                        None,
+                        parse_attempts,
+                        0,
+                    )
+                    .with_raw_text(raw_text, cleaned_text));
+                }
+                // A validated mock that violates the schema is stale, not a bad model turn.
+                Err(err @ StructuredError::Validation(_)) => return Err(err),
+                Err(err) => {
+                    parse_attempts += 1;
+                    if parse_attempts >= self.max_parse_attempts {
+                        return Err(err);
+                    }
+                    let reason = match &err {
+                        StructuredError::ParseWithContext { message, .. } => message.clone(),
+                        other => other.to_string(),
+                    };
+                    format!(
+                        "Failed to parse JSON: {reason}. Return ONLY valid JSON matching the schema."
+                    )
+                }
+            };
+            prompt_preview.push_str(&format!("\n---\n{raw}\n---\n{follow_up}"));
+        }
+    }
+
+    /// Name of the model the live loop would use for this attempt.
+    fn mock_model_name(&self, parse_attempts: usize, empty_escalation: bool) -> String {
+        if let Some(model) = &self.model_override {
+            return model.as_str().to_string();
+        }
+        let tier = if empty_escalation {
+            self.client.select_escalated_client(parse_attempts).1
+        } else {
+            self.client.select_client(parse_attempts).1
+        };
+        match tier {
+            Some(index) => self.client.tier_model_name(index),
+            None => self.client.model.as_str().to_string(),
+        }
+    }
+
+    /// Call the mock, requesting continuations while the joined text is not yet JSON.
+    fn call_mock_with_continuations(
+        &self,
+        mock: &MockHandler,
+        request: MockRequest,
+    ) -> Result<String> {
+        let mut raw = (mock)(request.clone())?;
+        let mut fragments = Vec::new();
+        while fragments.len() < self.max_continuations
+            && serde_json::from_str::<Value>(&clean_json_text(&join_continuations(
+                &fragments, &raw,
+            )))
+            .is_err()
+        {
+            let mut next = request.clone();
+            for fragment in fragments.iter().chain([&raw]) {
+                next.prompt_preview
+                    .push_str(&format!("\n---\n{fragment}\n---\n{CONTINUATION_PROMPT}"));
+            }
+            fragments.push(std::mem::replace(&mut raw, (mock)(next)?));
+        }
+        Ok(join_continuations(&fragments, &raw))
+    }
+
+    async fn execute_uncached(mut self) -> Result<GenerationOutcome<T>> {
+        self.apply_default_tools();
+        if let Some(mock) = &self.client.mock_handler {
+            return self.execute_mock(mock).await;
         }
 
         let mut messages = Vec::new();
@@ -272,34 +1063,55 @@ where
             });
         }
 
+        let override_client: Option<Arc<Gemini>> = match &self.model_override {
+            Some(model) => Some(self.client.client_for_model(model.clone())?),
+            None => None,
+        };
+
         let mut current_step = 0usize;
         let mut parse_attempts = 0usize;
         let mut total_network_attempts = 0usize;
-        let mut escalated = false;
+        let mut escalated_tier: Option<usize> = None;
+        let mut empty_escalation = false;
         let mut force_prompt_schema = false;
+        let mut fragments: Vec<String> = Vec::new();
+        let mut tool_invocations: Vec<ToolInvocation> = Vec::new();
 
         loop {
             // Retry loop for 503/429 errors
             let mut response = None;
             let mut last_error = None;
 
-            // If we are retrying due to a parsing error, we disable tools to force strict JSON mode.
-            // This ensures the model conforms to the schema on the correction attempt.
-            let tools_slice: &[Tool] = if parse_attempts > 0 {
-                debug!("Disabling tools to force strict JSON mode for retry");
-                &[]
-            } else {
-                &self.tools
-            };
+            // If we are retrying due to a parsing error, we disable tools to force strict JSON mode
+            // (unless `keep_tools_on_retry` is set). This ensures the model conforms to the schema
+            // on the correction attempt.
+            let tools_slice = self.tools_for_attempt(parse_attempts);
 
             // Determine which client to use based on escalation strategy
-            let (active_client, is_escalated) = self.client.select_client(parse_attempts);
-            if is_escalated && !escalated {
+            let (active_client, tier) = match &override_client {
+                Some(client) => (client, None),
+                None if empty_escalation => self.client.select_escalated_client(parse_attempts),
+                None => self.client.select_client(parse_attempts),
+            };
+            if let Some(index) = tier.filter(|_| tier > escalated_tier) {
+                let reason = if tier > self.client.select_client(parse_attempts).1 {
+                    "an empty response"
+                } else {
+                    "parse failures"
+                };
                 info!(
                     parse_attempts = parse_attempts,
-                    "Escalating to fallback model after parse failures"
+                    tier = index + 1,
+                    model = %self.client.tier_model_name(index),
+                    "Escalating to fallback model after {reason}"
                 );
-                escalated = true;
+                escalated_tier = tier;
+            }
+
+            let continuing = !fragments.is_empty();
+            let mut attempt_config = self.config_for_attempt(parse_attempts);
+            if continuing {
+                attempt_config.response_mime_type = Some("text/plain".to_string());
             }
 
             for attempt in 0..=self.retry_count {
@@ -312,11 +1124,13 @@ where
                         &messages,
                         BuilderOptions {
                             tools: tools_slice,
-                            config: &self.config,
+                            config: &attempt_config,
                             cache_settings: &self.cache_settings,
                             system_instruction: &self.system_instruction,
                             safety_settings: &self.safety_settings,
-                            force_prompt_schema,
+                            force_prompt_schema: force_prompt_schema || continuing,
+                            model: self.model_override.as_ref(),
+                            schema_transforms: &self.schema_transforms,
                         },
                     )
                     .await;
@@ -329,25 +1143,57 @@ where
                     }
                 };
 
+                // Dropping the permit (cancellation, timeout, non-backend error) frees a
+                // half-open probe slot.
+                let permit = match self.client.circuit_breaker.as_ref().map(|cb| cb.acquire()) {
+                    Some(Err(e)) => {
+                        last_error = Some(e);
+                        break;
+                    }
+                    Some(Ok(permit)) => Some(permit),
+                    None => None,
+                };
+
                 match builder.execute().await {
                     Ok(res) => {
+                        if let Some(permit) = permit {
+                            permit.record_success();
+                        }
                         response = Some(res);
                         break;
                     }
                     Err(e) => {
+                        if let Some(permit) = permit {
+                            if crate::circuit::is_backend_failure(&e) {
+                                permit.record_failure();
+                            }
+                        }
                         let mut status_code = None;
                         let mut depth_error = false;
-                        let mut retryable_status = false;
+                        let mut quota_exhausted = false;
 
                         if let gemini_rust::ClientError::BadResponse { code, description } = &e {
                             status_code = Some(*code);
                             depth_error =
                                 *code == 400 && is_schema_depth_error(description.as_deref());
-                            retryable_status = *code == 503 || *code == 429;
+                            quota_exhausted = *code == 429
+                                && description
+                                    .as_deref()
+                                    .is_some_and(crate::error::is_quota_exhaustion);
+                        }
+
+                        // 503 and non-quota 429 unless a custom retry classifier says otherwise.
+                        let structured_err = StructuredError::from_client_error(e);
+                        let retryable = self.client.is_retryable(&structured_err);
+
+                        if quota_exhausted && !retryable {
+                            // Daily quotas do not recover within the backoff window; fail fast.
+                            warn!(error = %structured_err, "Quota exhausted; not retrying");
+                            last_error = Some(structured_err);
+                            break;
                         }
 
                         if depth_error && !force_prompt_schema {
-                            let structured_err = StructuredError::Gemini(e);
                             warn!(
                                 error = %structured_err,
                                 "Schema depth rejected by API; retrying with prompt-embedded schema"
@@ -357,8 +1203,7 @@ where
                             continue;
                         }
 
-                        if retryable_status {
-                            let structured_err = StructuredError::Gemini(e);
+                        if retryable {
                             // Use API-provided retry delay if available, otherwise exponential backoff
                             let delay_secs = structured_err
                                 .retry_delay()
@@ -375,7 +1220,7 @@ where
                             continue;
                         }
 
-                        last_error = Some(StructuredError::Gemini(e));
+                        last_error = Some(structured_err);
                         break;
                     }
                 }
@@ -394,15 +1239,35 @@ where
                 response.function_calls().into_iter().cloned().collect();
 
             if function_calls.is_empty() {
-                let text = response.text();
+                let fragment = response.text();
+                if fragments.len() < self.max_continuations && hit_max_tokens(&response) {
+                    warn!(
+                        continuation = fragments.len() + 1,
+                        max_continuations = self.max_continuations,
+                        "Response truncated at max tokens; requesting continuation"
+                    );
+                    messages.push(Message::model(fragment.clone()));
+                    messages.push(Message::user(CONTINUATION_PROMPT));
+                    fragments.push(fragment);
+                    continue;
+                }
+                let text = join_continuations(&fragments, &fragment);
+                fragments.clear();
                 debug!(raw_response_length = text.len(), "Received model response");
-                trace!(raw_response = %text, "Raw model text");
+                let redact_paths = &self.client.config().redact_paths;
+                trace!(
+                    raw_response = %redact_json_text(&text, redact_paths),
+                    "Raw model text"
+                );
 
                 if text.trim().is_empty() {
                     warn!("Received empty response from model");
                     messages.push(Message::user(
-                        "The last response was empty. Return valid JSON matching the schema.",
+                        self.empty_response_prompt
+                            .as_deref()
+                            .unwrap_or(EMPTY_RESPONSE_PROMPT),
                     ));
+                    empty_escalation |= self.escalate_on_empty;
                     parse_attempts += 1;
                     if parse_attempts >= self.max_parse_attempts {
                         return Err(StructuredError::Context(
@@ -415,34 +1280,58 @@ where
                 // Clean the text to handle Markdown code blocks (e.g. ```json ... ```)
                 let cleaned_text = clean_json_text(&text);
                 if cleaned_text != text {
-                    trace!(cleaned_response = %cleaned_text, "Cleaned JSON text");
+                    trace!(
+                        cleaned_response = %redact_json_text(&cleaned_text, redact_paths),
+                        "Cleaned JSON text"
+                    );
                 }
 
                 // Parse to Value first, normalize maps (Array<__key__, __value__> -> Object), then deserialize to T
                 match serde_json::from_str::<Value>(&cleaned_text) {
                     Ok(mut json_value) => {
                         let schema = T::gemini_schema();
-
-                        // Prune null fields to handle Gemini's occasional nulls for optional fields
-                        crate::schema::prune_null_fields(&mut json_value);
-
-                        // Convert flat discriminator enums back to externally tagged format.
-                        crate::schema::unflatten_externally_tagged_enums(&mut json_value, &schema);
-
-                        // Coerce enum strings when the model returns close-but-invalid values.
-                        crate::schema::coerce_enum_strings(&mut json_value, &schema);
-
-                        // Recover internally-tagged enums that Gemini collapsed to strings
-                        crate::schema::recover_internally_tagged_enums(&mut json_value, &schema);
-
-                        // Apply user-provided response hook for custom transformations
-                        if let Some(hook) = self.client.response_hook() {
-                            hook(&mut json_value);
+                        self.client.normalize_response(
+                            &mut json_value,
+                            &schema,
+                            &self.field_defaults,
+                            self.normalize_enum_case,
+                        );
+
+                        if self.reject_empty_strings {
+                            let empty = crate::schema::assert_non_empty_strings(
+                                &json_value,
+                                &schema,
+                                self.whitespace_is_empty,
+                            );
+                            if !empty.is_empty() {
+                                let fields = empty.join(", ");
+                                warn!(fields = %fields, "Required string fields were empty");
+                                parse_attempts += 1;
+                                if parse_attempts >= self.max_parse_attempts {
+                                    return Err(StructuredError::Validation(format!(
+                                        "Required string fields still empty after {} attempts: {fields}",
+                                        self.max_parse_attempts
+                                    )));
+                                }
+                                messages.push(Message::user(format!(
+                                    "These required fields were left empty: {fields}. \
+                                     Fill each one with the actual value from the input."
+                                )));
+                                continue;
+                            }
                         }
 
                         match serde_json::from_value::<T>(json_value) {
                             Ok(parsed) => {
                                 debug!("Successfully parsed structured response");
+                                let parsed = self.apply_post_process(parsed);
+                                let media = if self.capture_media {
+                                    collect_media(&response)
+                                } else {
+                                    Vec::new()
+                                };
+                                let (raw_text, cleaned_text) =
+                                    self.raw_capture(&text, &cleaned_text);
                                 if let Some(instruction) = &self.refinement_instruction {
                                     debug!("Starting refinement step");
                                     let refinement = self
@@ -458,7 +1347,10 @@ where
                                         response.response_id,
                                         parse_attempts,
                                         total_network_attempts,
-                                    ));
+                                    )
+                                    .with_media(media)
+                                    .with_raw_text(raw_text, cleaned_text)
+                                    .with_tool_invocations(tool_invocations));
                                 }
 
                                 return Ok(GenerationOutcome::new(
@@ -469,14 +1361,17 @@ where
                                     response.response_id,
                                     parse_attempts,
                                     total_network_attempts,
-                                ));
+                                )
+                                .with_media(media)
+                                .with_raw_text(raw_text, cleaned_text)
+                                .with_tool_invocations(tool_invocations));
                             }
                             Err(err) => {
                                 let validation_hint = validation_errors_for::<T>(&serde_json::from_str::<Value>(&cleaned_text).unwrap_or_default());
                                 warn!(
                                     error = %err,
-                                    raw_response = %text,
-                                    cleaned_response = %cleaned_text,
+                                    raw_response = %redact_json_text(&text, redact_paths),
+                                    cleaned_response = %redact_json_text(&cleaned_text, redact_paths),
                                     validation = ?validation_hint,
                                     "JSON parsing failed"
                                 );
@@ -506,7 +1401,11 @@ where
                     }
                     Err(err) => {
                         // JSON syntax error in the raw text itself
-                        warn!(error = %err, raw_response = %text, "Failed to parse raw JSON syntax");
+                        warn!(
+                            error = %err,
+                            raw_response = %redact_json_text(&text, redact_paths),
+                            "Failed to parse raw JSON syntax"
+                        );
                         parse_attempts += 1;
                         if parse_attempts >= self.max_parse_attempts {
                             return Err(StructuredError::parse_error(err, &text));
@@ -538,11 +1437,22 @@ where
                 StructuredError::Context("Tool called but no registry provided".to_string())
             })?;
 
-            debug!(count = function_calls.len(), "Processing tool calls");
+            debug!(
+                count = function_calls.len(),
+                concurrency = self.max_tool_concurrency,
+                "Processing tool calls"
+            );
+
+            let results = dispatch_tool_calls(
+                registry,
+                &function_calls,
+                &self.tool_context,
+                self.max_tool_concurrency,
+            )
+            .await?;
 
-            for call in function_calls {
-                debug!(tool = %call.name, "Executing tool");
-                let result_json = registry.execute(&call.name, call.args.clone()).await?;
+            for (call, result_json) in function_calls.iter().zip(results) {
+                tool_invocations.push(self.tool_invocation(call, &result_json));
                 let content = gemini_rust::Content::function_response_json(&call.name, result_json)
                     .with_role(Role::User);
                 messages.push(Message {
@@ -553,11 +1463,110 @@ where
         }
     }
 
+    /// Stream the response, also emitting each top-level array element as it completes.
+    ///
+    /// The text is scanned incrementally; every time an element of the top-level array
+    /// (or of the `items` array in a `{ "items": [...] }` envelope such as
+    /// [`ListEnvelope`](crate::models::ListEnvelope)) closes, it is deserialized into `I`
+    /// and emitted as [`ArrayStreamEvent::Item`]. Every event of [`stream`](Self::stream)
+    /// is passed through as [`ArrayStreamEvent::Stream`], so the final `Complete` event
+    /// carries the fully parsed value and metadata. Responses that arrive without any
+    /// `Chunk` (such as mocks) emit their items just before `Complete`.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let mut events = client
+    ///     .request::<ListEnvelope<Row>>()
+    ///     .user_text("Generate 100 rows")
+    ///     .stream_array_items::<Row>()
+    ///     .await?;
+    /// while let Some(event) = events.next().await {
+    ///     if let ArrayStreamEvent::Item(row) = event? {
+    ///         insert(row).await?;
+    ///     }
+    /// }
+    /// ```
+    pub async fn stream_array_items<I>(
+        self,
+    ) -> Result<BoxStream<'a, Result<ArrayStreamEvent<T, I>>>>
+    where
+        I: DeserializeOwned + Send + 'a,
+    {
+        let inner = self.stream().await?;
+        let mut scanner = ArrayItemScanner::default();
+        let mut saw_chunk = false;
+        Ok(Box::pin(inner.flat_map(move |event| {
+            let (items, event) = match event {
+                Ok(StreamEvent::Chunk(chunk)) => {
+                    saw_chunk = true;
+                    (scanner.push(&chunk), Ok(StreamEvent::Chunk(chunk)))
+                }
+                Ok(StreamEvent::Complete(outcome)) if !saw_chunk => {
+                    let items = serde_json::to_string(&outcome.value)
+                        .map(|text| scanner.push(&text))
+                        .unwrap_or_default();
+                    (items, Ok(StreamEvent::Complete(outcome)))
+                }
+                other => (Vec::new(), other),
+            };
+            let items = items.into_iter().map(|item| {
+                serde_json::from_str::<I>(&item)
+                    .map(ArrayStreamEvent::Item)
+                    .map_err(|e| StructuredError::parse_error(e, &item))
+            });
+            // Items follow the chunk that completed them, but precede `Complete`.
+            let events: Vec<_> = match event {
+                Ok(StreamEvent::Complete(outcome)) => items
+                    .chain([Ok(ArrayStreamEvent::Stream(StreamEvent::Complete(outcome)))])
+                    .collect(),
+                event => std::iter::once(event.map(ArrayStreamEvent::Stream))
+                    .chain(items)
+                    .collect(),
+            };
+            stream::iter(events)
+        })))
+    }
+
     /// Stream raw text chunks before parsing into structured output.
     ///
     /// This is useful for UIs where you want to surface incremental model output
     /// while still validating against the target schema at the end.
-    pub async fn stream(mut self) -> Result<BoxStream<'a, Result<StreamEvent<T>>>> {
+    pub async fn stream(self) -> Result<BoxStream<'a, Result<StreamEvent<T>>>> {
+        let Some(token) = self.cancellation.clone() else {
+            return self.stream_with_deadline().await;
+        };
+        let inner = tokio::select! {
+            biased;
+            _ = token.cancelled() => return Err(StructuredError::Cancelled),
+            result = self.stream_with_deadline() => result?,
+        };
+        Ok(until_cancelled(inner, token))
+    }
+
+    async fn stream_with_deadline(self) -> Result<BoxStream<'a, Result<StreamEvent<T>>>> {
+        let Some(limit) = self.timeout else {
+            return self.stream_unbounded().await;
+        };
+        let deadline = tokio::time::Instant::now() + limit;
+        let inner = tokio::time::timeout_at(deadline, self.stream_unbounded())
+            .await
+            .map_err(|_| StructuredError::Timeout { elapsed: limit })??;
+        Ok(with_deadline(inner, deadline, limit))
+    }
+
+    async fn stream_unbounded(mut self) -> Result<BoxStream<'a, Result<StreamEvent<T>>>> {
+        let client = self.client;
+        let Some(instruction) = self.refinement_instruction.take() else {
+            return self.stream_parsed().await;
+        };
+        let inner = self.stream_parsed().await?;
+        Ok(with_refinement(inner, client, instruction))
+    }
+
+    async fn stream_parsed(mut self) -> Result<BoxStream<'a, Result<StreamEvent<T>>>> {
+        self.apply_locale();
+        self.apply_default_tools();
         if let Some(mock) = &self.client.mock_handler {
             let prompt_preview = self
                 .contents
@@ -567,16 +1576,29 @@ where
                 .join("\n---\n");
             let request = MockRequest {
                 target: std::any::type_name::<T>().to_string(),
+                model: self.mock_model_name(0, false),
                 system_instruction: self.system_instruction.clone(),
                 prompt_preview,
+                schema: Some(T::gemini_schema()),
+                tools: self.tools.iter().flat_map(function_names).collect(),
             };
             let raw = (mock)(request)?;
-            let parsed: T =
-                serde_json::from_str(&raw).map_err(|e| StructuredError::parse_error(e, &raw))?;
-            let outcome = GenerationOutcome::new(parsed, None, vec![], None, None, 0, 0);
-            return Ok(Box::pin(stream::once(async move {
-                Ok(StreamEvent::Complete(outcome))
-            })));
+            let parsed = self.client.parse_mock_response(
+                &raw,
+                &self.field_defaults,
+                self.normalize_enum_case,
+            )?;
+            let parsed = self.apply_post_process(parsed);
+            let (raw_text, cleaned_text) = self.raw_capture(&raw, &raw);
+            let partial = self
+                .partial_parsing
+                .then(|| Ok(StreamEvent::Partial(parsed.clone())));
+            let outcome = GenerationOutcome::new(parsed, None, vec![], None, None, 0, 0)
+                .with_raw_text(raw_text, cleaned_text);
+            let events = partial
+                .into_iter()
+                .chain([Ok(StreamEvent::Complete(outcome))]);
+            return Ok(Box::pin(stream::iter(events)));
         }
 
         let mut messages = Vec::new();
@@ -588,9 +1610,15 @@ where
             });
         }
 
+        let active_client = match &self.model_override {
+            Some(model) => self.client.client_for_model(model.clone())?,
+            None => self.client.client.clone(),
+        };
+
         let builder = self
             .client
-            .configured_builder::<T>(
+            .configured_builder_with_client::<T>(
+                &active_client,
                 &messages,
                 BuilderOptions {
                     tools: &self.tools,
@@ -599,6 +1627,8 @@ where
                     system_instruction: &self.system_instruction,
                     safety_settings: &self.safety_settings,
                     force_prompt_schema: false,
+                    model: self.model_override.as_ref(),
+                    schema_transforms: &self.schema_transforms,
                 },
             )
             .await?;
@@ -612,9 +1642,40 @@ where
             model_version: Option<String>,
             response_id: Option<String>,
             function_calls: Vec<gemini_rust::tools::FunctionCall>,
-            refinement_instruction: Option<String>,
             response_hook: Option<ResponseHook>,
-            _marker: PhantomData<T>,
+            capture_raw: bool,
+            normalize_enum_case: bool,
+            field_defaults: HashMap<String, Value>,
+            post_process: Option<PostProcess<T>>,
+            partial_parsing: bool,
+            pending_partial: Option<T>,
+            schema: Value,
+        }
+
+        impl<T> StreamState<T> {
+            /// Apply the same repairs as non-streaming responses to a parsed buffer.
+            ///
+            /// The response hook is left to the caller so it only sees the final value.
+            fn normalize(&self, json_value: &mut Value) {
+                let schema = &self.schema;
+                // Prune null fields to handle Gemini's occasional nulls for optional fields
+                crate::schema::prune_null_fields(json_value);
+                crate::schema::normalize_map_entry_arrays(json_value);
+                crate::schema::inject_defaults(json_value, &self.field_defaults);
+
+                // Convert flat discriminator enums back to externally tagged format.
+                crate::schema::unflatten_externally_tagged_enums(json_value, schema);
+
+                if self.normalize_enum_case {
+                    crate::schema::normalize_enum_case(json_value, schema);
+                }
+
+                // Coerce enum strings when the model returns close-but-invalid values.
+                crate::schema::coerce_enum_strings(json_value, schema);
+
+                // Recover internally-tagged enums that Gemini collapsed to strings
+                crate::schema::recover_internally_tagged_enums(json_value, schema);
+            }
         }
 
         let state = StreamState::<T> {
@@ -624,14 +1685,22 @@ where
             model_version: None,
             response_id: None,
             function_calls: Vec::new(),
-            refinement_instruction: self.refinement_instruction.clone(),
             response_hook: self.client.response_hook().cloned(),
-            _marker: PhantomData,
+            capture_raw: self.capture_raw,
+            normalize_enum_case: self.normalize_enum_case,
+            field_defaults: std::mem::take(&mut self.field_defaults),
+            post_process: self.post_process.take(),
+            partial_parsing: self.partial_parsing,
+            pending_partial: None,
+            schema: T::gemini_schema(),
         };
 
         Ok(Box::pin(stream::try_unfold(
             state,
             move |mut state| async move {
+                if let Some(partial) = state.pending_partial.take() {
+                    return Ok(Some((StreamEvent::Partial(partial), state)));
+                }
                 while let Some(resp) = state.inner.next().await {
                     let response = resp.map_err(StructuredError::Gemini)?;
                     if let Some(usage) = response.usage_metadata.clone() {
@@ -653,6 +1722,10 @@ where
                     let delta = response.text();
                     if !delta.is_empty() {
                         state.buffer.push_str(&delta);
+                        if state.partial_parsing {
+                            state.pending_partial =
+                                parse_partial(&state.buffer, |value| state.normalize(value));
+                        }
                         return Ok(Some((StreamEvent::Chunk(delta), state)));
                     }
                 }
@@ -664,19 +1737,7 @@ where
                 let cleaned = clean_json_text(&state.buffer);
                 let mut json_value: Value = serde_json::from_str(&cleaned)
                     .map_err(|e| StructuredError::parse_error(e, &cleaned))?;
-                let schema = T::gemini_schema();
-
-                // Prune null fields to handle Gemini's occasional nulls for optional fields
-                crate::schema::prune_null_fields(&mut json_value);
-
-                // Convert flat discriminator enums back to externally tagged format.
-                crate::schema::unflatten_externally_tagged_enums(&mut json_value, &schema);
-
-                // Coerce enum strings when the model returns close-but-invalid values.
-                crate::schema::coerce_enum_strings(&mut json_value, &schema);
-
-                // Recover internally-tagged enums that Gemini collapsed to strings
-                crate::schema::recover_internally_tagged_enums(&mut json_value, &schema);
+                state.normalize(&mut json_value);
 
                 // Apply user-provided response hook for custom transformations
                 if let Some(hook) = &state.response_hook {
@@ -685,12 +1746,10 @@ where
 
                 let parsed: T = serde_json::from_value(json_value)
                     .map_err(|e| StructuredError::parse_error(e, &cleaned))?;
-
-                if let Some(instr) = &state.refinement_instruction {
-                    return Err(StructuredError::Context(format!(
-                        "refine_with(\"{instr}\") is not supported in streaming mode yet"
-                    )));
-                }
+                let parsed = match &state.post_process {
+                    Some(transform) => transform(parsed),
+                    None => parsed,
+                };
 
                 let outcome = GenerationOutcome::new(
                     parsed,
@@ -701,6 +1760,11 @@ where
                     0,
                     1,
                 );
+                let outcome = if state.capture_raw {
+                    outcome.with_raw_text(Some(state.buffer.clone()), Some(cleaned))
+                } else {
+                    outcome
+                };
 
                 state.buffer.clear();
                 Ok(Some((StreamEvent::Complete(outcome), state)))
@@ -709,7 +1773,210 @@ where
     }
 }
 
-/// Helper to strip Markdown code blocks from the response text.
+/// Parse a possibly truncated buffer into `T` after closing whatever is still open.
+fn parse_partial<T: DeserializeOwned>(buffer: &str, normalize: impl Fn(&mut Value)) -> Option<T> {
+    let text = strip_code_fences(buffer.trim_start());
+    let start = text.find(['{', '['])?;
+    let mut value: Value = serde_json::from_str(&close_truncated_json(&text[start..])).ok()?;
+    normalize(&mut value);
+    serde_json::from_value(value).ok()
+}
+
+/// Complete a JSON prefix by closing an open string and every open array and object.
+///
+/// A trailing comma or dangling escape is dropped first. Prefixes that end mid-key or
+/// mid-literal stay invalid and are left for the next chunk.
+fn close_truncated_json(prefix: &str) -> String {
+    let mut closers = Vec::new();
+    let mut in_string = false;
+    let mut escaped = false;
+    for c in prefix.chars() {
+        if in_string {
+            if escaped {
+                escaped = false;
+            } else if c == '\\' {
+                escaped = true;
+            } else if c == '"' {
+                in_string = false;
+            }
+            continue;
+        }
+        match c {
+            '"' => in_string = true,
+            '{' => closers.push('}'),
+            '[' => closers.push(']'),
+            '}' | ']' => {
+                closers.pop();
+            }
+            _ => {}
+        }
+    }
+
+    let mut closed = prefix.to_string();
+    if escaped {
+        closed.pop();
+    }
+    if in_string {
+        closed.push('"');
+    } else {
+        let kept = closed.trim_end().trim_end_matches(',').len();
+        closed.truncate(kept);
+    }
+    closed.extend(closers.into_iter().rev());
+    closed
+}
+
+/// Refine each `Complete` value with `instruction`, announcing it with a `Refining` event.
+fn with_refinement<'s, T>(
+    inner: BoxStream<'s, Result<StreamEvent<T>>>,
+    client: &'s StructuredClient,
+    instruction: String,
+) -> BoxStream<'s, Result<StreamEvent<T>>>
+where
+    T: GeminiStructured
+        + StructuredValidator
+        + Serialize
+        + DeserializeOwned
+        + Clone
+        + Send
+        + Sync
+        + 'static,
+{
+    Box::pin(stream::unfold(
+        (inner, None::<GenerationOutcome<T>>),
+        move |(mut inner, pending)| {
+            let instruction = instruction.clone();
+            async move {
+                if let Some(mut outcome) = pending {
+                    debug!("Starting refinement step");
+                    let refined = client.refine(outcome.value, instruction).execute().await;
+                    let event = match refined {
+                        Ok(refinement) => {
+                            outcome.value = refinement.value;
+                            Ok(StreamEvent::Complete(outcome))
+                        }
+                        Err(err) => Err(err),
+                    };
+                    return Some((event, (inner, None)));
+                }
+                match inner.next().await? {
+                    Ok(StreamEvent::Complete(outcome)) => {
+                        Some((Ok(StreamEvent::Refining), (inner, Some(outcome))))
+                    }
+                    event => Some((event, (inner, None))),
+                }
+            }
+        },
+    ))
+}
+
+/// End `inner` with [`StructuredError::Timeout`] once `deadline` passes.
+fn with_deadline<'s, E: Send + 's>(
+    inner: BoxStream<'s, Result<E>>,
+    deadline: tokio::time::Instant,
+    limit: Duration,
+) -> BoxStream<'s, Result<E>> {
+    let sleep = Box::pin(tokio::time::sleep_until(deadline));
+    Box::pin(stream::unfold(
+        Some((inner, sleep)),
+        move |state| async move {
+            let (mut inner, mut sleep) = state?;
+            tokio::select! {
+                item = inner.next() => item.map(|item| (item, Some((inner, sleep)))),
+                _ = &mut sleep => Some((Err(StructuredError::Timeout { elapsed: limit }), None)),
+            }
+        },
+    ))
+}
+
+/// End `inner` with [`StructuredError::Cancelled`] once `token` is cancelled.
+fn until_cancelled<'s, E: Send + 's>(
+    inner: BoxStream<'s, Result<E>>,
+    token: CancellationToken,
+) -> BoxStream<'s, Result<E>> {
+    let cancelled = Box::pin(token.cancelled_owned());
+    Box::pin(stream::unfold(
+        Some((inner, cancelled)),
+        |state| async move {
+            let (mut inner, mut cancelled) = state?;
+            tokio::select! {
+                biased;
+                _ = &mut cancelled => Some((Err(StructuredError::Cancelled), None)),
+                item = inner.next() => item.map(|item| (item, Some((inner, cancelled)))),
+            }
+        },
+    ))
+}
+
+/// Standard instruction asking for natural-language values in `locale`.
+fn locale_instruction(locale: &str) -> String {
+    format!("Respond with all natural-language field values in {locale}.")
+}
+
+/// `system` with the locale instruction appended, as requests send it.
+pub(crate) fn localized_system_instruction(system: Option<String>, locale: &str) -> String {
+    let instruction = locale_instruction(locale);
+    match system {
+        Some(existing) => format!("{existing}\n\n{instruction}"),
+        None => instruction,
+    }
+}
+
+/// Execute one tool call, turning timeouts into an error payload for the model.
+#[instrument(skip_all, fields(tool = %call.name))]
+async fn dispatch_tool_call(
+    registry: &ToolRegistry,
+    call: &gemini_rust::tools::FunctionCall,
+    ctx: &Option<ToolContext>,
+) -> Result<Value> {
+    debug!(tool = %call.name, "Executing tool");
+    let result = registry
+        .execute_with_context(&call.name, call.args.clone(), ctx.clone())
+        .await;
+    match result {
+        Ok(value) => Ok(value),
+        Err(err @ StructuredError::ToolTimeout { .. }) => {
+            // Let the model recover (e.g. answer without the tool) instead of failing.
+            warn!(tool = %call.name, error = %err, "Tool timed out");
+            Ok(serde_json::json!({ "error": err.to_string() }))
+        }
+        Err(err) => Err(err),
+    }
+}
+
+/// Execute one round of tool calls, at most `max_concurrency` at a time, returning the
+/// results in call order.
+async fn dispatch_tool_calls(
+    registry: &ToolRegistry,
+    calls: &[gemini_rust::tools::FunctionCall],
+    ctx: &Option<ToolContext>,
+    max_concurrency: usize,
+) -> Result<Vec<Value>> {
+    if max_concurrency <= 1 {
+        let mut results = Vec::with_capacity(calls.len());
+        for call in calls {
+            results.push(dispatch_tool_call(registry, call, ctx).await?);
+        }
+        return Ok(results);
+    }
+
+    // A round never needs more permits than it has calls.
+    let semaphore = tokio::sync::Semaphore::new(max_concurrency.min(calls.len()));
+    futures::future::join_all(calls.iter().map(|call| {
+        let semaphore = &semaphore;
+        async move {
+            let _permit = semaphore
+                .acquire()
+                .await
+                .map_err(|e| StructuredError::Context(format!("Tool semaphore closed: {e}")))?;
+            dispatch_tool_call(registry, call, ctx).await
+        }
+    }))
+    .await
+    .into_iter()
+    .collect()
+}
+
 fn validation_errors_for<T: GeminiStructured>(value: &Value) -> Option<String> {
     let validator = compile_validator::<T>().ok()?;
     let errors: Vec<String> = validator
@@ -724,6 +1991,158 @@ fn validation_errors_for<T: GeminiStructured>(value: &Value) -> Option<String> {
     }
 }
 
+/// Incremental scanner that extracts completed elements of a streamed top-level array.
+///
+/// The target array is either the document itself or the `items` field of a top-level
+/// object. Text before the first `{` or `[` (such as a code fence) is ignored.
+#[derive(Default)]
+struct ArrayItemScanner {
+    buffer: String,
+    scanned: usize,
+    started: bool,
+    done: bool,
+    depth: usize,
+    in_string: bool,
+    escaped: bool,
+    /// Nesting depth at which the target array's elements live.
+    target_depth: Option<usize>,
+    element_start: Option<usize>,
+}
+
+impl ArrayItemScanner {
+    /// Append a chunk and return the raw JSON of every element completed by it.
+    fn push(&mut self, chunk: &str) -> Vec<String> {
+        self.buffer.push_str(chunk);
+        let mut items = Vec::new();
+        let start = self.scanned;
+        self.scanned = self.buffer.len();
+        if self.done {
+            return items;
+        }
+
+        for (i, c) in self.buffer[start..].char_indices() {
+            let i = start + i;
+            if !self.started {
+                if c == '{' || c == '[' {
+                    self.started = true;
+                    self.depth = 1;
+                    if c == '[' {
+                        self.target_depth = Some(1);
+                    }
+                }
+                continue;
+            }
+            if self.in_string {
+                if self.escaped {
+                    self.escaped = false;
+                } else if c == '\\' {
+                    self.escaped = true;
+                } else if c == '"' {
+                    self.in_string = false;
+                }
+                continue;
+            }
+
+            let at_target = self.target_depth == Some(self.depth);
+            if at_target
+                && self.element_start.is_none()
+                && !c.is_whitespace()
+                && c != ','
+                && c != ']'
+            {
+                self.element_start = Some(i);
+            }
+
+            match c {
+                '"' => self.in_string = true,
+                '{' | '[' => {
+                    if c == '[' && self.target_depth.is_none() && self.depth == 1 {
+                        let before = self.buffer[..i].trim_end();
+                        let key = before.strip_suffix(':').map(str::trim_end);
+                        if key.is_some_and(|k| k.ends_with("\"items\"")) {
+                            self.target_depth = Some(2);
+                            self.depth += 1;
+                            continue;
+                        }
+                    }
+                    self.depth += 1;
+                }
+                '}' | ']' => {
+                    if at_target && c == ']' {
+                        if let Some(s) = self.element_start.take() {
+                            items.push(self.buffer[s..i].trim().to_string());
+                        }
+                        self.done = true;
+                        break;
+                    }
+                    self.depth = self.depth.saturating_sub(1);
+                    if self.target_depth == Some(self.depth) {
+                        if let Some(s) = self.element_start.take() {
+                            items.push(self.buffer[s..=i].to_string());
+                        }
+                    }
+                }
+                ',' if at_target => {
+                    if let Some(s) = self.element_start.take() {
+                        items.push(self.buffer[s..i].trim().to_string());
+                    }
+                }
+                _ => {}
+            }
+        }
+        items
+    }
+}
+
+/// Default follow-up sent after an empty response.
+const EMPTY_RESPONSE_PROMPT: &str =
+    "The last response was empty. Return valid JSON matching the schema.";
+
+/// Follow-up sent after a response is cut off at the output token limit.
+const CONTINUATION_PROMPT: &str = "Your previous response was cut off at the output limit. \
+     Continue the JSON exactly where it stopped, without repeating any earlier text \
+     and without code fences.";
+
+/// Whether the first candidate stopped because it reached the output token limit.
+fn hit_max_tokens(response: &gemini_rust::GenerationResponse) -> bool {
+    response.candidates.first().is_some_and(|candidate| {
+        matches!(
+            candidate.finish_reason,
+            Some(gemini_rust::generation::model::FinishReason::MaxTokens)
+        )
+    })
+}
+
+/// Concatenate truncated fragments and the final response into one document.
+///
+/// Code fences around each fragment are removed; all other text, including whitespace
+/// at the seams, is kept so strings split mid-way reassemble exactly.
+fn join_continuations(fragments: &[String], last: &str) -> String {
+    if fragments.is_empty() {
+        return last.to_string();
+    }
+    fragments
+        .iter()
+        .map(String::as_str)
+        .chain([last])
+        .map(strip_code_fences)
+        .collect()
+}
+
+fn strip_code_fences(fragment: &str) -> &str {
+    let mut text = fragment;
+    if text.trim_start().starts_with("```") {
+        text = text
+            .trim_start()
+            .split_once('\n')
+            .map_or("", |(_, rest)| rest);
+    }
+    if let Some(stripped) = text.trim_end().strip_suffix("```") {
+        text = stripped;
+    }
+    text
+}
+
 /// Helper to strip Markdown code blocks from the response text.
 pub(crate) fn clean_json_text(text: &str) -> String {
     let text = text.trim();
@@ -755,8 +2174,855 @@ pub(crate) fn clean_json_text(text: &str) -> String {
     text.to_string()
 }
 
+/// Collect inline media parts from the first candidate of a response.
+fn collect_media(response: &gemini_rust::GenerationResponse) -> Vec<MediaPart> {
+    response
+        .candidates
+        .first()
+        .and_then(|candidate| candidate.content.parts.as_ref())
+        .map(|parts| {
+            parts
+                .iter()
+                .filter_map(|part| match part {
+                    Part::InlineData { inline_data } => Some(MediaPart {
+                        mime_type: inline_data.mime_type.clone(),
+                        data: inline_data.data.clone(),
+                    }),
+                    _ => None,
+                })
+                .collect()
+        })
+        .unwrap_or_default()
+}
+
 fn is_schema_depth_error(description: Option<&str>) -> bool {
     description
         .map(|desc| desc.contains("maximum allowed nesting depth"))
         .unwrap_or(false)
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StructuredClientBuilder;
+
+    #[test]
+    fn temperature_ramp_increases_per_attempt_up_to_max() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client
+            .request::<String>()
+            .temperature(0.0)
+            .temperature_ramp(0.3, 0.8);
+
+        let temps: Vec<f32> = (0..4)
+            .map(|attempt| request.config_for_attempt(attempt).temperature.unwrap())
+            .collect();
+
+        assert_eq!(temps[0], 0.0);
+        assert!((temps[1] - 0.3).abs() < f32::EPSILON);
+        assert!((temps[2] - 0.6).abs() < 1e-6);
+        assert!((temps[3] - 0.8).abs() < f32::EPSILON);
+    }
+
+    #[test]
+    fn parse_retries_drop_tools_unless_kept() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client.request::<String>().with_google_search();
+        assert_eq!(request.tools_for_attempt(0).len(), 1);
+        assert!(request.tools_for_attempt(1).is_empty());
+
+        let request = request.keep_tools_on_retry(true);
+        assert_eq!(request.tools_for_attempt(0).len(), 1);
+        assert_eq!(request.tools_for_attempt(2).len(), 1);
+    }
+
+    #[test]
+    fn inline_image_bytes_are_base64_encoded() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client
+            .request::<String>()
+            .user_image_bytes("Describe this", &[0x89, b'P', b'N', b'G'], "image/png")
+            .unwrap();
+
+        let parts = request.contents[0].parts.as_ref().unwrap();
+        match &parts[1] {
+            Part::InlineData { inline_data } => {
+                assert_eq!(inline_data.mime_type, "image/png");
+                assert_eq!(inline_data.data, "iVBORw==");
+            }
+            other => panic!("expected inline data, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn inline_bytes_reject_non_image_mime_types() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client.request::<String>();
+        let result = request.user_image_bytes("Read this", b"hello", "text/plain");
+
+        assert!(matches!(result, Err(StructuredError::Context(_))));
+    }
+
+    /// A registry whose `wait` tool sleeps for `args["ms"]` and echoes it, plus a counter
+    /// of the most calls that were in flight at once.
+    fn peak_tracking_registry() -> (ToolRegistry, Arc<std::sync::atomic::AtomicUsize>) {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let peak_in_handler = Arc::clone(&peak);
+        let registry = ToolRegistry::new().register_with_handler::<Value, Value, _, _>(
+            "wait",
+            "Sleep, then echo the delay",
+            move |args| {
+                let active = Arc::clone(&active);
+                let peak = Arc::clone(&peak_in_handler);
+                async move {
+                    let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                    peak.fetch_max(now, Ordering::SeqCst);
+                    tokio::time::sleep(Duration::from_millis(args["ms"].as_u64().unwrap())).await;
+                    active.fetch_sub(1, Ordering::SeqCst);
+                    Ok(args["ms"].clone())
+                }
+            },
+        );
+        (registry, peak)
+    }
+
+    fn wait_calls(delays: &[u64]) -> Vec<gemini_rust::tools::FunctionCall> {
+        delays
+            .iter()
+            .map(|ms| {
+                gemini_rust::tools::FunctionCall::new("wait", serde_json::json!({ "ms": ms }))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn model_override_clients_are_built_once() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_model(Model::Gemini25Flash)
+            .with_fallback_strategy(crate::FallbackStrategy::Escalate {
+                after_attempts: 1,
+                target: Model::Gemini25Pro,
+            })
+            .build()
+            .unwrap();
+
+        let primary = client.client_for_model(Model::Gemini25Flash).unwrap();
+        assert!(Arc::ptr_eq(&primary, &client.client));
+        let tier = client.client_for_model(Model::Gemini25Pro).unwrap();
+        assert!(Arc::ptr_eq(&tier, client.fallback_client.as_ref().unwrap()));
+
+        let first = client.client_for_model(Model::Gemini3Flash).unwrap();
+        let again = client
+            .clone()
+            .client_for_model(Model::Gemini3Flash)
+            .unwrap();
+        assert!(Arc::ptr_eq(&first, &again));
+    }
+
+    #[tokio::test]
+    async fn tool_calls_run_concurrently_and_keep_call_order() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client.request::<String>();
+        let (registry, peak) = peak_tracking_registry();
+        // Later calls finish first, so completion order differs from call order.
+        let calls = wait_calls(&[40, 30, 20, 10]);
+
+        let results = dispatch_tool_calls(&registry, &calls, &None, request.max_tool_concurrency)
+            .await
+            .unwrap();
+
+        assert_eq!(results, [40, 30, 20, 10]);
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 4);
+    }
+
+    #[tokio::test]
+    async fn tool_concurrency_cap_bounds_in_flight_calls() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let (registry, peak) = peak_tracking_registry();
+        let calls = wait_calls(&[20, 20, 20, 20, 20, 20]);
+
+        let request = client.request::<String>().max_tool_concurrency(3);
+        let results = dispatch_tool_calls(&registry, &calls, &None, request.max_tool_concurrency)
+            .await
+            .unwrap();
+        assert_eq!(results.len(), 6);
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 3);
+
+        let (registry, peak) = peak_tracking_registry();
+        let request = client.request::<String>().max_tool_concurrency(0);
+        dispatch_tool_calls(&registry, &calls, &None, request.max_tool_concurrency)
+            .await
+            .unwrap();
+        assert_eq!(peak.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn timed_out_tool_returns_structured_error_to_model() {
+        let registry = ToolRegistry::new()
+            .register_with_handler::<Value, Value, _, _>("slow", "Never finishes", |_| async {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+                Ok(Value::Null)
+            })
+            .with_default_timeout(Duration::from_millis(10));
+        let call = gemini_rust::tools::FunctionCall::new("slow", serde_json::json!({}));
+
+        let response = dispatch_tool_call(&registry, &call, &None).await.unwrap();
+        let error = response["error"].as_str().unwrap();
+        assert!(error.contains("'slow' timed out"), "{error}");
+    }
+
+    #[tokio::test]
+    async fn tool_context_reaches_context_aware_handlers() {
+        let registry = ToolRegistry::new().register_tool_with_ctx::<Value, String, _, _>(
+            "whoami",
+            "Current tenant",
+            |_, ctx| async move {
+                let tenant = ctx.as_deref().and_then(|ctx| ctx.downcast_ref::<String>());
+                Ok(tenant.cloned().unwrap_or_else(|| "anonymous".to_string()))
+            },
+        );
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client
+            .request::<String>()
+            .with_tool_context(Arc::new("acme".to_string()));
+        let call = gemini_rust::tools::FunctionCall::new("whoami", serde_json::json!({}));
+
+        let scoped = dispatch_tool_call(&registry, &call, &request.tool_context).await;
+        assert_eq!(scoped.unwrap(), "acme");
+        let unscoped = dispatch_tool_call(&registry, &call, &None).await;
+        assert_eq!(unscoped.unwrap(), "anonymous");
+    }
+
+    #[test]
+    fn no_ramp_keeps_configured_temperature() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client.request::<String>().temperature(0.2);
+
+        assert_eq!(request.config_for_attempt(3).temperature, Some(0.2));
+    }
+
+    #[tokio::test]
+    async fn continuation_reassembles_truncated_mock_fragments() {
+        let script = std::sync::Mutex::new(vec![
+            "\"more\"]}".to_string(),
+            "```json\n{\"items\": [\"one\", ".to_string(),
+        ]);
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(move |_| Ok(script.lock().unwrap().pop().unwrap()))
+            .build()
+            .unwrap();
+
+        let outcome = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .with_continuation(2)
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.value.items, vec!["one", "more"]);
+    }
+
+    #[test]
+    fn schema_transforms_replace_but_depth_limit_appends() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+
+        let replaced = client
+            .request::<String>()
+            .max_schema_depth(2)
+            .with_schema_transforms(vec![Box::new(crate::schema::CloseObjects)]);
+        assert_eq!(replaced.schema_transforms.len(), 1);
+
+        let appended = client
+            .request::<String>()
+            .with_schema_transforms(vec![Box::new(crate::schema::CloseObjects)])
+            .max_schema_depth(2);
+        assert_eq!(appended.schema_transforms.len(), 2);
+    }
+
+    #[tokio::test]
+    async fn continuation_accepts_fenced_complete_mock() {
+        let calls = Arc::new(std::sync::atomic::AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(move |_| {
+                counter.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+                Ok("```json\n{\"items\": [\"one\"]}\n```".to_string())
+            })
+            .build()
+            .unwrap();
+
+        let outcome = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .with_continuation(2)
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.value.items, vec!["one"]);
+        assert_eq!(calls.load(std::sync::atomic::Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn correlation_id_is_echoed_or_generated() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok("{\"items\": [\"one\"]}".to_string()))
+            .build()
+            .unwrap();
+
+        let tagged = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .with_correlation_id("req-42")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(tagged.correlation_id.as_deref(), Some("req-42"));
+
+        let generated = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .execute()
+            .await
+            .unwrap();
+        let id = generated.correlation_id.expect("generated correlation id");
+        assert!(uuid::Uuid::parse_str(&id).is_ok());
+    }
+
+    #[tokio::test]
+    async fn post_process_normalizes_execute_and_stream_values() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok("{\"items\": [\" B \", \"a\"]}".to_string()))
+            .build()
+            .unwrap();
+        let normalize = |mut list: crate::models::ListEnvelope<String>| {
+            list.items = list.items.iter().map(|s| s.trim().to_lowercase()).collect();
+            list.items.sort();
+            list
+        };
+
+        let outcome = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .post_process(normalize)
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(outcome.value.items, vec!["a", "b"]);
+
+        let events: Vec<_> = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .post_process(normalize)
+            .stream()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        match events.last() {
+            Some(Ok(StreamEvent::Complete(outcome))) => {
+                assert_eq!(outcome.value.items, vec!["a", "b"])
+            }
+            other => panic!("expected Complete, got {other:?}"),
+        }
+    }
+
+    #[derive(Debug, PartialEq, serde::Deserialize)]
+    struct Form {
+        name: String,
+        #[serde(default)]
+        tags: Vec<String>,
+    }
+
+    #[test]
+    fn partial_parses_track_incrementally_completing_json() {
+        let chunks = [
+            "```json\n{\"na",
+            "me\": \"Ad",
+            "a\", \"tags\": [\"x\",",
+            " \"y\"]}\n```",
+        ];
+        let mut buffer = String::new();
+        let partials: Vec<Option<Form>> = chunks
+            .iter()
+            .map(|chunk| {
+                buffer.push_str(chunk);
+                parse_partial(&buffer, |_| {})
+            })
+            .collect();
+
+        assert_eq!(partials[0], None);
+        assert_eq!(
+            partials[1],
+            Some(Form {
+                name: "Ad".to_string(),
+                tags: vec![]
+            })
+        );
+        assert_eq!(
+            partials[2],
+            Some(Form {
+                name: "Ada".to_string(),
+                tags: vec!["x".to_string()]
+            })
+        );
+        assert_eq!(
+            partials[3],
+            Some(Form {
+                name: "Ada".to_string(),
+                tags: vec!["x".to_string(), "y".to_string()]
+            })
+        );
+    }
+
+    #[test]
+    fn close_truncated_json_closes_strings_and_containers() {
+        assert_eq!(close_truncated_json(r#"{"a": [1, 2,"#), r#"{"a": [1, 2]}"#);
+        assert_eq!(close_truncated_json(r#"{"a": "x\"#), r#"{"a": "x"}"#);
+        assert_eq!(close_truncated_json(r#"[{"b": "}"#), r#"[{"b": "}"}]"#);
+    }
+
+    #[tokio::test]
+    async fn partial_events_are_opt_in() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok(r#"{"items": ["a"]}"#.to_string()))
+            .build()
+            .unwrap();
+        let count_partials = |events: Vec<Result<StreamEvent<_>>>| {
+            events
+                .iter()
+                .filter(|event| matches!(event, Ok(StreamEvent::Partial(_))))
+                .count()
+        };
+
+        let events: Vec<_> = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .stream()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(count_partials(events), 0);
+
+        let events: Vec<_> = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .with_partial_parsing()
+            .stream()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+        assert_eq!(count_partials(events), 1);
+    }
+
+    struct PatchGenerator;
+
+    #[async_trait::async_trait]
+    impl crate::TextGenerator for PatchGenerator {
+        async fn generate_text(
+            &self,
+            _system: Option<&str>,
+            _prompt: &str,
+            _config: GenerationConfig,
+        ) -> Result<String> {
+            Ok(r#"[{"op": "replace", "path": "/items/0", "value": "A"}]"#.to_string())
+        }
+    }
+
+    #[tokio::test]
+    async fn streamed_result_is_refined_before_completion() {
+        let engine = crate::RefinementEngine::from_generators(Arc::new(PatchGenerator), None);
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok(r#"{"items": ["a", "b"]}"#.to_string()))
+            .with_refinement_engine(engine)
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .refine_with("Uppercase the first item")
+            .stream()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert!(matches!(events[0], Ok(StreamEvent::Refining)));
+        match &events[1] {
+            Ok(StreamEvent::Complete(outcome)) => {
+                assert_eq!(outcome.value.items, vec!["A", "b"])
+            }
+            other => panic!("expected Complete, got {other:?}"),
+        }
+        assert_eq!(events.len(), 2);
+    }
+
+    #[test]
+    fn empty_response_handling_defaults_to_stock_prompt_without_escalation() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client.request::<String>();
+        assert!(request.empty_response_prompt.is_none());
+        assert!(!request.escalate_on_empty);
+
+        let request = request
+            .empty_response_prompt("La respuesta estaba vacía. Devuelve JSON válido.")
+            .escalate_on_empty_response(true);
+        assert_eq!(
+            request.empty_response_prompt.as_deref(),
+            Some("La respuesta estaba vacía. Devuelve JSON válido.")
+        );
+        assert!(request.escalate_on_empty);
+    }
+
+    #[test]
+    fn seed_comes_from_client_default_and_survives_retries() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_default_seed(7)
+            .build()
+            .unwrap();
+        let request = client.request::<String>();
+        assert_eq!(request.config_for_attempt(0).seed, Some(7));
+
+        let request = request.seed(42);
+        assert_eq!(request.config_for_attempt(2).seed, Some(42));
+    }
+
+    #[test]
+    fn stop_sequences_survive_per_attempt_config() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let request = client
+            .request::<String>()
+            .with_stop_sequences(vec!["<END>".to_string()])
+            .temperature_ramp(0.2, 1.0);
+
+        for attempt in 0..3 {
+            assert_eq!(
+                request.config_for_attempt(attempt).stop_sequences,
+                Some(vec!["<END>".to_string()])
+            );
+        }
+    }
+
+    #[test]
+    fn sampling_setters_override_earlier_generation_config() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let config = GenerationConfig {
+            top_k: Some(20),
+            stop_sequences: Some(vec!["###".to_string()]),
+            ..Default::default()
+        };
+
+        let request = client.request::<String>().with_generation_config(config);
+        let attempt = request.config_for_attempt(0);
+        assert_eq!(attempt.top_k, Some(20));
+        assert_eq!(attempt.stop_sequences, Some(vec!["###".to_string()]));
+
+        let request = request.top_k(5).stop_sequences(vec!["<END>".to_string()]);
+        let attempt = request.config_for_attempt(1);
+        assert_eq!(attempt.top_k, Some(5));
+        assert_eq!(attempt.stop_sequences, Some(vec!["<END>".to_string()]));
+
+        let replaced = request.with_generation_config(GenerationConfig::default());
+        let attempt = replaced.config_for_attempt(0);
+        assert_eq!(attempt.top_k, None);
+        assert_eq!(attempt.stop_sequences, None);
+    }
+
+    #[test]
+    fn tool_results_are_recorded_only_when_captured() {
+        let client = StructuredClientBuilder::new("test-key").build().unwrap();
+        let call = gemini_rust::tools::FunctionCall::new(
+            "get_stock_price",
+            serde_json::json!({"ticker": "AAPL"}),
+        );
+        let result = serde_json::json!({"price": 178.5});
+
+        let lean = client.request::<String>().tool_invocation(&call, &result);
+        assert_eq!(lean.name, "get_stock_price");
+        assert_eq!(lean.args["ticker"], "AAPL");
+        assert!(lean.result.is_none());
+
+        let full = client
+            .request::<String>()
+            .capture_tool_results(true)
+            .tool_invocation(&call, &result);
+        assert_eq!(full.result, Some(result));
+    }
+
+    #[tokio::test]
+    async fn locale_composes_with_system_instruction() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_default_locale("en-GB")
+            .with_mock(|req| Ok(serde_json::to_string(&req.system_instruction).unwrap()))
+            .build()
+            .unwrap();
+
+        let seen = client
+            .request::<String>()
+            .with_locale("fr-FR")
+            .system("Summarize the ticket.")
+            .user_text("hi")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(
+            seen.value,
+            "Summarize the ticket.\n\nRespond with all natural-language field values in fr-FR."
+        );
+
+        let default = client
+            .request::<String>()
+            .user_text("hi")
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(default.value, locale_instruction("en-GB"));
+    }
+
+    #[tokio::test]
+    async fn warm_cache_uses_the_localized_system_instruction() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_default_locale("en-GB")
+            .with_mock(|req| Ok(serde_json::to_string(&req.system_instruction).unwrap()))
+            .build()
+            .unwrap();
+
+        let sent = client
+            .request::<String>()
+            .system("Summarize the ticket.")
+            .user_text("hi")
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(
+            client.request_system_instruction("Summarize the ticket.".to_string()),
+            sent.value
+        );
+    }
+
+    #[tokio::test]
+    async fn deadline_ends_stalled_stream_with_timeout() {
+        let stalled: BoxStream<'static, Result<u8>> =
+            Box::pin(stream::iter([Ok(1)]).chain(stream::pending()));
+        let limit = Duration::from_millis(20);
+        let deadline = tokio::time::Instant::now() + limit;
+
+        let items: Vec<Result<u8>> = with_deadline(stalled, deadline, limit).collect().await;
+
+        assert_eq!(items.len(), 2);
+        assert_eq!(*items[0].as_ref().unwrap(), 1);
+        assert!(matches!(
+            items[1],
+            Err(StructuredError::Timeout { elapsed }) if elapsed == limit
+        ));
+    }
+
+    #[tokio::test]
+    async fn cancelled_token_aborts_execute() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok("\"done\"".to_string()))
+            .build()
+            .unwrap();
+        let token = CancellationToken::new();
+        token.cancel();
+
+        let err = client
+            .request::<String>()
+            .user_text("hi")
+            .with_cancellation(token)
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StructuredError::Cancelled));
+    }
+
+    #[tokio::test]
+    async fn cancellation_ends_stalled_stream() {
+        let stalled: BoxStream<'static, Result<u8>> =
+            Box::pin(stream::iter([Ok(1)]).chain(stream::pending()));
+        let token = CancellationToken::new();
+        let mut events = until_cancelled(stalled, token.clone());
+
+        assert_eq!(events.next().await.unwrap().unwrap(), 1);
+        token.cancel();
+        assert!(matches!(
+            events.next().await,
+            Some(Err(StructuredError::Cancelled))
+        ));
+        assert!(events.next().await.is_none());
+    }
+
+    #[tokio::test]
+    async fn slow_mock_exceeds_timeout() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| {
+                std::thread::sleep(Duration::from_millis(50));
+                Ok("\"done\"".to_string())
+            })
+            .build()
+            .unwrap();
+        let limit = Duration::from_millis(10);
+
+        let err = client
+            .request::<String>()
+            .user_text("hi")
+            .timeout(limit)
+            .execute()
+            .await
+            .unwrap_err();
+        assert!(matches!(
+            err,
+            StructuredError::Timeout { elapsed } if elapsed == limit
+        ));
+    }
+
+    #[tokio::test]
+    async fn timeout_passes_fast_requests_through() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok("\"done\"".to_string()))
+            .build()
+            .unwrap();
+
+        let outcome = client
+            .request::<String>()
+            .user_text("hi")
+            .timeout(Duration::from_secs(5))
+            .execute()
+            .await
+            .unwrap();
+        assert_eq!(outcome.value, "done");
+    }
+
+    #[tokio::test]
+    async fn capture_media_still_parses_the_text_part() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok("{\"items\": [\"chart\"]}".to_string()))
+            .build()
+            .unwrap();
+
+        let outcome = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("analyze and plot")
+            .capture_media(true)
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.value.items, vec!["chart"]);
+        assert!(outcome.media.is_empty());
+    }
+
+    #[test]
+    fn collect_media_reads_inline_parts_only() {
+        let response: gemini_rust::GenerationResponse = serde_json::from_value(serde_json::json!({
+            "candidates": [{
+                "content": {
+                    "role": "model",
+                    "parts": [
+                        {"text": "{\"items\": []}"},
+                        {"inlineData": {"mimeType": "image/png", "data": "aGk="}}
+                    ]
+                }
+            }]
+        }))
+        .unwrap();
+
+        let media = collect_media(&response);
+        assert_eq!(media.len(), 1);
+        assert_eq!(media[0].mime_type, "image/png");
+        assert_eq!(media[0].bytes().unwrap(), b"hi");
+    }
+
+    #[test]
+    fn join_continuations_keeps_seam_whitespace() {
+        let fragments = vec!["```json\n{\"text\": \"split ".to_string()];
+        assert_eq!(
+            join_continuations(&fragments, "here\"}\n```"),
+            "{\"text\": \"split here\"}\n"
+        );
+    }
+
+    #[test]
+    fn array_item_scanner_emits_elements_across_chunks() {
+        let mut scanner = ArrayItemScanner::default();
+        let mut items = Vec::new();
+        for chunk in [
+            "```json\n{\"items\": [{\"name\": \"a]\\\"\"}, {\"na",
+            "me\": \"b\"}, ",
+            "{\"name\": \"c\", \"tags\": [1, 2]}]}\n```",
+        ] {
+            items.extend(scanner.push(chunk));
+        }
+
+        assert_eq!(
+            items,
+            [
+                r#"{"name": "a]\""}"#,
+                r#"{"name": "b"}"#,
+                r#"{"name": "c", "tags": [1, 2]}"#
+            ]
+        );
+
+        let mut bare = ArrayItemScanner::default();
+        assert_eq!(bare.push("[1, \"two\", 3"), ["1", "\"two\""]);
+        assert_eq!(bare.push("]"), ["3"]);
+    }
+
+    #[tokio::test]
+    async fn stream_array_items_emits_items_then_complete() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok(r#"{"items": ["a", "b"]}"#.to_string()))
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .stream_array_items::<String>()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        let items: Vec<&String> = events
+            .iter()
+            .filter_map(|e| match e {
+                Ok(ArrayStreamEvent::Item(item)) => Some(item),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(items, ["a", "b"]);
+        assert!(matches!(
+            events.last(),
+            Some(Ok(ArrayStreamEvent::Stream(StreamEvent::Complete(_))))
+        ));
+    }
+
+    #[tokio::test]
+    async fn mock_stream_yields_only_complete() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|_| Ok(r#"{"items": ["a"]}"#.to_string()))
+            .build()
+            .unwrap();
+
+        let events: Vec<_> = client
+            .request::<crate::models::ListEnvelope<String>>()
+            .user_text("list")
+            .stream()
+            .await
+            .unwrap()
+            .collect()
+            .await;
+
+        assert_eq!(events.len(), 1);
+        assert!(matches!(events[0], Ok(StreamEvent::Complete(_))));
+    }
+}
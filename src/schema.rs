@@ -1,3 +1,5 @@
+use std::collections::HashMap;
+
 use schemars::{
     generate::{SchemaGenerator, SchemaSettings},
     JsonSchema,
@@ -48,6 +50,171 @@ pub fn schema_hash(value: &Value) -> String {
         .collect::<String>()
 }
 
+/// A single JSON Schema document holding the schemas of several structured types.
+///
+/// Each type is stored under `$defs` by its schema name, with nested definitions hoisted
+/// alongside it, so the bundle can be committed and shared with non-Rust consumers as a
+/// record of the exact shapes the model is asked to produce. Build one with
+/// [`gemini_schema_bundle!`](crate::gemini_schema_bundle) or [`SchemaBundle::add`].
+#[derive(Debug, Clone, Default)]
+pub struct SchemaBundle {
+    defs: Map<String, Value>,
+}
+
+impl SchemaBundle {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add `T`'s Gemini schema to the bundle.
+    pub fn add<T: GeminiStructured>(mut self) -> Self {
+        let name = T::schema_name().into_owned();
+        let mut schema = T::gemini_schema();
+        if let Some(map) = schema.as_object_mut() {
+            if let Some(Value::Object(defs)) = map.remove("$defs") {
+                for (def_name, def) in defs {
+                    self.defs.entry(def_name).or_insert(def);
+                }
+            }
+            map.remove("$schema");
+        }
+        // Self-references point at the type's own root, which is now a `$defs` entry.
+        rewrite_root_refs(&mut schema, &format!("#/$defs/{name}"));
+        self.defs.insert(name, schema);
+        self
+    }
+
+    /// Names of the bundled definitions, including hoisted nested types.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.defs.keys().map(String::as_str)
+    }
+
+    /// The bundle as a JSON Schema document.
+    pub fn to_value(&self) -> Value {
+        json!({
+            "$schema": "https://json-schema.org/draft/2020-12/schema",
+            "$defs": self.defs,
+        })
+    }
+
+    /// Write the bundle to `path` as pretty-printed JSON.
+    pub fn write_to(&self, path: impl AsRef<std::path::Path>) -> Result<()> {
+        let json = serde_json::to_string_pretty(&self.to_value())?;
+        std::fs::write(path, json)?;
+        Ok(())
+    }
+}
+
+fn rewrite_root_refs(value: &mut Value, target: &str) {
+    match value {
+        Value::Object(map) => {
+            for (key, child) in map.iter_mut() {
+                if key == "$ref" && child.as_str() == Some("#") {
+                    *child = Value::String(target.to_string());
+                } else {
+                    rewrite_root_refs(child, target);
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                rewrite_root_refs(item, target);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Build a [`SchemaBundle`] from a list of types.
+///
+/// ```rust,ignore
+/// let bundle = gemini_schema_bundle![Invoice, Contact, Forecast];
+/// bundle.write_to("schemas/contracts.json")?;
+/// ```
+#[macro_export]
+macro_rules! gemini_schema_bundle {
+    ($($ty:ty),+ $(,)?) => {
+        $crate::schema::SchemaBundle::new()$(.add::<$ty>())+
+    };
+}
+
+/// Environment variable that makes [`assert_schema_unchanged!`] rewrite golden files.
+#[cfg(any(test, feature = "testing"))]
+pub const UPDATE_SCHEMA_SNAPSHOTS_ENV: &str = "UPDATE_SCHEMA_SNAPSHOTS";
+
+/// Fail a test when `T`'s Gemini schema no longer matches a committed golden file.
+///
+/// The path is relative to the calling crate's manifest directory. After an intended
+/// change, rerun with `UPDATE_SCHEMA_SNAPSHOTS=1` to rewrite the golden and commit it.
+/// Requires the `testing` feature (enable it under `[dev-dependencies]`).
+///
+/// ```rust,ignore
+/// #[test]
+/// fn invoice_schema_is_stable() {
+///     assert_schema_unchanged!(Invoice, "tests/schemas/invoice.json");
+/// }
+/// ```
+#[cfg(any(test, feature = "testing"))]
+#[macro_export]
+macro_rules! assert_schema_unchanged {
+    ($ty:ty, $path:expr $(,)?) => {
+        $crate::schema::assert_schema_snapshot(
+            &<$ty as $crate::schema::GeminiStructured>::gemini_schema(),
+            ::std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join($path),
+        )
+    };
+}
+
+/// Compare `schema` against the golden JSON at `path`; backs [`assert_schema_unchanged!`].
+///
+/// Panics listing the changed JSON pointers on drift, or when the golden is missing.
+#[cfg(any(test, feature = "testing"))]
+#[doc(hidden)]
+pub fn assert_schema_snapshot(schema: &Value, path: impl AsRef<std::path::Path>) {
+    let update = std::env::var_os(UPDATE_SCHEMA_SNAPSHOTS_ENV).is_some();
+    check_schema_snapshot(schema, path.as_ref(), update);
+}
+
+#[cfg(any(test, feature = "testing"))]
+fn check_schema_snapshot(schema: &Value, path: &std::path::Path, update: bool) {
+    if update {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent).expect("create schema snapshot directory");
+        }
+        let json = serde_json::to_string_pretty(schema).expect("schema serializes");
+        std::fs::write(path, json + "\n").expect("write schema snapshot");
+        return;
+    }
+
+    let golden = std::fs::read_to_string(path).unwrap_or_else(|err| {
+        panic!(
+            "schema snapshot {} is unreadable ({err}); run with {UPDATE_SCHEMA_SNAPSHOTS_ENV}=1 to create it",
+            path.display()
+        )
+    });
+    let golden: Value = serde_json::from_str(&golden)
+        .unwrap_or_else(|err| panic!("schema snapshot {} is not JSON: {err}", path.display()));
+    if &golden == schema {
+        return;
+    }
+
+    let changes: Vec<String> = json_patch::diff(&golden, schema)
+        .0
+        .iter()
+        .map(|op| match op {
+            json_patch::PatchOperation::Add(op) => format!("added {}", op.path),
+            json_patch::PatchOperation::Remove(op) => format!("removed {}", op.path),
+            json_patch::PatchOperation::Replace(op) => format!("changed {}", op.path),
+            other => format!("{other:?}"),
+        })
+        .collect();
+    panic!(
+        "schema drifted from {}:\n  {}\nrerun with {UPDATE_SCHEMA_SNAPSHOTS_ENV}=1 if the change is intended",
+        path.display(),
+        changes.join("\n  ")
+    );
+}
+
 /// Optional logical validator that types can implement to enforce domain rules
 /// that JSON Schema cannot express.
 ///
@@ -77,6 +244,70 @@ pub trait GeminiValidator {
     fn gemini_validate(&self) -> Option<String>;
 }
 
+/// Reusable validation rule wrapping a closure, composable with other validators.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let currency = ValidatorFn::new(|inv: &Invoice| {
+///     (inv.currency.len() != 3).then(|| "currency must be an ISO 4217 code".to_string())
+/// });
+/// let rules = ValidatorFn::from_derived().and_then(currency);
+///
+/// client.refine(invoice, "Fix totals").with_validator(rules.into_fn());
+/// ```
+pub struct ValidatorFn<T: ?Sized>(std::sync::Arc<dyn Fn(&T) -> Option<String> + Send + Sync>);
+
+impl<T: ?Sized> Clone for ValidatorFn<T> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<T: ?Sized + 'static> ValidatorFn<T> {
+    pub fn new(f: impl Fn(&T) -> Option<String> + Send + Sync + 'static) -> Self {
+        Self(std::sync::Arc::new(f))
+    }
+
+    /// Wrap the rules generated by `#[derive(GeminiValidated)]`.
+    pub fn from_derived() -> Self
+    where
+        T: GeminiValidator,
+    {
+        Self::new(|value: &T| value.gemini_validate())
+    }
+
+    /// Run this validator. Returns `Some(message)` when invalid.
+    pub fn validate(&self, value: &T) -> Option<String> {
+        (self.0)(value)
+    }
+
+    /// Run `other` after this validator, concatenating both error messages.
+    pub fn and_then(self, other: ValidatorFn<T>) -> Self {
+        combine(vec![self, other])
+    }
+
+    /// Convert into a closure accepted by `with_validator`.
+    pub fn into_fn(self) -> impl Fn(&T) -> Option<String> + Send + Sync + 'static {
+        move |value: &T| self.validate(value)
+    }
+}
+
+/// Combine validators into one that runs each and joins their error messages with `; `.
+pub fn combine<T: ?Sized + 'static>(validators: Vec<ValidatorFn<T>>) -> ValidatorFn<T> {
+    ValidatorFn::new(move |value: &T| {
+        let errors: Vec<String> = validators
+            .iter()
+            .filter_map(|validator| validator.validate(value))
+            .collect();
+        if errors.is_empty() {
+            None
+        } else {
+            Some(errors.join("; "))
+        }
+    })
+}
+
 /// Recursively strip fields that Gemini strict schema mode does not support.
 pub fn clean_schema_for_gemini(value: &mut Value) {
     match value {
@@ -211,6 +442,307 @@ fn uniform_properties_schema(map: &Map<String, Value>) -> Option<Value> {
     }
 }
 
+/// A post-processing step applied to a response schema before it is sent to Gemini.
+///
+/// Transforms run in registration order on the schema generated by
+/// [`GeminiStructured::gemini_schema`], before the built-in cleanup strips
+/// unsupported keywords. Register them with
+/// [`StructuredRequest::with_schema_transforms`](crate::StructuredRequest::with_schema_transforms).
+pub trait SchemaTransform: Send + Sync {
+    fn apply(&self, schema: &mut Value);
+}
+
+impl<F> SchemaTransform for F
+where
+    F: Fn(&mut Value) + Send + Sync,
+{
+    fn apply(&self, schema: &mut Value) {
+        self(schema)
+    }
+}
+
+/// Collapse `oneOf`/`anyOf` lists of string constants into a single `enum`.
+///
+/// Documented unit enums are emitted as one `const` schema per variant, which the
+/// cleanup pass would otherwise strip down to unconstrained strings.
+#[derive(Clone, Debug, Default)]
+pub struct CollapseEnums;
+
+impl SchemaTransform for CollapseEnums {
+    fn apply(&self, schema: &mut Value) {
+        collapse_const_enums(schema);
+    }
+}
+
+fn collapse_const_enums(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                collapse_const_enums(value);
+            }
+
+            for key in ["oneOf", "anyOf"] {
+                let Some(variants) = map.get(key).and_then(|v| v.as_array()) else {
+                    continue;
+                };
+                let constants: Option<Vec<Value>> = variants
+                    .iter()
+                    .map(|variant| match variant.get("const") {
+                        Some(Value::String(s)) => Some(vec![Value::String(s.clone())]),
+                        _ => variant
+                            .get("enum")
+                            .and_then(|e| e.as_array())
+                            .filter(|e| e.iter().all(Value::is_string))
+                            .cloned(),
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .map(|groups| groups.into_iter().flatten().collect());
+
+                if let Some(constants) = constants.filter(|c| !c.is_empty()) {
+                    map.remove(key);
+                    map.insert("type".to_string(), Value::String("string".to_string()));
+                    map.insert("enum".to_string(), Value::Array(constants));
+                }
+            }
+        }
+        Value::Array(arr) => {
+            for value in arr {
+                collapse_const_enums(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Replace local `$ref`s with the referenced definition.
+///
+/// Recursive types are expanded up to `max_depth` levels; `$defs` is dropped once no
+/// references remain.
+#[derive(Clone, Debug)]
+pub struct InlineRefs {
+    pub max_depth: usize,
+}
+
+impl Default for InlineRefs {
+    fn default() -> Self {
+        Self { max_depth: 8 }
+    }
+}
+
+impl SchemaTransform for InlineRefs {
+    fn apply(&self, schema: &mut Value) {
+        let root = schema.clone();
+        inline_refs(schema, &root, self.max_depth);
+
+        if !contains_ref(schema) {
+            if let Some(map) = schema.as_object_mut() {
+                map.remove("$defs");
+                map.remove("definitions");
+            }
+        }
+    }
+}
+
+fn inline_refs(schema: &mut Value, root: &Value, remaining: usize) {
+    match schema {
+        Value::Object(map) => {
+            if remaining > 0 {
+                let resolved = map
+                    .get("$ref")
+                    .and_then(|v| v.as_str())
+                    .and_then(|reference| resolve_pointer(root, reference))
+                    .cloned();
+                if let Some(Value::Object(definition)) = resolved {
+                    map.remove("$ref");
+                    for (key, value) in definition {
+                        // Keep sibling keywords (e.g. a field description) over the definition's.
+                        map.entry(key).or_insert(value);
+                    }
+                    inline_refs(schema, root, remaining - 1);
+                    return;
+                }
+            }
+
+            for (key, value) in map.iter_mut() {
+                if key != "$defs" && key != "definitions" {
+                    inline_refs(value, root, remaining);
+                }
+            }
+        }
+        Value::Array(arr) => {
+            for value in arr {
+                inline_refs(value, root, remaining);
+            }
+        }
+        _ => {}
+    }
+}
+
+fn contains_ref(schema: &Value) -> bool {
+    match schema {
+        Value::Object(map) => map.iter().any(|(key, value)| {
+            key == "$ref" || (key != "$defs" && key != "definitions" && contains_ref(value))
+        }),
+        Value::Array(arr) => arr.iter().any(contains_ref),
+        _ => false,
+    }
+}
+
+/// Cap the data nesting depth of the schema sent to the model.
+///
+/// References are inlined first, then any object, array or union deeper than
+/// `max_depth` (counted like [`schema_depth`]) is replaced with a permissive schema of the
+/// same `type` and no structure below it. Smaller models complete complex types more
+/// reliably, at the cost of the server no longer enforcing the pruned levels; the
+/// response is still deserialized into the full type locally.
+#[derive(Clone, Debug)]
+pub struct LimitDepth {
+    pub max_depth: usize,
+}
+
+impl LimitDepth {
+    pub fn new(max_depth: usize) -> Self {
+        Self { max_depth }
+    }
+}
+
+impl SchemaTransform for LimitDepth {
+    fn apply(&self, schema: &mut Value) {
+        InlineRefs {
+            max_depth: self.max_depth + 1,
+        }
+        .apply(schema);
+        limit_depth(schema, 0, self.max_depth);
+
+        if !contains_ref(schema) {
+            if let Some(map) = schema.as_object_mut() {
+                map.remove("$defs");
+                map.remove("definitions");
+            }
+        }
+    }
+}
+
+fn limit_depth(schema: &mut Value, depth: usize, max_depth: usize) {
+    let Some(map) = schema.as_object_mut() else {
+        return;
+    };
+
+    if depth >= max_depth {
+        const NESTED: [&str; 6] = [
+            "properties",
+            "items",
+            "additionalProperties",
+            "anyOf",
+            "oneOf",
+            "$ref",
+        ];
+        let nested = NESTED
+            .iter()
+            .any(|key| map.get(*key).is_some_and(|v| !v.is_boolean()));
+        if nested {
+            let mut permissive = Map::new();
+            for key in ["type", "description", "nullable"] {
+                if let Some(value) = map.get(key) {
+                    permissive.insert(key.to_string(), value.clone());
+                }
+            }
+            *map = permissive;
+        }
+        return;
+    }
+
+    if let Some(props) = map.get_mut("properties").and_then(Value::as_object_mut) {
+        for sub_schema in props.values_mut() {
+            limit_depth(sub_schema, depth + 1, max_depth);
+        }
+    }
+    for key in ["items", "additionalProperties"] {
+        if let Some(sub_schema) = map.get_mut(key) {
+            limit_depth(sub_schema, depth + 1, max_depth);
+        }
+    }
+    for key in ["anyOf", "oneOf"] {
+        if let Some(variants) = map.get_mut(key).and_then(Value::as_array_mut) {
+            for variant in variants {
+                limit_depth(variant, depth, max_depth);
+            }
+        }
+    }
+}
+
+/// Mark every object schema with properties as `additionalProperties: false`
+/// unless it already says otherwise.
+#[derive(Clone, Debug, Default)]
+pub struct CloseObjects;
+
+impl SchemaTransform for CloseObjects {
+    fn apply(&self, schema: &mut Value) {
+        close_objects(schema);
+    }
+}
+
+fn close_objects(schema: &mut Value) {
+    match schema {
+        Value::Object(map) => {
+            for value in map.values_mut() {
+                close_objects(value);
+            }
+            if map.contains_key("properties") {
+                map.entry("additionalProperties")
+                    .or_insert(Value::Bool(false));
+            }
+        }
+        Value::Array(arr) => {
+            for value in arr {
+                close_objects(value);
+            }
+        }
+        _ => {}
+    }
+}
+
+/// Inject descriptions for fields addressed by dot-separated property paths
+/// (e.g. `"customer.email"`).
+///
+/// Paths follow nested `properties`; combine with [`InlineRefs`] to reach fields of
+/// referenced types. Existing descriptions are replaced.
+#[derive(Clone, Debug, Default)]
+pub struct DescribeFields {
+    descriptions: Vec<(String, String)>,
+}
+
+impl DescribeFields {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the description for the field at `path`.
+    pub fn field(mut self, path: impl Into<String>, description: impl Into<String>) -> Self {
+        self.descriptions.push((path.into(), description.into()));
+        self
+    }
+}
+
+impl SchemaTransform for DescribeFields {
+    fn apply(&self, schema: &mut Value) {
+        for (path, description) in &self.descriptions {
+            let target = path.split('.').try_fold(&mut *schema, |node, segment| {
+                node.get_mut("properties")?.get_mut(segment)
+            });
+            match target.and_then(Value::as_object_mut) {
+                Some(field) => {
+                    field.insert(
+                        "description".to_string(),
+                        Value::String(description.clone()),
+                    );
+                }
+                None => warn!(path = %path, "DescribeFields: no schema property at path"),
+            }
+        }
+    }
+}
+
 /// Returns the max logical nesting depth of the data structure described by the schema.
 /// This ignores JSON Schema syntax nesting and counts actual data layers.
 pub fn schema_depth(value: &Value) -> usize {
@@ -271,6 +803,127 @@ pub fn warn_if_schema_too_deep(schema: &Value, max_depth: usize) {
 /// Updated based on logical depth calculation.
 pub const STRICT_SCHEMA_DEPTH_LIMIT: usize = 5;
 
+/// Nesting depth after which [`schema_example`] falls back to `null` for recursive schemas.
+const EXAMPLE_MAX_DEPTH: usize = 8;
+
+/// Build a minimal instance of `schema`, for dry runs and schema-derived mock responses.
+///
+/// Uses `const`, `default`, the first of `examples` or `enum` when present. Otherwise
+/// objects get every property, arrays get `minItems` elements (at least one), strings a
+/// placeholder matching their `format`, and numbers their `minimum`. Nullable unions
+/// pick the non-null branch until recursion gets deep, then `null`.
+pub fn schema_example(schema: &Value) -> Value {
+    example_with_root(schema, schema, 0)
+}
+
+fn example_with_root(schema: &Value, root: &Value, depth: usize) -> Value {
+    if depth > EXAMPLE_MAX_DEPTH {
+        return Value::Null;
+    }
+    let Some(obj) = deref_schema(schema, root).as_object() else {
+        return Value::Null;
+    };
+
+    let preset = obj.get("const").or_else(|| obj.get("default")).or_else(|| {
+        ["examples", "enum"]
+            .iter()
+            .find_map(|key| obj.get(*key)?.as_array()?.first())
+    });
+    if let Some(value) = preset {
+        return value.clone();
+    }
+
+    for key in ["oneOf", "anyOf"] {
+        if let Some(variants) = obj.get(key).and_then(Value::as_array) {
+            let is_null = |v: &&Value| deref_schema(v, root).get("type") == Some(&json!("null"));
+            let prefer_null = depth >= EXAMPLE_MAX_DEPTH / 2;
+            let variant = variants
+                .iter()
+                .find(|v| is_null(v) == prefer_null)
+                .or(variants.first());
+            if let Some(variant) = variant {
+                return example_with_root(variant, root, depth + 1);
+            }
+        }
+    }
+
+    if let Some(parts) = obj.get("allOf").and_then(Value::as_array) {
+        let mut merged = Map::new();
+        for part in parts {
+            match example_with_root(part, root, depth + 1) {
+                Value::Object(fields) => merged.extend(fields),
+                other => return other,
+            }
+        }
+        return Value::Object(merged);
+    }
+
+    let ty = match obj.get("type") {
+        Some(Value::String(ty)) => ty.as_str(),
+        Some(Value::Array(types)) => {
+            let non_null = types
+                .iter()
+                .filter_map(Value::as_str)
+                .find(|t| *t != "null");
+            match non_null {
+                Some(ty) if depth < EXAMPLE_MAX_DEPTH / 2 => ty,
+                _ => "null",
+            }
+        }
+        _ if obj.contains_key("properties") => "object",
+        _ if obj.contains_key("items") || obj.contains_key("prefixItems") => "array",
+        _ => "null",
+    };
+
+    match ty {
+        "object" => {
+            let mut fields = Map::new();
+            if let Some(props) = obj.get("properties").and_then(Value::as_object) {
+                for (name, prop) in props {
+                    fields.insert(name.clone(), example_with_root(prop, root, depth + 1));
+                }
+            }
+            Value::Object(fields)
+        }
+        "array" => {
+            if let Some(prefix) = obj.get("prefixItems").and_then(Value::as_array) {
+                return Value::Array(
+                    prefix
+                        .iter()
+                        .map(|item| example_with_root(item, root, depth + 1))
+                        .collect(),
+                );
+            }
+            let count = obj
+                .get("minItems")
+                .and_then(Value::as_u64)
+                .unwrap_or(1)
+                .max(1);
+            let item = obj
+                .get("items")
+                .map(|items| example_with_root(items, root, depth + 1))
+                .unwrap_or(Value::Null);
+            Value::Array(vec![item; count as usize])
+        }
+        "string" => {
+            let placeholder = match obj.get("format").and_then(Value::as_str) {
+                Some("date") => "2024-01-01",
+                Some("date-time") => "2024-01-01T00:00:00Z",
+                Some("time") => "00:00:00",
+                Some("email") => "user@example.com",
+                Some("uri") => "https://example.com",
+                Some("uuid") => "00000000-0000-0000-0000-000000000000",
+                _ => "example",
+            };
+            Value::String(placeholder.to_string())
+        }
+        "integer" => json!(obj.get("minimum").and_then(Value::as_i64).unwrap_or(0)),
+        "number" => json!(obj.get("minimum").and_then(Value::as_f64).unwrap_or(0.0)),
+        "boolean" => Value::Bool(false),
+        _ => Value::Null,
+    }
+}
+
 /// Recursively remove object keys where the value is null.
 pub fn prune_null_fields(value: &mut Value) {
     match value {
@@ -289,6 +942,76 @@ pub fn prune_null_fields(value: &mut Value) {
     }
 }
 
+/// Reserved field holding the key of a map entry encoded as an array element.
+pub const MAP_ENTRY_KEY: &str = "__key__";
+/// Reserved field holding the value of a map entry encoded as an array element.
+pub const MAP_ENTRY_VALUE: &str = "__value__";
+
+/// Convert arrays of `{"__key__": k, "__value__": v}` entries back into objects.
+///
+/// An array is converted only when it is non-empty and every element is an object with
+/// exactly the reserved [`MAP_ENTRY_KEY`] and [`MAP_ENTRY_VALUE`] fields and a string
+/// key. Arrays of objects with ordinary `key`/`value` fields, or with extra fields next
+/// to the reserved ones, are left untouched.
+pub fn normalize_map_entry_arrays(value: &mut Value) {
+    match value {
+        Value::Object(map) => {
+            for v in map.values_mut() {
+                normalize_map_entry_arrays(v);
+            }
+        }
+        Value::Array(items) => {
+            for v in items.iter_mut() {
+                normalize_map_entry_arrays(v);
+            }
+            if !items.is_empty() && items.iter().all(is_map_entry) {
+                let entries = items.drain(..).filter_map(|item| match item {
+                    Value::Object(mut entry) => {
+                        let key = entry.remove(MAP_ENTRY_KEY)?.as_str()?.to_string();
+                        Some((key, entry.remove(MAP_ENTRY_VALUE)?))
+                    }
+                    _ => None,
+                });
+                *value = Value::Object(entries.collect());
+            }
+        }
+        _ => {}
+    }
+}
+
+fn is_map_entry(item: &Value) -> bool {
+    item.as_object().is_some_and(|entry| {
+        entry.len() == 2
+            && entry.get(MAP_ENTRY_KEY).is_some_and(Value::is_string)
+            && entry.contains_key(MAP_ENTRY_VALUE)
+    })
+}
+
+/// Fill fields missing from `value` with user-supplied defaults.
+///
+/// Keys are top-level field names (`"currency"`) or JSON pointers
+/// (`"/totals/currency"`). A default is inserted only when the parent object exists and
+/// the field is absent; values the model did return are never overwritten.
+pub fn inject_defaults(value: &mut Value, defaults: &HashMap<String, Value>) {
+    for (key, default) in defaults {
+        let pointer = if key.starts_with('/') {
+            key.clone()
+        } else {
+            format!("/{}", key.replace('~', "~0").replace('/', "~1"))
+        };
+        let Some((parent, field)) = pointer.rsplit_once('/') else {
+            continue;
+        };
+        let field = field.replace("~1", "/").replace("~0", "~");
+        if let Some(Value::Object(map)) = value.pointer_mut(parent) {
+            if !map.contains_key(&field) {
+                debug!(field = %key, "Injecting default for missing field");
+                map.insert(field, default.clone());
+            }
+        }
+    }
+}
+
 const TAG_FIELD_NAMES: &[&str] = &["type", "kind", "model", "variant", "tag"];
 
 /// Recursively attempts to recover internally tagged enums where the LLM
@@ -639,6 +1362,229 @@ fn enum_candidates(schema: &Value) -> Vec<String> {
     values
 }
 
+/// Fix the casing of enum values and externally tagged enum keys.
+///
+/// Strings that differ from a declared enum value only in case or separators
+/// (`"auto"` vs `"Auto"`, `"LAST_VALUE"` vs `"LastValue"`) are rewritten to the declared
+/// value, as are single keys of objects matching an externally tagged variant. Values are
+/// only changed when exactly one declared value matches; unlike [`coerce_enum_strings`]
+/// this never guesses from prefixes or edit distance.
+pub fn normalize_enum_case(value: &mut Value, schema: &Value) {
+    normalize_enum_case_with_root(value, schema, schema);
+}
+
+fn normalize_enum_case_with_root(value: &mut Value, schema: &Value, root: &Value) {
+    let schema = deref_schema(schema, root);
+    let variants: Vec<&Value> = schema
+        .get("anyOf")
+        .or_else(|| schema.get("oneOf"))
+        .and_then(|v| v.as_array())
+        .map(|variants| variants.iter().map(|v| deref_schema(v, root)).collect())
+        .unwrap_or_default();
+
+    match value {
+        Value::String(s) => {
+            let mut candidates = enum_candidates(schema);
+            for variant in &variants {
+                candidates.extend(enum_candidates(variant));
+            }
+            if candidates.iter().any(|c| c == s) {
+                return;
+            }
+            if let Some(matched) = unique_case_match(s, &candidates) {
+                debug!(value.from = %s, value.to = %matched, "Normalized enum value case");
+                *s = matched;
+            }
+        }
+        Value::Array(arr) => {
+            if let Some(items_schema) = schema.get("items") {
+                for item in arr.iter_mut() {
+                    normalize_enum_case_with_root(item, items_schema, root);
+                }
+            } else if let Some(prefix_items) = schema.get("prefixItems").and_then(|v| v.as_array())
+            {
+                for (item, sub_schema) in arr.iter_mut().zip(prefix_items) {
+                    normalize_enum_case_with_root(item, sub_schema, root);
+                }
+            }
+        }
+        Value::Object(map) => {
+            if map.len() == 1 && !variants.is_empty() {
+                let tags: Vec<String> = variants
+                    .iter()
+                    .filter_map(|variant| external_tag_name(variant))
+                    .collect();
+                let key = map.keys().next().cloned().unwrap_or_default();
+                if !tags.contains(&key) {
+                    if let Some(tag) = unique_case_match(&key, &tags) {
+                        debug!(value.from = %key, value.to = %tag, "Normalized enum tag case");
+                        if let Some(inner) = map.remove(&key) {
+                            map.insert(tag, inner);
+                        }
+                    }
+                }
+            }
+
+            if let Some(props) = schema.get("properties").and_then(|v| v.as_object()) {
+                let additional = schema.get("additionalProperties");
+                for (k, v) in map.iter_mut() {
+                    if let Some(sub_schema) = props.get(k).or(additional) {
+                        normalize_enum_case_with_root(v, sub_schema, root);
+                    }
+                }
+            } else if !variants.is_empty() {
+                let keys: Vec<&String> = map.keys().collect();
+                let selected = variants.iter().find(|variant| {
+                    variant
+                        .get("properties")
+                        .and_then(|p| p.as_object())
+                        .is_some_and(|props| keys.iter().all(|key| props.contains_key(*key)))
+                });
+                if let Some(variant) = selected {
+                    normalize_enum_case_with_root(value, variant, root);
+                }
+            } else if let Some(additional) = schema.get("additionalProperties") {
+                for v in map.values_mut() {
+                    normalize_enum_case_with_root(v, additional, root);
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// JSON pointers of required string fields in `value` that are empty.
+///
+/// Empty strings in required fields usually mean the model gave up on extraction. With
+/// `whitespace_is_empty`, whitespace-only strings count as empty too. Fields with an
+/// `enum` or `const` constraint are skipped, since an empty value there is deliberate.
+pub fn assert_non_empty_strings(
+    value: &Value,
+    schema: &Value,
+    whitespace_is_empty: bool,
+) -> Vec<String> {
+    let mut empty = Vec::new();
+    collect_empty_strings(value, schema, schema, "", whitespace_is_empty, &mut empty);
+    empty
+}
+
+fn collect_empty_strings(
+    value: &Value,
+    schema: &Value,
+    root: &Value,
+    path: &str,
+    whitespace_is_empty: bool,
+    empty: &mut Vec<String>,
+) {
+    let schema = deref_schema(schema, root);
+    match value {
+        Value::Array(items) => {
+            if let Some(items_schema) = schema.get("items") {
+                for (index, item) in items.iter().enumerate() {
+                    let item_path = format!("{path}/{index}");
+                    collect_empty_strings(
+                        item,
+                        items_schema,
+                        root,
+                        &item_path,
+                        whitespace_is_empty,
+                        empty,
+                    );
+                }
+            }
+        }
+        Value::Object(map) => {
+            if let Some(props) = schema.get("properties").and_then(|v| v.as_object()) {
+                let required: Vec<&str> = schema
+                    .get("required")
+                    .and_then(|v| v.as_array())
+                    .map(|r| r.iter().filter_map(|k| k.as_str()).collect())
+                    .unwrap_or_default();
+                for (key, child) in map {
+                    let Some(sub_schema) = props.get(key) else {
+                        continue;
+                    };
+                    let child_path =
+                        format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                    let sub_schema = deref_schema(sub_schema, root);
+                    if let Value::String(s) = child {
+                        let is_plain_string = sub_schema.get("type").and_then(|t| t.as_str())
+                            == Some("string")
+                            && sub_schema.get("enum").is_none()
+                            && sub_schema.get("const").is_none();
+                        let is_empty = if whitespace_is_empty {
+                            s.trim().is_empty()
+                        } else {
+                            s.is_empty()
+                        };
+                        if is_plain_string && is_empty && required.contains(&key.as_str()) {
+                            empty.push(child_path);
+                        }
+                    } else {
+                        collect_empty_strings(
+                            child,
+                            sub_schema,
+                            root,
+                            &child_path,
+                            whitespace_is_empty,
+                            empty,
+                        );
+                    }
+                }
+            } else if let Some(variants) = schema
+                .get("anyOf")
+                .or_else(|| schema.get("oneOf"))
+                .and_then(|v| v.as_array())
+            {
+                let variants: Vec<Value> = variants
+                    .iter()
+                    .map(|v| deref_schema(v, root).clone())
+                    .collect();
+                if let Some(variant) = select_variant_for_object(map, &variants) {
+                    collect_empty_strings(value, variant, root, path, whitespace_is_empty, empty);
+                }
+            } else if let Some(additional) = schema.get("additionalProperties") {
+                for (key, child) in map {
+                    let child_path =
+                        format!("{path}/{}", key.replace('~', "~0").replace('/', "~1"));
+                    collect_empty_strings(
+                        child,
+                        additional,
+                        root,
+                        &child_path,
+                        whitespace_is_empty,
+                        empty,
+                    );
+                }
+            }
+        }
+        _ => {}
+    }
+}
+
+/// The single required property of an externally tagged enum variant.
+fn external_tag_name(variant: &Value) -> Option<String> {
+    let required = variant.get("required")?.as_array()?;
+    let props = variant.get("properties")?.as_object()?;
+    match (required.as_slice(), props.len()) {
+        ([tag], 1) => tag.as_str().map(str::to_string),
+        _ => None,
+    }
+}
+
+/// The only candidate equal to `input` ignoring case and separators, if unambiguous.
+fn unique_case_match(input: &str, candidates: &[String]) -> Option<String> {
+    let input_norm = normalize_tag(input);
+    let mut matches = candidates
+        .iter()
+        .filter(|candidate| normalize_tag(candidate) == input_norm);
+    let first = matches.next()?;
+    if matches.any(|other| other != first) {
+        return None;
+    }
+    Some(first.clone())
+}
+
 fn deref_schema<'a>(schema: &'a Value, root: &'a Value) -> &'a Value {
     if let Some(reference) = schema.get("$ref").and_then(|v| v.as_str()) {
         if let Some(resolved) = resolve_pointer(root, reference) {
@@ -830,6 +1776,96 @@ fn levenshtein(a: &str, b: &str) -> usize {
     curr[b_len]
 }
 
+/// A single field-level difference between two values.
+///
+/// `before` is `None` for added fields and `after` is `None` for removed ones.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FieldChange {
+    /// Human-readable path such as `items[0].name`.
+    pub path: String,
+    pub before: Option<Value>,
+    pub after: Option<Value>,
+}
+
+impl std::fmt::Display for FieldChange {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match (&self.before, &self.after) {
+            (None, Some(after)) => write!(f, "{}: added {}", self.path, format_change_value(after)),
+            (Some(before), None) => {
+                write!(f, "{}: removed {}", self.path, format_change_value(before))
+            }
+            (Some(before), Some(after)) => write!(
+                f,
+                "{}: {} -> {}",
+                self.path,
+                format_change_value(before),
+                format_change_value(after)
+            ),
+            (None, None) => write!(f, "{}: unchanged", self.path),
+        }
+    }
+}
+
+/// Compute a field-level change report between two values.
+///
+/// Built on [`json_patch::diff`], but each change carries the resolved value from both
+/// sides and a dotted path (`/items/0/name` becomes `items[0].name`) suitable for
+/// audit logs.
+pub fn diff_report<T: serde::Serialize>(old: &T, new: &T) -> Result<Vec<FieldChange>> {
+    let old = serde_json::to_value(old)?;
+    let new = serde_json::to_value(new)?;
+    let patch = json_patch::diff(&old, &new);
+
+    Ok(patch
+        .0
+        .iter()
+        .map(|op| {
+            let pointer = match op {
+                json_patch::PatchOperation::Add(op) => op.path.to_string(),
+                json_patch::PatchOperation::Remove(op) => op.path.to_string(),
+                json_patch::PatchOperation::Replace(op) => op.path.to_string(),
+                json_patch::PatchOperation::Move(op) => op.path.to_string(),
+                json_patch::PatchOperation::Copy(op) => op.path.to_string(),
+                json_patch::PatchOperation::Test(op) => op.path.to_string(),
+            };
+            FieldChange {
+                path: friendly_path(&pointer),
+                before: old.pointer(&pointer).cloned(),
+                after: new.pointer(&pointer).cloned(),
+            }
+        })
+        .collect())
+}
+
+/// Render a JSON Pointer as a dotted path with bracketed array indices.
+fn friendly_path(pointer: &str) -> String {
+    if pointer.is_empty() {
+        return "(root)".to_string();
+    }
+    let mut out = String::new();
+    for segment in pointer.trim_start_matches('/').split('/') {
+        let segment = segment.replace("~1", "/").replace("~0", "~");
+        if !segment.is_empty() && segment.chars().all(|c| c.is_ascii_digit()) {
+            out.push_str(&format!("[{segment}]"));
+        } else {
+            if !out.is_empty() {
+                out.push('.');
+            }
+            out.push_str(&segment);
+        }
+    }
+    out
+}
+
+fn format_change_value(value: &Value) -> String {
+    match value {
+        Value::String(s) => format!("\"{s}\""),
+        Value::Array(arr) => format!("[{} items]", arr.len()),
+        Value::Object(_) => value.to_string(),
+        other => other.to_string(),
+    }
+}
+
 /// Convert an OpenAPI-style schema (with nullable: true) to a standard JSON Schema
 /// (with type: [T, "null"]) for compatibility with the jsonschema crate.
 fn to_standard_json_schema(mut schema: Value) -> Value {
@@ -1059,4 +2095,378 @@ mod tests {
         let schema = Contact::gemini_schema();
         assert!(schema.get("properties").is_some());
     }
+
+    #[test]
+    fn diff_report_lists_added_removed_and_changed_fields() {
+        let old = json!({"name": "Acme", "tier": "basic", "tags": ["a"]});
+        let new = json!({"name": "Acme Ltd", "tags": ["a", "b"], "owner": {"id": 7}});
+
+        let changes = diff_report(&old, &new).unwrap();
+
+        let find = |path: &str| changes.iter().find(|c| c.path == path).cloned();
+        let name = find("name").expect("name change");
+        assert_eq!(name.before, Some(json!("Acme")));
+        assert_eq!(name.after, Some(json!("Acme Ltd")));
+        assert_eq!(name.to_string(), "name: \"Acme\" -> \"Acme Ltd\"");
+
+        let tier = find("tier").expect("tier removal");
+        assert_eq!(tier.after, None);
+
+        let tag = find("tags[1]").expect("array append");
+        assert_eq!(tag.before, None);
+        assert_eq!(tag.after, Some(json!("b")));
+
+        let owner = find("owner").expect("nested add");
+        assert_eq!(owner.after, Some(json!({"id": 7})));
+    }
+
+    #[test]
+    fn diff_report_renders_nested_paths() {
+        let old = json!({"items": [{"qty": 1}]});
+        let new = json!({"items": [{"qty": 2}]});
+
+        let changes = diff_report(&old, &new).unwrap();
+
+        assert_eq!(changes.len(), 1);
+        assert_eq!(changes[0].path, "items[0].qty");
+        assert_eq!(changes[0].to_string(), "items[0].qty: 1 -> 2");
+    }
+
+    #[test]
+    fn combined_validators_concatenate_errors() {
+        let positive =
+            ValidatorFn::new(|n: &i32| (*n <= 0).then(|| "must be positive".to_string()));
+        let even = ValidatorFn::new(|n: &i32| (n % 2 != 0).then(|| "must be even".to_string()));
+        let small = ValidatorFn::new(|n: &i32| (*n > 100).then(|| "must be <= 100".to_string()));
+
+        let rules = positive.and_then(combine(vec![even, small]));
+
+        assert_eq!(rules.validate(&4), None);
+        assert_eq!(
+            rules.validate(&-3).as_deref(),
+            Some("must be positive; must be even")
+        );
+        assert_eq!(
+            rules.validate(&101).as_deref(),
+            Some("must be even; must be <= 100")
+        );
+
+        let as_fn = rules.into_fn();
+        assert_eq!(as_fn(&2), None);
+    }
+
+    #[test]
+    fn collapse_enums_merges_const_variants() {
+        let mut schema = json!({
+            "oneOf": [
+                { "type": "string", "const": "Low", "description": "Low priority" },
+                { "type": "string", "enum": ["Medium", "High"] }
+            ]
+        });
+
+        CollapseEnums.apply(&mut schema);
+
+        assert_eq!(
+            schema,
+            json!({ "type": "string", "enum": ["Low", "Medium", "High"] })
+        );
+    }
+
+    #[test]
+    fn transforms_inline_and_close_objects() {
+        let mut schema = json!({
+            "type": "object",
+            "properties": {
+                "owner": { "$ref": "#/$defs/Person", "description": "Account owner" }
+            },
+            "$defs": {
+                "Person": {
+                    "type": "object",
+                    "properties": { "name": { "type": "string" } },
+                    "description": "A person"
+                }
+            }
+        });
+
+        let transforms: Vec<Box<dyn SchemaTransform>> = vec![
+            Box::new(InlineRefs::default()),
+            Box::new(CloseObjects),
+            Box::new(DescribeFields::new().field("owner.name", "Full legal name")),
+        ];
+        for transform in &transforms {
+            transform.apply(&mut schema);
+        }
+
+        assert!(schema.get("$defs").is_none());
+        let owner = &schema["properties"]["owner"];
+        assert_eq!(owner["description"], "Account owner");
+        assert_eq!(owner["additionalProperties"], json!(false));
+        assert_eq!(
+            owner["properties"]["name"]["description"],
+            "Full legal name"
+        );
+        assert_eq!(schema["additionalProperties"], json!(false));
+    }
+
+    #[test]
+    fn normalize_enum_case_fixes_only_unambiguous_values() {
+        let schema = json!({
+            "type": "object",
+            "properties": {
+                "mode": {"type": "string", "enum": ["Auto", "Manual"]},
+                "fill": {"type": "string", "enum": ["LastValue", "Zero"]},
+                "ambiguous": {"type": "string", "enum": ["ab", "AB"]},
+                "method": {
+                    "oneOf": [
+                        {
+                            "type": "object",
+                            "properties": {"Growth": {"type": "number"}},
+                            "required": ["Growth"]
+                        },
+                        {"type": "string", "enum": ["Flat"]}
+                    ]
+                }
+            }
+        });
+        let mut value = json!({
+            "mode": "auto",
+            "fill": "LAST_VALUE",
+            "ambiguous": "Ab",
+            "method": {"growth": 0.1}
+        });
+
+        normalize_enum_case(&mut value, &schema);
+
+        assert_eq!(value["mode"], "Auto");
+        assert_eq!(value["fill"], "LastValue");
+        assert_eq!(value["ambiguous"], "Ab");
+        assert_eq!(value["method"], json!({"Growth": 0.1}));
+    }
+
+    #[test]
+    fn limit_depth_prunes_six_level_schema() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct L6 {
+            value: i32,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct L5 {
+            next: L6,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct L4 {
+            next: L5,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct L3 {
+            next: Vec<L4>,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct L2 {
+            next: L3,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct L1 {
+            name: String,
+            next: L2,
+        }
+
+        let mut schema = L1::gemini_schema();
+        InlineRefs::default().apply(&mut schema);
+        assert_eq!(schema_depth(&schema), 7);
+
+        let mut pruned = L1::gemini_schema();
+        LimitDepth::new(3).apply(&mut pruned);
+
+        assert_eq!(schema_depth(&pruned), 3);
+        assert!(pruned.get("$defs").is_none());
+        assert_eq!(pruned["properties"]["name"]["type"], "string");
+        let cut = &pruned["properties"]["next"]["properties"]["next"]["properties"]["next"];
+        assert_eq!(cut, &json!({"type": "array"}));
+    }
+
+    #[test]
+    fn inject_defaults_fills_only_missing_fields() {
+        let mut value = json!({"name": "Ada", "totals": {"amount": 10}, "meta": null});
+        prune_null_fields(&mut value);
+        let defaults = HashMap::from([
+            ("name".to_string(), json!("unknown")),
+            ("currency".to_string(), json!("NZD")),
+            ("/totals/currency".to_string(), json!("NZD")),
+            ("/meta/source".to_string(), json!("model")),
+        ]);
+
+        inject_defaults(&mut value, &defaults);
+
+        assert_eq!(
+            value,
+            json!({"name": "Ada", "currency": "NZD", "totals": {"amount": 10, "currency": "NZD"}})
+        );
+    }
+
+    #[test]
+    fn schema_bundle_round_trips_as_valid_json_schema() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Address {
+            city: String,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Person {
+            name: String,
+            address: Address,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Node {
+            label: String,
+            children: Vec<Node>,
+        }
+
+        let bundle = crate::gemini_schema_bundle![Person, Node];
+        let text = serde_json::to_string(&bundle.to_value()).unwrap();
+        let parsed: Value = serde_json::from_str(&text).unwrap();
+        assert_eq!(
+            bundle.names().collect::<Vec<_>>(),
+            ["Address", "Person", "Node"]
+        );
+
+        let person = json!({"name": "Ada", "address": {"city": "Auckland"}});
+        let node = json!({"label": "root", "children": [{"label": "leaf", "children": []}]});
+        for (name, instance) in [("Person", person), ("Node", node)] {
+            let mut root = to_standard_json_schema(parsed.clone());
+            root["$ref"] = json!(format!("#/$defs/{name}"));
+            let validator = jsonschema::validator_for(&root).unwrap();
+            assert!(validator.is_valid(&instance), "{name} should validate");
+            assert!(!validator.is_valid(&json!({})), "{name} needs fields");
+        }
+    }
+
+    #[test]
+    fn assert_non_empty_strings_reports_required_blanks() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Line {
+            sku: String,
+            note: Option<String>,
+        }
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Invoice {
+            vendor: String,
+            reference: String,
+            lines: Vec<Line>,
+        }
+
+        let schema = Invoice::gemini_schema();
+        let value = json!({
+            "vendor": "",
+            "reference": "  ",
+            "lines": [{"sku": "A-1", "note": ""}, {"sku": ""}]
+        });
+
+        assert_eq!(
+            assert_non_empty_strings(&value, &schema, true),
+            ["/vendor", "/reference", "/lines/1/sku"]
+        );
+        assert_eq!(
+            assert_non_empty_strings(&value, &schema, false),
+            ["/vendor", "/lines/1/sku"]
+        );
+    }
+
+    #[test]
+    fn schema_snapshot_accepts_match_and_reports_drift() {
+        #[derive(JsonSchema)]
+        #[allow(dead_code)]
+        struct Invoice {
+            number: String,
+            total: f64,
+        }
+
+        let dir = std::env::temp_dir().join(format!("schema-snapshot-{}", uuid::Uuid::new_v4()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("golden").join("invoice.json");
+        let schema = Invoice::gemini_schema();
+
+        // Independent of UPDATE_SCHEMA_SNAPSHOTS in the test environment.
+        check_schema_snapshot(&schema, &path, true);
+        check_schema_snapshot(&schema, &path, false);
+
+        let mut drifted = schema.clone();
+        drifted["properties"]
+            .as_object_mut()
+            .unwrap()
+            .remove("total");
+        let panic = std::panic::catch_unwind(|| check_schema_snapshot(&drifted, &path, false))
+            .expect_err("drift must fail");
+        let message = panic.downcast_ref::<String>().unwrap();
+        assert!(message.contains("removed /properties/total"), "{message}");
+
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+
+    #[test]
+    fn map_entry_arrays_convert_only_with_reserved_keys() {
+        let mut value = json!({
+            "failover_matrix": [
+                {"__key__": "db_shard_1", "__value__": {"primary": "us-east"}},
+                {"__key__": "db_shard_2", "__value__": {"primary": "eu-west"}}
+            ],
+            "settings": [
+                {"key": "timeout", "value": 30},
+                {"key": "retries", "value": 3}
+            ],
+            "mixed": [
+                {"__key__": "a", "__value__": 1, "note": "extra field"}
+            ],
+            "tags": []
+        });
+
+        normalize_map_entry_arrays(&mut value);
+
+        assert_eq!(
+            value["failover_matrix"],
+            json!({"db_shard_1": {"primary": "us-east"}, "db_shard_2": {"primary": "eu-west"}})
+        );
+        assert_eq!(
+            value["settings"],
+            json!([{"key": "timeout", "value": 30}, {"key": "retries", "value": 3}])
+        );
+        assert!(value["mixed"].is_array());
+        assert_eq!(value["tags"], json!([]));
+    }
+
+    #[test]
+    fn schema_example_deserializes_into_nested_recursive_types() {
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        enum Tier {
+            Basic,
+            Premium,
+        }
+
+        #[derive(Debug, serde::Deserialize, JsonSchema)]
+        struct Node {
+            label: String,
+            #[schemars(length(min = 2))]
+            weights: Vec<u32>,
+            tier: Tier,
+            child: Option<Box<Node>>,
+        }
+
+        let example = schema_example(&Node::gemini_schema());
+        let node: Node = serde_json::from_value(example).unwrap();
+
+        assert_eq!(node.label, "example");
+        assert_eq!(node.weights, vec![0, 0]);
+        assert!(matches!(node.tier, Tier::Basic));
+        assert!(node.child.is_some());
+    }
 }
@@ -1,14 +1,20 @@
 use std::collections::HashMap;
+use std::io::{Read, Write};
 
 use chrono::{DateTime, Utc};
-use gemini_rust::{Content, Message, Role};
+use gemini_rust::{Content, Message, Part, Role};
 use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use serde_json::Value;
 
 use crate::{
     context::ContextBuilder,
     error::{Result, StructuredError},
     models::RefinementOutcome,
-    schema::{GeminiStructured, StructuredValidator},
+    schema::{
+        compile_validator, normalize_map_entry_arrays, prune_null_fields,
+        recover_internally_tagged_enums, unflatten_externally_tagged_enums, GeminiStructured,
+        StructuredValidator,
+    },
     StructuredClient,
 };
 
@@ -85,6 +91,66 @@ impl SessionEntry {
         self.metadata.insert(key.to_string(), value.to_string());
         self
     }
+
+    /// Concatenated text parts of this entry's message.
+    pub fn text(&self) -> String {
+        self.message
+            .content
+            .parts
+            .iter()
+            .flatten()
+            .filter_map(|part| match part {
+                Part::Text { text, .. } => Some(text.as_str()),
+                _ => None,
+            })
+            .collect::<Vec<_>>()
+            .join("\n")
+    }
+}
+
+/// Thresholds at which [`InteractiveSession::chat`] compacts older history.
+///
+/// Token counts are estimated at ~4 characters per token.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HistoryCompaction {
+    /// Compact once the history holds more than this many entries.
+    pub max_entries: Option<usize>,
+    /// Compact once the history text exceeds roughly this many tokens.
+    pub max_tokens: Option<usize>,
+    /// Number of most recent entries kept verbatim when compacting.
+    pub keep_recent: usize,
+}
+
+impl HistoryCompaction {
+    /// Keep the `keep_recent` latest entries verbatim; set a threshold with the builders.
+    pub fn new(keep_recent: usize) -> Self {
+        Self {
+            max_entries: None,
+            max_tokens: None,
+            keep_recent,
+        }
+    }
+
+    /// Compact once the history holds more than `max_entries` entries.
+    pub fn with_max_entries(mut self, max_entries: usize) -> Self {
+        self.max_entries = Some(max_entries);
+        self
+    }
+
+    /// Compact once the history exceeds roughly `max_tokens` tokens.
+    pub fn with_max_tokens(mut self, max_tokens: usize) -> Self {
+        self.max_tokens = Some(max_tokens);
+        self
+    }
+
+    fn is_exceeded_by(&self, history: &[SessionEntry]) -> bool {
+        let too_many = self.max_entries.is_some_and(|max| history.len() > max);
+        let too_long = self.max_tokens.is_some_and(|max| {
+            let chars: usize = history.iter().map(|entry| entry.text().len()).sum();
+            chars / 4 > max
+        });
+        history.len() > self.keep_recent && (too_many || too_long)
+    }
 }
 
 /// Describes the observed impact of a change to help models reason about downstream effects.
@@ -102,9 +168,21 @@ pub struct PendingChange<C> {
     pub reasoning: Option<String>,
 }
 
+/// Persisted format version written by [`InteractiveSession::save_to_writer`].
+///
+/// Sessions saved before the version tag existed load as version 1.
+pub const SESSION_FORMAT_VERSION: u32 = 1;
+
+fn default_session_version() -> u32 {
+    1
+}
+
 /// Top-level container for managing stateful, human-in-the-loop interactions.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InteractiveSession<C, O> {
+    /// Persisted format version, used to migrate older saved sessions.
+    #[serde(default = "default_session_version")]
+    pub version: u32,
     /// The currently accepted configuration.
     pub config: C,
     /// Derived output generated from the configuration (e.g., a forecast).
@@ -113,6 +191,9 @@ pub struct InteractiveSession<C, O> {
     pub history: Vec<SessionEntry>,
     /// AI-proposed change awaiting review.
     pub pending_change: Option<PendingChange<C>>,
+    /// When set, `chat` compacts older history once a threshold is crossed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compaction: Option<HistoryCompaction>,
 }
 
 impl<C, O> InteractiveSession<C, O>
@@ -129,13 +210,22 @@ where
 {
     pub fn new(initial_config: C, initial_output: Option<O>) -> Self {
         Self {
+            version: SESSION_FORMAT_VERSION,
             config: initial_config,
             output: initial_output,
             history: Vec::new(),
             pending_change: None,
+            compaction: None,
         }
     }
 
+    /// Compact history automatically during [`chat`](Self::chat) once `compaction`'s
+    /// threshold is crossed.
+    pub fn with_history_compaction(mut self, compaction: HistoryCompaction) -> Self {
+        self.compaction = Some(compaction);
+        self
+    }
+
     /// Replace the derived output after recomputing it externally.
     pub fn update_output(&mut self, output: Option<O>) {
         self.output = output;
@@ -180,6 +270,12 @@ where
         user_query: impl Into<String>,
     ) -> Result<String> {
         let user_query = user_query.into();
+        if let Some(compaction) = &self.compaction {
+            if compaction.is_exceeded_by(&self.history) {
+                let keep_recent = compaction.keep_recent;
+                self.compact_history(client, keep_recent).await?;
+            }
+        }
         let (system_prompt, history_messages) = self.build_context(&user_query)?;
 
         let ctx = ContextBuilder::new()
@@ -197,6 +293,71 @@ where
         Ok(response_text)
     }
 
+    /// Summarize all but the `keep_recent` latest history entries into one system note.
+    ///
+    /// The model condenses the older conversation; the `patch_summary` of every older
+    /// state change is appended to the note verbatim so no configuration change is
+    /// lost, including those listed by a note from an earlier compaction. Returns the
+    /// number of entries that were replaced (0 if there was nothing to compact).
+    pub async fn compact_history(
+        &mut self,
+        client: &StructuredClient,
+        keep_recent: usize,
+    ) -> Result<usize> {
+        if self.history.len() <= keep_recent {
+            return Ok(0);
+        }
+        let split = self.history.len() - keep_recent;
+
+        let mut transcript = String::new();
+        let mut change_summaries = Vec::new();
+        for entry in &self.history[..split] {
+            let speaker = match (&entry.kind, &entry.message.role) {
+                (EntryKind::SystemNote, _) => "System",
+                (_, Role::Model) => "Assistant",
+                _ => "User",
+            };
+            if let EntryKind::StateChange { patch_summary, .. } = &entry.kind {
+                transcript.push_str(&format!("[state change: {patch_summary}]\n"));
+                change_summaries.push(patch_summary.clone());
+            }
+            if entry.metadata.get("type").map(String::as_str) == Some("history_summary") {
+                let earlier = entry
+                    .metadata
+                    .get("changes")
+                    .and_then(|changes| serde_json::from_str::<Vec<String>>(changes).ok());
+                change_summaries.extend(earlier.unwrap_or_default());
+            }
+            transcript.push_str(&format!("{speaker}: {}\n\n", entry.text()));
+        }
+
+        let ctx = ContextBuilder::new()
+            .with_system(
+                "Summarize the earlier part of a configuration-editing conversation so it can \
+                 replace the original messages. Keep decisions, open questions, user \
+                 preferences and the reasons behind configuration changes. Be concise.",
+            )
+            .add_user_text(transcript);
+        let summary: String = client.generate(ctx, None).await?;
+
+        let mut note = format!("Summary of earlier conversation:\n{summary}");
+        if !change_summaries.is_empty() {
+            note.push_str("\n\nConfiguration changes in that period:");
+            for change in &change_summaries {
+                note.push_str(&format!("\n- {change}"));
+            }
+        }
+
+        let recent = self.history.split_off(split);
+        self.history = vec![SessionEntry::new_system_note(note)
+            .with_meta("type", "history_summary")
+            .with_meta("compacted_entries", &split.to_string())
+            .with_meta("changes", &serde_json::to_string(&change_summaries)?)];
+        self.history.extend(recent);
+
+        Ok(split)
+    }
+
     /// Ask the AI to propose a configuration change and stage it for review.
     pub async fn request_change(
         &mut self,
@@ -327,6 +488,53 @@ where
         Ok(patch)
     }
 
+    /// Write the session as JSON, e.g. to park it in Redis or Postgres between turns.
+    pub fn save_to_writer(&self, writer: impl Write) -> Result<()> {
+        serde_json::to_writer(writer, self)?;
+        Ok(())
+    }
+
+    /// Restore a session written by [`save_to_writer`](Self::save_to_writer).
+    ///
+    /// Older format versions are migrated. The restored `config` must still deserialize
+    /// into `C`, pass its JSON schema after the same normalization model responses get,
+    /// and pass [`StructuredValidator::validate`]; if the persisted state is no longer
+    /// valid, a [`StructuredError::Validation`] describing why is returned.
+    pub fn load_from_reader(reader: impl Read) -> Result<Self> {
+        let persisted: Value = serde_json::from_reader(reader)?;
+        let migrated = migrate_persisted_session(persisted)?;
+
+        let mut config = migrated.get("config").cloned().unwrap_or(Value::Null);
+        if let Err(err) = serde_json::from_value::<C>(config.clone()) {
+            return Err(StructuredError::Validation(format!(
+                "Persisted session config no longer matches its type: {err}"
+            )));
+        }
+        let schema = C::gemini_schema();
+        prune_null_fields(&mut config);
+        normalize_map_entry_arrays(&mut config);
+        unflatten_externally_tagged_enums(&mut config, &schema);
+        recover_internally_tagged_enums(&mut config, &schema);
+        let schema_errors: Vec<String> = compile_validator::<C>()?
+            .iter_errors(&config)
+            .map(|err| format!("{}: {}", err.instance_path(), err))
+            .collect();
+        if !schema_errors.is_empty() {
+            return Err(StructuredError::Validation(format!(
+                "Persisted session config no longer matches its schema: {}",
+                schema_errors.join("; ")
+            )));
+        }
+
+        let session: Self = serde_json::from_value(migrated)?;
+        if let Some(reason) = session.config.validate() {
+            return Err(StructuredError::Validation(format!(
+                "Persisted session config is no longer valid: {reason}"
+            )));
+        }
+        Ok(session)
+    }
+
     /// Squash a refinement outcome into a single history entry.
     pub fn record_refinement_outcome(
         &mut self,
@@ -370,3 +578,25 @@ where
         }
     }
 }
+
+/// Upgrade a persisted session to [`SESSION_FORMAT_VERSION`].
+///
+/// Version 1 is the only format so far; newer versions are rejected rather than
+/// guessed at.
+fn migrate_persisted_session(mut persisted: Value) -> Result<Value> {
+    let version = persisted
+        .get("version")
+        .and_then(Value::as_u64)
+        .unwrap_or(u64::from(default_session_version()));
+
+    if version > u64::from(SESSION_FORMAT_VERSION) {
+        return Err(StructuredError::Context(format!(
+            "Session was saved in format version {version}, but this build reads up to version {SESSION_FORMAT_VERSION}"
+        )));
+    }
+
+    if let Some(object) = persisted.as_object_mut() {
+        object.insert("version".to_string(), Value::from(SESSION_FORMAT_VERSION));
+    }
+    Ok(persisted)
+}
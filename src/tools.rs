@@ -1,7 +1,9 @@
-use std::collections::HashMap;
+use std::any::Any;
+use std::collections::{HashMap, HashSet};
 use std::future::Future;
 use std::pin::Pin;
-use std::sync::Arc;
+use std::sync::{Arc, PoisonError, RwLock, RwLockReadGuard, RwLockWriteGuard};
+use std::time::Duration;
 
 use gemini_rust::{FunctionDeclaration, Tool};
 use schemars::JsonSchema;
@@ -13,25 +15,133 @@ use crate::error::{Result, StructuredError};
 /// A dynamic error type for tool execution.
 pub type ToolError = Box<dyn std::error::Error + Send + Sync>;
 
-/// A handler that takes a JSON argument and returns a JSON result (async).
-type HandlerFn = dyn Fn(
+/// Request-scoped value shared with context-aware tool handlers.
+///
+/// Set it with [`StructuredRequest::with_tool_context`](crate::StructuredRequest::with_tool_context)
+/// and read it in handlers registered through
+/// [`ToolRegistry::register_tool_with_ctx`] by downcasting.
+pub type ToolContext = Arc<dyn Any + Send + Sync>;
+
+/// A handler that takes JSON arguments (and the request's [`ToolContext`], if any) and
+/// returns a JSON result (async).
+pub type ToolHandler = dyn Fn(
         Value,
+        Option<ToolContext>,
     ) -> Pin<Box<dyn Future<Output = std::result::Result<Value, ToolError>> + Send + 'static>>
     + Send
     + Sync;
 
 #[derive(Clone, Default)]
-pub struct ToolRegistry {
+struct RegistryState {
     tools: Vec<Tool>,
-    handlers: Arc<HashMap<String, Arc<HandlerFn>>>,
+    handlers: HashMap<String, Arc<ToolHandler>>,
+    timeouts: HashMap<String, Duration>,
+}
+
+/// Lock around [`RegistryState`]; cloning it copies the current state.
+#[derive(Default)]
+struct SharedState(RwLock<RegistryState>);
+
+impl Clone for SharedState {
+    fn clone(&self) -> Self {
+        let state = self.0.read().unwrap_or_else(PoisonError::into_inner);
+        Self(RwLock::new(state.clone()))
+    }
+}
+
+/// Tool definitions plus the handlers that resolve them.
+///
+/// Builder methods (taking `self`) return a registry independent of earlier clones.
+/// Runtime methods such as [`register_boxed`](Self::register_boxed) and
+/// [`remove_tool`](Self::remove_tool) take `&self` and are seen by every clone, so a
+/// registry handed to a client can be reshaped per session without rebuilding it.
+/// Requests snapshot the definitions when the tools are attached.
+#[derive(Clone, Default)]
+pub struct ToolRegistry {
+    state: Arc<SharedState>,
+    default_timeout: Option<Duration>,
 }
 
 impl ToolRegistry {
     pub fn new() -> Self {
-        Self {
-            tools: Vec::new(),
-            handlers: Arc::new(HashMap::new()),
+        Self::default()
+    }
+
+    fn read(&self) -> RwLockReadGuard<'_, RegistryState> {
+        self.state.0.read().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    fn write(&self) -> RwLockWriteGuard<'_, RegistryState> {
+        self.state.0.write().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Write access for builder methods, detaching from other clones first.
+    fn owned_state(&mut self) -> &mut RegistryState {
+        Arc::make_mut(&mut self.state)
+            .0
+            .get_mut()
+            .unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add or replace a function tool at runtime; every clone sees the change.
+    ///
+    /// Any existing tool with that name is replaced, including its timeout. Returns
+    /// [`StructuredError::Config`] when `name` differs from the declaration's name, since
+    /// the model would call a function no handler is registered for.
+    pub fn register_boxed(
+        &self,
+        name: &str,
+        definition: FunctionDeclaration,
+        handler: Box<ToolHandler>,
+    ) -> Result<()> {
+        let tool = Tool::new(definition);
+        let declared = function_names(&tool);
+        if declared != [name] {
+            return Err(StructuredError::Config(format!(
+                "Tool registered as '{name}' declares {declared:?}"
+            )));
         }
+        let mut state = self.write();
+        remove_declaration(&mut state.tools, name);
+        state.tools.push(tool);
+        state.handlers.insert(name.to_string(), Arc::from(handler));
+        state.timeouts.remove(name);
+        Ok(())
+    }
+
+    /// Remove the function tool `name` at runtime, returning whether it existed.
+    ///
+    /// Other declarations grouped in the same [`Tool`] are kept.
+    pub fn remove_tool(&self, name: &str) -> bool {
+        let mut state = self.write();
+        let had_declaration = remove_declaration(&mut state.tools, name);
+        let had_handler = state.handlers.remove(name).is_some();
+        state.timeouts.remove(name);
+        had_handler || had_declaration
+    }
+
+    /// Names of the registered function tools, in registration order.
+    pub fn tool_names(&self) -> Vec<String> {
+        self.read().tools.iter().flat_map(function_names).collect()
+    }
+
+    /// Apply a timeout to every handler that has no per-tool timeout.
+    ///
+    /// Timed-out handlers are not cancelled: the future is dropped, so work only stops
+    /// at its next `.await` point, and anything spawned elsewhere keeps running unless
+    /// the handler is cancellation-aware.
+    pub fn with_default_timeout(mut self, timeout: Duration) -> Self {
+        self.default_timeout = Some(timeout);
+        self
+    }
+
+    /// Timeout that applies to `name`, if any.
+    pub fn timeout_for(&self, name: &str) -> Option<Duration> {
+        self.read()
+            .timeouts
+            .get(name)
+            .copied()
+            .or(self.default_timeout)
     }
 
     /// Register a function tool using typed arguments and response payloads (no handler).
@@ -44,13 +154,13 @@ impl ToolRegistry {
             .with_parameters::<Args>()
             .with_response::<Resp>();
 
-        self.tools.push(Tool::new(declaration));
+        self.owned_state().tools.push(Tool::new(declaration));
         self
     }
 
     /// Register a function tool with an async handler implementation.
     pub fn register_with_handler<Args, Resp, F, Fut>(
-        mut self,
+        self,
         name: &str,
         description: &str,
         handler: F,
@@ -60,21 +170,56 @@ impl ToolRegistry {
         Resp: JsonSchema + Serialize + Send + Sync + 'static,
         F: Fn(Args) -> Fut + Send + Sync + 'static,
         Fut: Future<Output = std::result::Result<Resp, ToolError>> + Send + 'static,
+    {
+        self.register_tool_with_ctx(name, description, move |args: Args, _| handler(args))
+    }
+
+    /// Register a function tool whose handler also receives the request's
+    /// [`ToolContext`], e.g. an auth token or tenant id.
+    ///
+    /// The context is `None` when the request set none. Stateless tools registered with
+    /// [`register_with_handler`](Self::register_with_handler) never see it.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let registry = ToolRegistry::new().register_tool_with_ctx(
+    ///     "list_invoices",
+    ///     "List the tenant's invoices",
+    ///     |args: InvoiceQuery, ctx: Option<ToolContext>| async move {
+    ///         let tenant = ctx
+    ///             .as_deref()
+    ///             .and_then(|ctx| ctx.downcast_ref::<Tenant>())
+    ///             .ok_or("missing tenant")?;
+    ///         Ok(fetch_invoices(&tenant.id, args).await?)
+    ///     },
+    /// );
+    /// ```
+    pub fn register_tool_with_ctx<Args, Resp, F, Fut>(
+        mut self,
+        name: &str,
+        description: &str,
+        handler: F,
+    ) -> Self
+    where
+        Args: JsonSchema + Serialize + DeserializeOwned + Send + Sync + 'static,
+        Resp: JsonSchema + Serialize + Send + Sync + 'static,
+        F: Fn(Args, Option<ToolContext>) -> Fut + Send + Sync + 'static,
+        Fut: Future<Output = std::result::Result<Resp, ToolError>> + Send + 'static,
     {
         let declaration = FunctionDeclaration::new(name, description, None)
             .with_parameters::<Args>()
             .with_response::<Resp>();
-        self.tools.push(Tool::new(declaration));
 
         let name_owned = name.to_string();
         let handler_arc: Arc<F> = Arc::new(handler);
         let handler_ref = handler_arc.clone();
-        let wrapper: Arc<HandlerFn> = Arc::new(move |args_val: Value| {
+        let wrapper: Arc<ToolHandler> = Arc::new(move |args_val: Value, ctx| {
             let handler_call = handler_ref.clone();
             let fut = async move {
                 let args: Args = serde_json::from_value(args_val)
                     .map_err(|e| Box::new(StructuredError::Json(e)) as ToolError)?;
-                let result = handler_call(args).await?;
+                let result = handler_call(args, ctx).await?;
                 let res_val = serde_json::to_value(result)
                     .map_err(|e| Box::new(StructuredError::Json(e)) as ToolError)?;
                 Ok(res_val)
@@ -82,16 +227,16 @@ impl ToolRegistry {
             Box::pin(fut)
         });
 
-        let mut new_handlers = (*self.handlers).clone();
-        new_handlers.insert(name_owned, wrapper);
-        self.handlers = Arc::new(new_handlers);
+        let state = self.owned_state();
+        state.tools.push(Tool::new(declaration));
+        state.handlers.insert(name_owned, wrapper);
 
         self
     }
 
     /// Add an existing tool instance (e.g., Google Search or Code Execution).
     pub fn with_tool(mut self, tool: Tool) -> Self {
-        self.tools.push(tool);
+        self.owned_state().tools.push(tool);
         self
     }
 
@@ -106,14 +251,125 @@ impl ToolRegistry {
     }
 
     pub fn definitions(&self) -> Vec<Tool> {
-        self.tools.clone()
+        self.read().tools.clone()
     }
 
+    /// Check that every function tool's parameter schema compiles as JSON Schema.
+    ///
+    /// Call this at startup to catch broken hand-written or macro-generated schemas
+    /// before the model sees them. Returns one message per offending tool.
+    pub fn validate_definitions(&self) -> std::result::Result<(), Vec<String>> {
+        let mut problems = Vec::new();
+        for decl in self.read().tools.iter().flat_map(function_declarations) {
+            let name = decl
+                .get("name")
+                .and_then(Value::as_str)
+                .unwrap_or("<unnamed>");
+            let Some(schema) = decl
+                .get("parametersJsonSchema")
+                .or_else(|| decl.get("parameters"))
+            else {
+                continue;
+            };
+            if let Err(err) = jsonschema::validator_for(schema) {
+                problems.push(format!("{name}: {err}"));
+            }
+        }
+        if problems.is_empty() {
+            Ok(())
+        } else {
+            Err(problems)
+        }
+    }
+
+    /// Combine two registries, letting `other` win on name collisions.
+    ///
+    /// A function tool of `self` is dropped when `other` declares a function with the
+    /// same name, and its handler and timeout are replaced by `other`'s. Built-in tools
+    /// such as Google Search are kept once. Each side's default timeout keeps applying
+    /// to its own handlers.
+    pub fn merge(self, other: ToolRegistry) -> Self {
+        let mut tools = self.tools_not_shadowed_by(&other);
+        let theirs = other.read().clone();
+        tools.extend(theirs.tools);
+
+        let RegistryState {
+            mut handlers,
+            mut timeouts,
+            ..
+        } = self.read().clone();
+        for (name, handler) in theirs.handlers {
+            let timeout = theirs.timeouts.get(&name).copied();
+            match timeout.or(other.default_timeout) {
+                Some(timeout) => timeouts.insert(name.clone(), timeout),
+                None => timeouts.remove(&name),
+            };
+            handlers.insert(name, handler);
+        }
+
+        Self {
+            state: Arc::new(SharedState(RwLock::new(RegistryState {
+                tools,
+                handlers,
+                timeouts,
+            }))),
+            default_timeout: self.default_timeout,
+        }
+    }
+
+    /// Definitions of `self` that survive a [`merge`](Self::merge) with `other`.
+    pub(crate) fn tools_not_shadowed_by(&self, other: &ToolRegistry) -> Vec<Tool> {
+        let other_tools = other.definitions();
+        let shadowed: HashSet<String> = other_tools.iter().flat_map(function_names).collect();
+        let builtins: Vec<Value> = other_tools
+            .iter()
+            .filter(|tool| function_names(tool).is_empty())
+            .filter_map(|tool| serde_json::to_value(tool).ok())
+            .collect();
+
+        self.read()
+            .tools
+            .iter()
+            .filter(|tool| {
+                let names = function_names(tool);
+                if names.is_empty() {
+                    !serde_json::to_value(tool).is_ok_and(|value| builtins.contains(&value))
+                } else {
+                    !names.iter().any(|name| shadowed.contains(name))
+                }
+            })
+            .cloned()
+            .collect()
+    }
+
+    /// Run the handler registered for `name`.
+    ///
+    /// Returns [`StructuredError::ToolTimeout`] when the handler exceeds its timeout.
     pub async fn execute(&self, name: &str, args: Value) -> Result<Value> {
-        if let Some(handler) = self.handlers.get(name) {
-            handler(args)
-                .await
-                .map_err(|e| StructuredError::Context(e.to_string()))
+        self.execute_with_context(name, args, None).await
+    }
+
+    /// Like [`execute`](Self::execute), passing `ctx` to context-aware handlers.
+    pub async fn execute_with_context(
+        &self,
+        name: &str,
+        args: Value,
+        ctx: Option<ToolContext>,
+    ) -> Result<Value> {
+        // Release the lock before awaiting so runtime registration never waits on a tool.
+        let handler = self.read().handlers.get(name).cloned();
+        if let Some(handler) = handler {
+            let fut = handler(args, ctx);
+            let result = match self.timeout_for(name) {
+                Some(timeout) => tokio::time::timeout(timeout, fut).await.map_err(|_| {
+                    StructuredError::ToolTimeout {
+                        tool_name: name.to_string(),
+                        timeout,
+                    }
+                })?,
+                None => fut.await,
+            };
+            result.map_err(|e| StructuredError::Context(e.to_string()))
         } else {
             Err(StructuredError::Context(format!(
                 "No handler registered for tool: {name}"
@@ -145,4 +401,82 @@ impl ToolRegistry {
     {
         registrar(self)
     }
+
+    /// Register a tool via its registrar and bound its handler by `timeout`.
+    ///
+    /// Overrides any registry-wide default for the tools added by `registrar`. See
+    /// [`with_default_timeout`](Self::with_default_timeout) for cancellation caveats.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let registry = ToolRegistry::new()
+    ///     .register_tool_with_timeout(get_stock_price_tool::registrar(), Duration::from_secs(5));
+    /// ```
+    pub fn register_tool_with_timeout<F>(self, registrar: F, timeout: Duration) -> Self
+    where
+        F: FnOnce(ToolRegistry) -> ToolRegistry,
+    {
+        let existing: Vec<String> = self.read().handlers.keys().cloned().collect();
+        let mut registry = registrar(self);
+
+        let state = registry.owned_state();
+        let added: Vec<String> = state
+            .handlers
+            .keys()
+            .filter(|name| !existing.contains(name))
+            .cloned()
+            .collect();
+        for name in added {
+            state.timeouts.insert(name, timeout);
+        }
+        registry
+    }
+}
+
+/// Serialized function declarations carried by `tool`, empty for built-in tools.
+fn function_declarations(tool: &Tool) -> Vec<Value> {
+    serde_json::to_value(tool)
+        .ok()
+        .and_then(|value| value.get("functionDeclarations").cloned())
+        .and_then(|decls| decls.as_array().cloned())
+        .unwrap_or_default()
+}
+
+/// Drop the function declaration `name` from `tools`, keeping other declarations that
+/// share its [`Tool`]. Returns whether one was removed.
+fn remove_declaration(tools: &mut Vec<Tool>, name: &str) -> bool {
+    let mut removed = false;
+    tools.retain_mut(|tool| {
+        let names = function_names(tool);
+        if !names.iter().any(|n| n == name) {
+            return true;
+        }
+        removed = true;
+        let Ok(mut value) = serde_json::to_value(&*tool) else {
+            return false;
+        };
+        if let Some(decls) = value
+            .get_mut("functionDeclarations")
+            .and_then(Value::as_array_mut)
+        {
+            decls.retain(|decl| decl.get("name").and_then(Value::as_str) != Some(name));
+        }
+        match serde_json::from_value(value) {
+            Ok(rest) if names.len() > 1 => {
+                *tool = rest;
+                true
+            }
+            _ => false,
+        }
+    });
+    removed
+}
+
+/// Names of the function declarations carried by `tool`, empty for built-in tools.
+pub(crate) fn function_names(tool: &Tool) -> Vec<String> {
+    function_declarations(tool)
+        .iter()
+        .filter_map(|decl| decl.get("name").and_then(Value::as_str).map(str::to_string))
+        .collect()
 }
@@ -0,0 +1,175 @@
+//! Artifact store and input-injection combinator for blackboard-style pipelines.
+//!
+//! Artifacts recorded with [`ExecutionContext::emit_artifact`] are kept in an
+//! [`ArtifactMap`] on the context, so later steps can read them back by key instead of
+//! threading every intermediate value through tuples or a `StateWorkflow`.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use crate::Result;
+
+use super::graph::{GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+#[derive(Debug, Clone)]
+struct ArtifactEntry {
+    step_name: String,
+    data: Value,
+}
+
+/// Artifacts emitted during a workflow run, keyed by artifact key.
+///
+/// When several steps emit the same key, the most recent value wins.
+#[derive(Debug, Clone, Default)]
+pub struct ArtifactMap {
+    entries: HashMap<String, ArtifactEntry>,
+}
+
+impl ArtifactMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Store an artifact, replacing any previous value under `key`.
+    pub fn insert(&mut self, step_name: impl Into<String>, key: impl Into<String>, data: Value) {
+        self.entries.insert(
+            key.into(),
+            ArtifactEntry {
+                step_name: step_name.into(),
+                data,
+            },
+        );
+    }
+
+    /// Raw JSON value of an artifact.
+    pub fn get_raw(&self, key: &str) -> Option<&Value> {
+        self.entries.get(key).map(|entry| &entry.data)
+    }
+
+    /// Deserialize an artifact into `T`.
+    ///
+    /// Returns `None` when the key is missing or the value does not deserialize as `T`.
+    pub fn get<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.get_raw(key)
+            .and_then(|value| serde_json::from_value(value.clone()).ok())
+    }
+
+    /// Name of the step that emitted an artifact.
+    pub fn step_of(&self, key: &str) -> Option<&str> {
+        self.entries.get(key).map(|entry| entry.step_name.as_str())
+    }
+
+    pub fn contains(&self, key: &str) -> bool {
+        self.entries.contains_key(key)
+    }
+
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Step that builds its inner step's input from the incoming value plus the
+/// artifacts recorded so far.
+///
+/// Created by calling `.with_artifacts()` on any `Step`.
+pub struct WithArtifactsStep<S, F, P, I> {
+    inner: S,
+    builder: Arc<F>,
+    _marker: std::marker::PhantomData<(P, I)>,
+}
+
+impl<S, F, P, I> WithArtifactsStep<S, F, P, I> {
+    /// Create a new step that prepares the inner step's input with `builder`.
+    pub fn new(inner: S, builder: F) -> Self {
+        Self {
+            inner,
+            builder: Arc::new(builder),
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, F, P, I, O> Step<P, O> for WithArtifactsStep<S, F, P, I>
+where
+    P: Send + Sync + 'static,
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+    F: Fn(P, &ArtifactMap) -> I + Send + Sync + 'static,
+{
+    async fn run(&self, input: P, ctx: &ExecutionContext) -> Result<O> {
+        let prepared = {
+            let artifacts = ctx.artifacts.lock().unwrap();
+            (self.builder)(input, &artifacts)
+        };
+        self.inner.run(prepared, ctx).await
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inject = graph.add_node("with artifacts", "map");
+        let inner = self.inner.describe(graph);
+        graph.add_edge(inject, inner.entry, None);
+        GraphSpan {
+            entry: inject,
+            exit: inner.exit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+
+    #[tokio::test]
+    async fn later_step_reads_earlier_artifacts() {
+        let pipeline = LambdaStep(|x: i32| async move { Ok(x + 1) })
+            .tap(|value: &i32, ctx: &ExecutionContext| {
+                ctx.emit_artifact("Increment", "baseline", value);
+            })
+            .map(|value| value * 10)
+            .then(
+                LambdaStep(|(value, baseline): (i32, i32)| async move { Ok(value - baseline) })
+                    .with_artifacts(|value: i32, artifacts: &ArtifactMap| {
+                        (value, artifacts.get::<i32>("baseline").unwrap_or_default())
+                    }),
+            );
+
+        let ctx = ExecutionContext::new();
+        let result = pipeline.run(4, &ctx).await.unwrap();
+
+        assert_eq!(result, 45);
+        assert_eq!(ctx.get_artifact::<i32>("baseline"), Some(5));
+        assert_eq!(
+            ctx.artifacts_snapshot().step_of("baseline"),
+            Some("Increment")
+        );
+    }
+
+    #[test]
+    fn artifact_map_latest_value_wins() {
+        let mut artifacts = ArtifactMap::new();
+        artifacts.insert("A", "score", serde_json::json!(1));
+        artifacts.insert("B", "score", serde_json::json!(2));
+
+        assert_eq!(artifacts.len(), 1);
+        assert_eq!(artifacts.get::<i32>("score"), Some(2));
+        assert_eq!(artifacts.step_of("score"), Some("B"));
+        assert_eq!(artifacts.get::<String>("score"), None);
+    }
+}
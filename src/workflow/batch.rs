@@ -11,6 +11,7 @@ use futures::stream::{self, StreamExt};
 
 use crate::Result;
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -124,6 +125,22 @@ where
 
         Ok(outputs)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let node = graph.add_node(
+            format!(
+                "Batch (size {}, concurrency {})",
+                self.batch_size, self.concurrency
+            ),
+            "batch",
+        );
+        let worker = self.worker.describe(graph);
+        graph.add_edge(node, worker.entry, Some("each batch"));
+        GraphSpan {
+            entry: node,
+            exit: worker.exit,
+        }
+    }
 }
 
 /// Adapter that converts a `Step<I, O>` into `Step<(Vec<I>, ()), Vec<O>>`.
@@ -160,6 +177,16 @@ where
         }
         Ok(outputs)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let node = graph.add_node("SingleItemAdapter", "batch");
+        let worker = self.inner.describe(graph);
+        graph.add_edge(node, worker.entry, Some("each item"));
+        GraphSpan {
+            entry: node,
+            exit: worker.exit,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -0,0 +1,119 @@
+//! Predicate-driven branching between two steps.
+//!
+//! Unlike `RouterStep`, which asks the model to pick a branch, `BranchStep` decides
+//! from the input itself, so deterministic routing costs no model call.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::Result;
+
+use super::graph::{GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A step that runs one of two steps depending on a predicate over the input.
+///
+/// The branches may be different step types; they are boxed internally. Only the
+/// branch that runs touches the context, so its metrics, artifacts and trace events
+/// are recorded as usual.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use gemini_structured_output::workflow::{BranchStep, Step};
+///
+/// let summarize = BranchStep::new(
+///     |doc: &Document| doc.text.len() > 20_000,
+///     chunked_summarizer,
+///     summarizer,
+/// );
+/// ```
+pub struct BranchStep<Input, Output> {
+    predicate: Arc<dyn Fn(&Input) -> bool + Send + Sync>,
+    if_true: Box<dyn Step<Input, Output>>,
+    if_false: Box<dyn Step<Input, Output>>,
+}
+
+impl<Input, Output> BranchStep<Input, Output> {
+    /// Create a step running `if_true` when `predicate` holds for the input and
+    /// `if_false` otherwise.
+    pub fn new(
+        predicate: impl Fn(&Input) -> bool + Send + Sync + 'static,
+        if_true: impl Step<Input, Output> + 'static,
+        if_false: impl Step<Input, Output> + 'static,
+    ) -> Self {
+        Self {
+            predicate: Arc::new(predicate),
+            if_true: Box::new(if_true),
+            if_false: Box::new(if_false),
+        }
+    }
+}
+
+#[async_trait]
+impl<Input, Output> Step<Input, Output> for BranchStep<Input, Output>
+where
+    Input: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    async fn run(&self, input: Input, ctx: &ExecutionContext) -> Result<Output> {
+        if (self.predicate)(&input) {
+            self.if_true.run(input, ctx).await
+        } else {
+            self.if_false.run(input, ctx).await
+        }
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let decision = graph.add_node("branch", "router");
+        let if_true = self.if_true.describe(graph);
+        let if_false = self.if_false.describe(graph);
+        graph.add_edge(decision, if_true.entry, Some("true"));
+        graph.add_edge(decision, if_false.entry, Some("false"));
+        let join = graph.add_node("merge", "join");
+        graph.add_edge(if_true.exit, join, None);
+        graph.add_edge(if_false.exit, join, None);
+        GraphSpan {
+            entry: decision,
+            exit: join,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+
+    fn classify() -> BranchStep<i32, String> {
+        BranchStep::new(
+            |x: &i32| *x >= 0,
+            LambdaStep(|x: i32| async move { Ok(format!("positive {x}")) }).metered(),
+            LambdaStep(|x: i32| async move { Ok(x.abs()) }).map(|x| format!("negative {x}")),
+        )
+    }
+
+    #[tokio::test]
+    async fn runs_branch_selected_by_predicate() {
+        let step = classify();
+        let ctx = ExecutionContext::new();
+
+        assert_eq!(step.run(3, &ctx).await.unwrap(), "positive 3");
+        assert_eq!(step.run(-2, &ctx).await.unwrap(), "negative 2");
+        // Only the metered true branch counts towards steps_completed.
+        assert_eq!(ctx.snapshot().steps_completed, 1);
+    }
+
+    #[test]
+    fn describes_both_branches() {
+        let graph = classify().describe_graph();
+        let labels: Vec<_> = graph
+            .edges
+            .iter()
+            .filter_map(|e| e.label.as_deref())
+            .collect();
+        assert!(labels.contains(&"true") && labels.contains(&"false"));
+    }
+}
@@ -9,6 +9,7 @@ use async_trait::async_trait;
 
 use crate::Result;
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -63,6 +64,16 @@ where
         let intermediate = self.first.run(input, ctx).await?;
         self.second.run(intermediate, ctx).await
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let first = self.first.describe(graph);
+        let second = self.second.describe(graph);
+        graph.add_edge(first.exit, second.entry, None);
+        GraphSpan {
+            entry: first.entry,
+            exit: second.exit,
+        }
+    }
 }
 
 /// Connects two steps, returning both the intermediate and final results as a tuple.
@@ -118,6 +129,19 @@ where
         let output = self.second.run(intermediate.clone(), ctx).await?;
         Ok((intermediate, output))
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let first = self.first.describe(graph);
+        let second = self.second.describe(graph);
+        graph.add_edge(first.exit, second.entry, None);
+        let join = graph.add_node("tuple", "join");
+        graph.add_edge(first.exit, join, Some("intermediate"));
+        graph.add_edge(second.exit, join, None);
+        GraphSpan {
+            entry: first.entry,
+            exit: join,
+        }
+    }
 }
 
 #[cfg(test)]
@@ -10,6 +10,7 @@ use serde::Serialize;
 use crate::{Result, StructuredError};
 
 use super::events::WorkflowEvent;
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -90,6 +91,10 @@ where
             data,
         })
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node(self.name.clone(), "checkpoint"))
+    }
 }
 
 /// A conditional checkpoint that only triggers when a predicate is true.
@@ -157,6 +162,10 @@ where
             Ok(input)
         }
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node(self.name.clone(), "checkpoint"))
+    }
 }
 
 #[cfg(test)]
@@ -39,6 +39,19 @@ pub enum WorkflowEvent {
         /// The artifact data as a JSON value.
         data: serde_json::Value,
     },
+    /// A failed step is about to be re-run.
+    ///
+    /// Emitted by `RetryStep` before each backoff sleep.
+    Retry {
+        /// Name of the step being retried.
+        step_name: String,
+        /// Number of retries so far, including this one.
+        retries: usize,
+        /// Backoff before the next attempt, in milliseconds.
+        delay_ms: u128,
+        /// Error message from the failed attempt.
+        message: String,
+    },
     /// An error occurred during step execution.
     Error {
         /// Name of the step where the error occurred.
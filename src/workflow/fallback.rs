@@ -0,0 +1,322 @@
+//! Fallbacks for failing steps.
+//!
+//! The `FallbackStep` combinator turns a failing step into a recorded failure plus a
+//! default output, so "nice to have" enrichment does not abort the whole pipeline.
+//! `RecoverStep` instead hands a [`StepFailure`] to a recovery step (typically an
+//! agent) that produces the typed output, and `AlternateStep` re-runs the same input
+//! through a different step (e.g. a cheap extractor backed by an expensive one).
+
+use async_trait::async_trait;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::Result;
+
+use super::events::WorkflowEvent;
+use super::graph::{short_type_name, GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A step that returns a default value when its inner step fails.
+///
+/// The error is recorded on the context as a failure and an `Error` trace event, so
+/// the fallback stays visible in metrics and traces.
+///
+/// Created by calling `.or_default()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let pipeline = extractor
+///     .then(sentiment_tagger.or_default(Sentiment::Unknown))
+///     .then(writer);
+/// ```
+pub struct FallbackStep<S, O> {
+    inner: S,
+    default: O,
+}
+
+impl<S, O> FallbackStep<S, O> {
+    /// Create a fallback step returning `default` when `inner` fails.
+    pub fn new(inner: S, default: O) -> Self {
+        Self { inner, default }
+    }
+}
+
+#[async_trait]
+impl<S, I, O> Step<I, O> for FallbackStep<S, O>
+where
+    I: Send + Sync + 'static,
+    O: Clone + Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        match self.inner.run(input, ctx).await {
+            Ok(output) => Ok(output),
+            Err(err) => {
+                let step_name = short_type_name(std::any::type_name::<S>());
+                let message = format!("Falling back to default output: {err}");
+                ctx.record_failure(format!("{step_name}: {message}"));
+                ctx.emit(WorkflowEvent::Error { step_name, message });
+                Ok(self.default.clone())
+            }
+        }
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        let fallback = graph.add_node("or default", "fallback");
+        graph.add_edge(inner.exit, fallback, None);
+        GraphSpan {
+            entry: inner.entry,
+            exit: fallback,
+        }
+    }
+}
+
+/// Serializable summary of a step error, passed to recovery steps.
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+pub struct StepFailure {
+    /// Short type name of the step that failed.
+    pub step_name: String,
+    /// The error message.
+    pub error: String,
+    /// Whether the error looked transient (rate limits, unavailability).
+    pub retryable: bool,
+}
+
+/// A step that asks a recovery step for the output when its inner step fails.
+///
+/// The failure is recorded on the context, emitted as an `Error` trace event and
+/// stored as the `{step}.recovered_error` artifact (`step` being the failed step's
+/// short type name) before `recovery` runs. If recovery fails too, its error is
+/// returned.
+///
+/// Created by calling `.recover_with_agent()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// // FailureExplainer: Step<StepFailure, ExtractionReport>
+/// let pipeline = extractor.recover_with_agent(FailureExplainer::new(client));
+/// ```
+pub struct RecoverStep<S, R> {
+    inner: S,
+    recovery: R,
+}
+
+impl<S, R> RecoverStep<S, R> {
+    /// Create a step that runs `recovery` when `inner` fails.
+    pub fn new(inner: S, recovery: R) -> Self {
+        Self { inner, recovery }
+    }
+}
+
+#[async_trait]
+impl<S, R, I, O> Step<I, O> for RecoverStep<S, R>
+where
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+    R: Step<StepFailure, O> + Send + Sync,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        match self.inner.run(input, ctx).await {
+            Ok(output) => Ok(output),
+            Err(err) => {
+                let step_name = short_type_name(std::any::type_name::<S>());
+                let failure = StepFailure {
+                    step_name: step_name.clone(),
+                    error: err.to_string(),
+                    retryable: err.is_retryable(),
+                };
+                let message = format!("Recovering from failure: {err}");
+                ctx.record_failure(format!("{step_name}: {message}"));
+                ctx.emit(WorkflowEvent::Error {
+                    step_name: step_name.clone(),
+                    message,
+                });
+                let key = format!("{step_name}.recovered_error");
+                ctx.emit_artifact(&step_name, &key, &failure);
+                self.recovery.run(failure, ctx).await
+            }
+        }
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        let recovery = self.recovery.describe(graph);
+        graph.add_edge(inner.exit, recovery.entry, Some("on error"));
+        GraphSpan {
+            entry: inner.entry,
+            exit: inner.exit,
+        }
+    }
+}
+
+/// A step that runs an alternate step on the same input when its primary step fails.
+///
+/// The primary failure is recorded on the context, emitted as an `Error` trace event
+/// and stored as the `{step}.fallback_fired` artifact (`step` being the primary's short
+/// type name) before `alternate` runs. If the alternate fails too, its error is
+/// returned.
+///
+/// Created by calling `.or_else()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let extractor = flash_extractor.or_else(pro_extractor);
+/// ```
+pub struct AlternateStep<S, A> {
+    primary: S,
+    alternate: A,
+}
+
+impl<S, A> AlternateStep<S, A> {
+    /// Create a step that runs `alternate` when `primary` fails.
+    pub fn new(primary: S, alternate: A) -> Self {
+        Self { primary, alternate }
+    }
+}
+
+#[async_trait]
+impl<S, A, I, O> Step<I, O> for AlternateStep<S, A>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+    A: Step<I, O> + Send + Sync,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        match self.primary.run(input.clone(), ctx).await {
+            Ok(output) => Ok(output),
+            Err(err) => {
+                let step_name = short_type_name(std::any::type_name::<S>());
+                let alternate_name = short_type_name(std::any::type_name::<A>());
+                let failure = StepFailure {
+                    step_name: step_name.clone(),
+                    error: err.to_string(),
+                    retryable: err.is_retryable(),
+                };
+                let message = format!("Falling back to {alternate_name}: {err}");
+                ctx.record_failure(format!("{step_name}: {message}"));
+                ctx.emit(WorkflowEvent::Error {
+                    step_name: step_name.clone(),
+                    message,
+                });
+                let key = format!("{step_name}.fallback_fired");
+                ctx.emit_artifact(&step_name, &key, &failure);
+                self.alternate.run(input, ctx).await
+            }
+        }
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let primary = self.primary.describe(graph);
+        let alternate = self.alternate.describe(graph);
+        graph.add_edge(primary.exit, alternate.entry, Some("on error"));
+        GraphSpan {
+            entry: primary.entry,
+            exit: primary.exit,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+    use crate::StructuredError;
+
+    #[tokio::test]
+    async fn returns_default_and_records_failure() {
+        let step = LambdaStep(|_: i32| async move {
+            Err::<String, _>(StructuredError::Context("enrichment unavailable".into()))
+        })
+        .or_default("unknown".to_string());
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(1, &ctx).await.unwrap(), "unknown");
+        assert_eq!(ctx.snapshot().failures.len(), 1);
+        assert!(matches!(
+            ctx.trace_snapshot()[0].event,
+            WorkflowEvent::Error { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn passes_through_success() {
+        let step = LambdaStep(|x: i32| async move { Ok(x * 2) }).or_default(0);
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(4, &ctx).await.unwrap(), 8);
+        assert!(ctx.snapshot().failures.is_empty());
+    }
+
+    #[tokio::test]
+    async fn recovery_step_builds_output_from_failure() {
+        let step = LambdaStep(|_: i32| async move {
+            Err::<String, _>(StructuredError::Context("no invoice found".into()))
+        })
+        .recover_with_agent(LambdaStep(|failure: StepFailure| async move {
+            Ok(format!("failed: {}", failure.error))
+        }));
+
+        let ctx = ExecutionContext::new();
+        let output = step.run(1, &ctx).await.unwrap();
+
+        assert!(output.starts_with("failed: ") && output.contains("no invoice found"));
+        assert_eq!(ctx.snapshot().failures.len(), 1);
+        let recorded: StepFailure = ctx.get_artifact("LambdaStep.recovered_error").unwrap();
+        assert!(!recorded.retryable);
+    }
+
+    #[tokio::test]
+    async fn nested_recoveries_keep_separate_artifacts() {
+        let step = LambdaStep(|_: i32| async move {
+            Err::<String, _>(StructuredError::Context("extraction failed".into()))
+        })
+        .recover_with_agent(LambdaStep(|_: StepFailure| async move {
+            Err::<String, _>(StructuredError::Context("explainer failed".into()))
+        }))
+        .recover_with_agent(LambdaStep(|failure: StepFailure| async move {
+            Ok(failure.error)
+        }));
+
+        let ctx = ExecutionContext::new();
+        step.run(1, &ctx).await.unwrap();
+
+        let inner: StepFailure = ctx.get_artifact("LambdaStep.recovered_error").unwrap();
+        assert!(inner.error.contains("extraction failed"));
+        let outer: StepFailure = ctx.get_artifact("RecoverStep.recovered_error").unwrap();
+        assert!(outer.error.contains("explainer failed"));
+    }
+
+    #[tokio::test]
+    async fn alternate_runs_with_same_input_after_primary_fails() {
+        let step = LambdaStep(|_: String| async move {
+            Err::<usize, _>(StructuredError::Context("cheap model gave up".into()))
+        })
+        .or_else(LambdaStep(|text: String| async move { Ok(text.len()) }));
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run("invoice".to_string(), &ctx).await.unwrap(), 7);
+
+        let traces = ctx.trace_snapshot();
+        assert!(matches!(traces[0].event, WorkflowEvent::Error { .. }));
+        let fired: StepFailure = ctx.get_artifact("LambdaStep.fallback_fired").unwrap();
+        assert!(fired.error.contains("cheap model gave up"));
+    }
+
+    #[tokio::test]
+    async fn alternate_is_skipped_when_primary_succeeds() {
+        let step = LambdaStep(|x: i32| async move { Ok(x) }).or_else(LambdaStep(|_: i32| async {
+            Err::<i32, _>(StructuredError::Context("should not run".into()))
+        }));
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(3, &ctx).await.unwrap(), 3);
+        assert!(ctx.trace_snapshot().is_empty());
+    }
+}
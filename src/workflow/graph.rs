@@ -0,0 +1,234 @@
+//! Structural description of composed workflows for visualization.
+//!
+//! Steps are type-erased once composed, so each combinator contributes its own
+//! structure through [`Step::describe`](super::Step::describe). Leaf steps default to
+//! a single node labelled with their type name; wrap them with `.named()` for
+//! friendlier labels.
+//!
+//! # Example
+//!
+//! ```rust,ignore
+//! let pipeline = summarizer.named("Summarize").then(drafter.named("Draft"));
+//! println!("{}", pipeline.describe_graph().to_mermaid());
+//! ```
+
+use std::fmt::Write;
+
+/// A node in a [`WorkflowGraph`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphNode {
+    pub id: usize,
+    pub label: String,
+    /// Combinator or step kind (e.g. `"step"`, `"parallel"`, `"router"`).
+    pub kind: String,
+}
+
+/// A directed edge between two nodes.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct GraphEdge {
+    pub from: usize,
+    pub to: usize,
+    pub label: Option<String>,
+}
+
+/// Entry and exit nodes of a described sub-graph.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GraphSpan {
+    pub entry: usize,
+    pub exit: usize,
+}
+
+impl GraphSpan {
+    /// A span consisting of a single node.
+    pub fn single(id: usize) -> Self {
+        Self {
+            entry: id,
+            exit: id,
+        }
+    }
+}
+
+/// Best-effort structural graph of a workflow.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkflowGraph {
+    pub nodes: Vec<GraphNode>,
+    pub edges: Vec<GraphEdge>,
+}
+
+impl WorkflowGraph {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Add a node and return its id.
+    pub fn add_node(&mut self, label: impl Into<String>, kind: impl Into<String>) -> usize {
+        let id = self.nodes.len();
+        self.nodes.push(GraphNode {
+            id,
+            label: label.into(),
+            kind: kind.into(),
+        });
+        id
+    }
+
+    /// Add a directed edge between two nodes.
+    pub fn add_edge(&mut self, from: usize, to: usize, label: Option<&str>) {
+        self.edges.push(GraphEdge {
+            from,
+            to,
+            label: label.map(str::to_string),
+        });
+    }
+
+    /// Rename a node, e.g. when a wrapper knows a better label than the inner step.
+    pub fn relabel(&mut self, id: usize, label: impl Into<String>) {
+        if let Some(node) = self.nodes.get_mut(id) {
+            node.label = label.into();
+        }
+    }
+
+    /// Render as a Mermaid `flowchart`.
+    pub fn to_mermaid(&self) -> String {
+        let mut out = String::from("flowchart TD\n");
+        for node in &self.nodes {
+            let label = escape_label(&node.label);
+            let shape = match node.kind.as_str() {
+                "router" => format!("{{\"{label}\"}}"),
+                "parallel" | "batch" | "windowed" => format!("[[\"{label}\"]]"),
+                "checkpoint" => format!("([\"{label}\"])"),
+                _ => format!("[\"{label}\"]"),
+            };
+            writeln!(out, "    n{}{}", node.id, shape).unwrap();
+        }
+        for edge in &self.edges {
+            match &edge.label {
+                Some(label) => writeln!(
+                    out,
+                    "    n{} -->|\"{}\"| n{}",
+                    edge.from,
+                    escape_label(label),
+                    edge.to
+                )
+                .unwrap(),
+                None => writeln!(out, "    n{} --> n{}", edge.from, edge.to).unwrap(),
+            }
+        }
+        out
+    }
+
+    /// Render as a Graphviz DOT digraph.
+    pub fn to_dot(&self) -> String {
+        let mut out = String::from("digraph workflow {\n    rankdir=TB;\n");
+        for node in &self.nodes {
+            let shape = match node.kind.as_str() {
+                "router" => "diamond",
+                "parallel" | "batch" | "windowed" => "box3d",
+                "checkpoint" => "octagon",
+                _ => "box",
+            };
+            writeln!(
+                out,
+                "    n{} [label=\"{}\", shape={}];",
+                node.id,
+                escape_label(&node.label),
+                shape
+            )
+            .unwrap();
+        }
+        for edge in &self.edges {
+            match &edge.label {
+                Some(label) => writeln!(
+                    out,
+                    "    n{} -> n{} [label=\"{}\"];",
+                    edge.from,
+                    edge.to,
+                    escape_label(label)
+                )
+                .unwrap(),
+                None => writeln!(out, "    n{} -> n{};", edge.from, edge.to).unwrap(),
+            }
+        }
+        out.push_str("}\n");
+        out
+    }
+}
+
+/// Short, human-readable name for a step type (`my_app::agents::Summarizer<T>` -> `Summarizer`).
+pub(crate) fn short_type_name(full: &str) -> String {
+    let base = full.split('<').next().unwrap_or(full);
+    base.rsplit("::").next().unwrap_or(base).to_string()
+}
+
+fn escape_label(label: &str) -> String {
+    label.replace('\\', "\\\\").replace('"', "\\\"")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::{LambdaStep, ParallelMapStep, Step};
+
+    #[test]
+    fn chain_of_named_steps_produces_linear_graph() {
+        let pipeline = LambdaStep(|x: i32| async move { Ok(x + 1) })
+            .named("Increment")
+            .then(LambdaStep(|x: i32| async move { Ok(x * 2) }).named("Double"));
+
+        let graph = pipeline.describe_graph();
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes[0].label, "Increment");
+        assert_eq!(graph.nodes[1].label, "Double");
+        assert_eq!(graph.edges.len(), 1);
+
+        let mermaid = graph.to_mermaid();
+        assert!(mermaid.starts_with("flowchart TD"));
+        assert!(mermaid.contains("n0 --> n1"));
+
+        let dot = graph.to_dot();
+        assert!(dot.contains("n0 -> n1;"));
+        assert!(dot.contains("label=\"Double\""));
+    }
+
+    #[test]
+    fn workflow_exports_dot_with_step_names() {
+        let workflow = crate::workflow::Workflow::new(
+            LambdaStep(|x: i32| async move { Ok(vec![x]) })
+                .named("Split")
+                .then(ParallelMapStep::new(
+                    LambdaStep(|x: i32| async move { Ok(x) }).named("Worker"),
+                    2,
+                )),
+        );
+
+        let dot = workflow.to_dot();
+
+        assert!(dot.starts_with("digraph workflow {"));
+        assert!(dot.contains("label=\"Split\""));
+        assert!(dot.contains("label=\"Worker\""));
+        assert!(dot.contains("shape=box3d"));
+        assert!(dot.trim_end().ends_with('}'));
+    }
+
+    #[test]
+    fn parallel_map_wraps_worker() {
+        let worker = LambdaStep(|x: i32| async move { Ok(x) }).named("Worker");
+        let parallel = ParallelMapStep::new(worker, 3);
+
+        let graph = Step::<Vec<i32>, Vec<i32>>::describe_graph(&parallel);
+
+        assert_eq!(graph.nodes.len(), 2);
+        assert_eq!(graph.nodes[0].kind, "parallel");
+        assert!(graph.nodes[0].label.contains('3'));
+        assert_eq!(graph.nodes[1].label, "Worker");
+    }
+
+    #[test]
+    fn unnamed_steps_use_short_type_names() {
+        assert_eq!(short_type_name("my_app::agents::Summarizer"), "Summarizer");
+        assert_eq!(
+            short_type_name("gemini_structured_output::workflow::LambdaStep<closure>"),
+            "LambdaStep"
+        );
+    }
+}
@@ -11,6 +11,7 @@ use async_trait::async_trait;
 use crate::Result;
 
 use super::events::WorkflowEvent;
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -92,6 +93,7 @@ where
 
         match &result {
             Ok(_) => {
+                ctx.record_step_duration(self.name.clone(), duration);
                 ctx.emit(WorkflowEvent::StepEnd {
                     step_name: self.name.clone(),
                     duration_ms: duration,
@@ -107,6 +109,14 @@ where
 
         result
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let span = self.inner.describe(graph);
+        if span.entry == span.exit {
+            graph.relabel(span.entry, self.name.clone());
+        }
+        span
+    }
 }
 
 #[cfg(test)]
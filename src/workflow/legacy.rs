@@ -6,6 +6,7 @@ use async_trait::async_trait;
 use std::future::Future;
 use std::pin::Pin;
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 use crate::error::Result;
@@ -53,4 +54,8 @@ where
     async fn run(&self, input: Input, _ctx: &ExecutionContext) -> Result<Output> {
         self.execute(input).await
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node(self.name.clone(), "step"))
+    }
 }
@@ -0,0 +1,130 @@
+//! Iterate a step until its output satisfies a condition.
+//!
+//! `LoopStep` feeds each output of an inner `Step<T, T>` back in as the next input,
+//! for pipelines that polish a draft or expand a plan until it is good enough. Unlike
+//! the refinement engine, the inner step can be any transform or agent.
+
+use std::sync::Arc;
+
+use async_trait::async_trait;
+
+use crate::{Result, StructuredError};
+
+use super::graph::{GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A step that re-applies its inner step until `condition` holds for the output.
+///
+/// The inner step runs at least once. Each iteration is recorded as a completed step
+/// on the context. If `condition` still fails after `max_iterations` runs, the loop
+/// stops with [`StructuredError::Context`].
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use gemini_structured_output::workflow::LoopStep;
+///
+/// let polished = LoopStep::new(editor, 4, |draft: &Draft| draft.issues.is_empty());
+/// let draft = polished.run(first_draft, &ctx).await?;
+/// ```
+pub struct LoopStep<S, T> {
+    inner: S,
+    max_iterations: usize,
+    condition: Arc<dyn Fn(&T) -> bool + Send + Sync>,
+}
+
+impl<S, T> LoopStep<S, T> {
+    /// Create a loop running `inner` up to `max_iterations` times until `condition` holds.
+    ///
+    /// `max_iterations` is clamped to at least 1.
+    pub fn new(
+        inner: S,
+        max_iterations: usize,
+        condition: impl Fn(&T) -> bool + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            inner,
+            max_iterations: max_iterations.max(1),
+            condition: Arc::new(condition),
+        }
+    }
+}
+
+#[async_trait]
+impl<S, T> Step<T, T> for LoopStep<S, T>
+where
+    T: Send + Sync + 'static,
+    S: Step<T, T> + Send + Sync,
+{
+    async fn run(&self, input: T, ctx: &ExecutionContext) -> Result<T> {
+        let mut value = input;
+        for _ in 0..self.max_iterations {
+            value = self.inner.run(value, ctx).await?;
+            ctx.record_step();
+            if (self.condition)(&value) {
+                return Ok(value);
+            }
+        }
+        Err(StructuredError::Context(format!(
+            "Loop did not converge after {} iterations",
+            self.max_iterations
+        )))
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        graph.add_edge(
+            inner.exit,
+            inner.entry,
+            Some(&format!("until condition (max {})", self.max_iterations)),
+        );
+        inner
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+
+    #[tokio::test]
+    async fn feeds_output_back_until_condition_holds() {
+        let step = LoopStep::new(
+            LambdaStep(|x: u32| async move { Ok(x * 2) }),
+            10,
+            |x: &u32| *x >= 20,
+        );
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(3, &ctx).await.unwrap(), 24);
+        assert_eq!(ctx.snapshot().steps_completed, 3);
+    }
+
+    #[tokio::test]
+    async fn fails_when_cap_is_hit() {
+        let step = LoopStep::new(
+            LambdaStep(|x: u32| async move { Ok(x + 1) }),
+            3,
+            |x: &u32| *x > 100,
+        );
+
+        let ctx = ExecutionContext::new();
+        let err = step.run(0, &ctx).await.unwrap_err();
+        assert!(matches!(err, StructuredError::Context(_)));
+        assert_eq!(ctx.snapshot().steps_completed, 3);
+    }
+
+    #[tokio::test]
+    async fn zero_iterations_still_runs_once() {
+        let step = LoopStep::new(
+            LambdaStep(|x: u32| async move { Ok(x + 1) }),
+            0,
+            |x: &u32| *x == 1,
+        );
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(0, &ctx).await.unwrap(), 1);
+        assert_eq!(ctx.snapshot().steps_completed, 1);
+    }
+}
@@ -0,0 +1,82 @@
+//! Step-completion metering for steps that do not record metrics themselves.
+//!
+//! Agent steps and the LLM-backed combinators call `ExecutionContext::record_step`
+//! when they finish. Plain transforms such as `LambdaStep` or `.map()` do not, so the
+//! `MeteredStep` combinator counts them in `steps_completed`.
+
+use async_trait::async_trait;
+
+use crate::Result;
+
+use super::graph::{GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A wrapper that records a completed step on the context when its inner step succeeds.
+///
+/// Created by calling `.metered()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let pipeline = LambdaStep(load_document)
+///     .metered()
+///     .then(summarizer)
+///     .map(render_markdown)
+///     .metered();
+/// ```
+pub struct MeteredStep<S> {
+    inner: S,
+}
+
+impl<S> MeteredStep<S> {
+    /// Create a metered step wrapping `inner`.
+    pub fn new(inner: S) -> Self {
+        Self { inner }
+    }
+}
+
+#[async_trait]
+impl<S, I, O> Step<I, O> for MeteredStep<S>
+where
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        let output = self.inner.run(input, ctx).await?;
+        ctx.record_step();
+        Ok(output)
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        self.inner.describe(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+    use crate::StructuredError;
+
+    #[tokio::test]
+    async fn counts_successful_runs_only() {
+        let pipeline = LambdaStep(|x: i32| async move { Ok(x + 1) })
+            .metered()
+            .map(|x: i32| x * 2)
+            .metered();
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(pipeline.run(1, &ctx).await.unwrap(), 4);
+        assert_eq!(ctx.snapshot().steps_completed, 2);
+
+        let failing =
+            LambdaStep(
+                |_: i32| async move { Err::<i32, _>(StructuredError::Context("boom".into())) },
+            )
+            .metered();
+        assert!(failing.run(1, &ctx).await.is_err());
+        assert_eq!(ctx.snapshot().steps_completed, 2);
+    }
+}
@@ -3,11 +3,15 @@
 //! This module provides observability primitives for tracking workflow execution,
 //! including token usage, retry attempts, failure logging, and structured event tracing.
 
+use std::fmt::Write;
 use std::sync::{Arc, Mutex};
 
-use gemini_rust::generation::model::UsageMetadata;
-use serde::Serialize;
+use gemini_rust::{generation::model::UsageMetadata, Model};
+use serde::{de::DeserializeOwned, Serialize};
+use tokio::sync::mpsc;
+use tracing::warn;
 
+use super::artifacts::ArtifactMap;
 use super::events::{TraceEntry, WorkflowEvent};
 use crate::models::GenerationOutcome;
 
@@ -28,6 +32,10 @@ pub struct WorkflowMetrics {
     pub steps_completed: usize,
     /// Collected failure messages from the workflow.
     pub failures: Vec<String>,
+    /// Model assignments for steps that ran with a model override, as `(step, model)` pairs.
+    pub step_models: Vec<(String, String)>,
+    /// Wall-clock durations in milliseconds for instrumented steps, as `(step, ms)` pairs.
+    pub step_durations: Vec<(String, u128)>,
 }
 
 impl WorkflowMetrics {
@@ -55,6 +63,63 @@ impl WorkflowMetrics {
     pub fn record_step(&mut self) {
         self.steps_completed += 1;
     }
+
+    /// Record which model a step ran against.
+    pub fn record_step_model(&mut self, step: String, model: String) {
+        self.step_models.push((step, model));
+    }
+
+    /// Record how long a step took, in milliseconds.
+    pub fn record_step_duration(&mut self, step: String, duration_ms: u128) {
+        self.step_durations.push((step, duration_ms));
+    }
+
+    /// The slowest recorded step and its duration in milliseconds.
+    ///
+    /// Only steps wrapped with `.instrumented()` record timings.
+    pub fn slowest_step(&self) -> Option<(&str, u128)> {
+        self.step_durations
+            .iter()
+            .max_by_key(|(_, ms)| *ms)
+            .map(|(step, ms)| (step.as_str(), *ms))
+    }
+
+    /// Render a human-readable summary of the run.
+    ///
+    /// The public fields stay available for custom formatting.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (result, metrics) = workflow.run(input).await?;
+    /// println!("{}", metrics.report());
+    /// ```
+    pub fn report(&self) -> String {
+        let mut out = String::new();
+        writeln!(out, "Steps completed: {}", self.steps_completed).unwrap();
+        writeln!(out, "Total tokens: {}", self.total_token_count).unwrap();
+        writeln!(out, "  - Prompt tokens: {}", self.prompt_token_count).unwrap();
+        writeln!(
+            out,
+            "  - Completion tokens: {}",
+            self.candidates_token_count
+        )
+        .unwrap();
+        writeln!(out, "Network attempts: {}", self.network_attempts).unwrap();
+        writeln!(out, "Parse correction attempts: {}", self.parse_attempts).unwrap();
+        if let Some((step, ms)) = self.slowest_step() {
+            writeln!(out, "Slowest step: {step} ({ms} ms)").unwrap();
+        }
+        if self.failures.is_empty() {
+            writeln!(out, "Failures: none").unwrap();
+        } else {
+            writeln!(out, "Failures ({}):", self.failures.len()).unwrap();
+            for failure in &self.failures {
+                writeln!(out, "  - {failure}").unwrap();
+            }
+        }
+        out
+    }
 }
 
 /// Context passed to every step in the workflow.
@@ -88,6 +153,12 @@ pub struct ExecutionContext {
     pub metrics: Arc<Mutex<WorkflowMetrics>>,
     /// Shared trace log for structured workflow events.
     pub traces: Arc<Mutex<Vec<TraceEntry>>>,
+    /// Shared store of artifacts emitted via [`emit_artifact`](Self::emit_artifact).
+    pub artifacts: Arc<Mutex<ArtifactMap>>,
+    /// Model override applied to agent steps running under this context.
+    model_override: Option<Model>,
+    /// Channel that receives each emitted event as it happens.
+    event_sink: Option<mpsc::Sender<WorkflowEvent>>,
 }
 
 impl Default for ExecutionContext {
@@ -102,9 +173,52 @@ impl ExecutionContext {
         Self {
             metrics: Arc::new(Mutex::new(WorkflowMetrics::default())),
             traces: Arc::new(Mutex::new(Vec::new())),
+            artifacts: Arc::new(Mutex::new(ArtifactMap::new())),
+            model_override: None,
+            event_sink: None,
         }
     }
 
+    /// Forward every emitted event to `sink` as well as the trace log.
+    ///
+    /// Lets a dashboard follow steps live instead of reading `trace_snapshot()` after the
+    /// run. Forwarding never blocks the workflow: when the channel is full or closed the
+    /// event is dropped with a warning (it is still kept in the trace log).
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (tx, mut rx) = tokio::sync::mpsc::channel(256);
+    /// tokio::spawn(async move {
+    ///     while let Some(event) = rx.recv().await {
+    ///         println!("{event:?}");
+    ///     }
+    /// });
+    /// let ctx = ExecutionContext::new().with_event_sink(tx);
+    /// ```
+    pub fn with_event_sink(mut self, sink: mpsc::Sender<WorkflowEvent>) -> Self {
+        self.event_sink = Some(sink);
+        self
+    }
+
+    /// Derive a context that shares metrics and traces but pins steps to `model`.
+    ///
+    /// Agents generated by `#[gemini_agent]` honor the override on each request.
+    pub fn with_model(&self, model: Model) -> Self {
+        Self {
+            metrics: Arc::clone(&self.metrics),
+            traces: Arc::clone(&self.traces),
+            artifacts: Arc::clone(&self.artifacts),
+            model_override: Some(model),
+            event_sink: self.event_sink.clone(),
+        }
+    }
+
+    /// The model override for steps running under this context, if any.
+    pub fn model_override(&self) -> Option<&Model> {
+        self.model_override.as_ref()
+    }
+
     /// Record usage and attempt counts from a generation outcome.
     pub fn record_outcome<T>(&self, outcome: &GenerationOutcome<T>) {
         let mut m = self.metrics.lock().unwrap();
@@ -124,6 +238,18 @@ impl ExecutionContext {
         m.record_failure(error.into());
     }
 
+    /// Record which model a step ran against.
+    pub fn record_step_model(&self, step: impl Into<String>, model: impl Into<String>) {
+        let mut m = self.metrics.lock().unwrap();
+        m.record_step_model(step.into(), model.into());
+    }
+
+    /// Record how long a step took, in milliseconds.
+    pub fn record_step_duration(&self, step: impl Into<String>, duration_ms: u128) {
+        let mut m = self.metrics.lock().unwrap();
+        m.record_step_duration(step.into(), duration_ms);
+    }
+
     /// Get a snapshot of the current metrics.
     pub fn snapshot(&self) -> WorkflowMetrics {
         let m = self.metrics.lock().unwrap();
@@ -143,6 +269,11 @@ impl ExecutionContext {
     /// });
     /// ```
     pub fn emit(&self, event: WorkflowEvent) {
+        if let Some(sink) = &self.event_sink {
+            if let Err(err) = sink.try_send(event.clone()) {
+                warn!(error = %err, "Dropped workflow event for event sink");
+            }
+        }
         let entry = TraceEntry::new(event);
         self.traces.lock().unwrap().push(entry);
     }
@@ -150,7 +281,8 @@ impl ExecutionContext {
     /// Emit an artifact event with automatic JSON serialization.
     ///
     /// This is a convenience method for recording intermediate outputs
-    /// from workflow steps.
+    /// from workflow steps. The value is also stored on the context so later
+    /// steps can read it with [`get_artifact`](Self::get_artifact).
     ///
     /// # Example
     ///
@@ -161,6 +293,10 @@ impl ExecutionContext {
     pub fn emit_artifact<T: Serialize>(&self, step_name: &str, key: &str, data: &T) {
         let json_data = serde_json::to_value(data)
             .unwrap_or_else(|_| serde_json::json!("<serialization_error>"));
+        self.artifacts
+            .lock()
+            .unwrap()
+            .insert(step_name, key, json_data.clone());
         self.emit(WorkflowEvent::Artifact {
             step_name: step_name.to_string(),
             key: key.to_string(),
@@ -168,6 +304,18 @@ impl ExecutionContext {
         });
     }
 
+    /// Read back an artifact emitted earlier in the run, deserialized into `T`.
+    ///
+    /// Returns `None` when no artifact has that key or it does not deserialize as `T`.
+    pub fn get_artifact<T: DeserializeOwned>(&self, key: &str) -> Option<T> {
+        self.artifacts.lock().unwrap().get(key)
+    }
+
+    /// Get a snapshot of all artifacts recorded so far.
+    pub fn artifacts_snapshot(&self) -> ArtifactMap {
+        self.artifacts.lock().unwrap().clone()
+    }
+
     /// Get a snapshot of the current trace log.
     ///
     /// Returns all trace entries recorded so far. Useful for debugging
@@ -183,3 +331,56 @@ impl ExecutionContext {
         self.traces.lock().unwrap().clear();
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn report_includes_slowest_step_and_failures() {
+        let mut metrics = WorkflowMetrics {
+            steps_completed: 2,
+            total_token_count: 150,
+            prompt_token_count: 100,
+            candidates_token_count: 50,
+            ..Default::default()
+        };
+        metrics.record_step_duration("Summarize".to_string(), 120);
+        metrics.record_step_duration("Review".to_string(), 480);
+        metrics.record_failure("Review: timed out once".to_string());
+
+        let report = metrics.report();
+
+        assert!(report.contains("Steps completed: 2"));
+        assert!(report.contains("Total tokens: 150"));
+        assert!(report.contains("Slowest step: Review (480 ms)"));
+        assert!(report.contains("Failures (1):\n  - Review: timed out once"));
+    }
+
+    #[test]
+    fn report_omits_slowest_step_without_timings() {
+        let report = WorkflowMetrics::default().report();
+
+        assert!(!report.contains("Slowest step"));
+        assert!(report.contains("Failures: none"));
+    }
+
+    #[tokio::test]
+    async fn event_sink_receives_events_without_blocking() {
+        let (tx, mut rx) = mpsc::channel(1);
+        let ctx = ExecutionContext::new().with_event_sink(tx);
+
+        ctx.emit_artifact("Extract", "count", &3);
+        ctx.emit(WorkflowEvent::Error {
+            step_name: "Extract".to_string(),
+            message: "dropped when the channel is full".to_string(),
+        });
+
+        assert!(matches!(
+            rx.recv().await,
+            Some(WorkflowEvent::Artifact { ref key, .. }) if key == "count"
+        ));
+        assert!(rx.try_recv().is_err());
+        assert_eq!(ctx.trace_snapshot().len(), 2);
+    }
+}
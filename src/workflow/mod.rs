@@ -7,7 +7,7 @@
 //! # Core Concepts
 //!
 //! - **Step**: The fundamental trait for workflow units
-//! - **ExecutionContext**: Shared context for metrics collection
+//! - **ExecutionContext**: Shared context for metrics collection and artifacts
 //! - **WorkflowMetrics**: Aggregated token usage and execution statistics
 //! - **ChainStep**: Sequential composition of steps
 //! - **ChainTupleStep**: Sequential composition preserving intermediate results
@@ -15,7 +15,12 @@
 //! - **ParallelMapStep**: Apply a step to multiple inputs concurrently
 //! - **ReduceStep**: Aggregate multiple results into one
 //! - **RouterStep**: Conditional branching based on LLM decisions
+//! - **BranchStep**: Conditional branching based on a predicate over the input
+//! - **ValidatedStep**: Predicate-checked output with feedback retries
+//! - **ResumableWorkflow**: Pipeline that can be resumed from a named checkpoint
+//! - **LoopStep**: Re-apply a step to its own output until a condition holds
 //! - **Workflow**: High-level container with automatic metrics collection
+//! - **WorkflowGraph**: Structural description for Mermaid/DOT export
 //!
 //! # Example: Fluent Pipeline with Metrics
 //!
@@ -33,42 +38,83 @@
 //!
 //! println!("Total tokens: {}", metrics.total_token_count);
 //! ```
+//!
+//! # Example: Deterministic Branching
+//!
+//! When the branch is already known from the data, `BranchStep` picks it without a
+//! model call. The branches may be different step types.
+//!
+//! ```rust,ignore
+//! use gemini_structured_output::workflow::{BranchStep, Step};
+//!
+//! let triage = BranchStep::new(
+//!     |ticket: &Ticket| ticket.customer_tier == Tier::Enterprise,
+//!     escalation_agent.named("Escalate"),
+//!     faq_responder.named("AutoReply"),
+//! );
+//!
+//! // Equivalent, written as a combinator on the `true` branch:
+//! let triage = escalation_agent.branch(|ticket: &Ticket| ticket.is_enterprise(), faq_responder);
+//! ```
 
+mod artifacts;
 mod batch;
+mod branch;
 mod chain;
 mod checkpoint;
 mod events;
+mod fallback;
+mod graph;
 mod instrumented;
 mod legacy;
+mod loop_step;
+mod metered;
 mod metrics;
 mod parallel;
 mod reduce;
+mod resumable;
+mod retry;
 mod review;
 mod router;
 mod state;
 mod tap;
+mod timeout;
 mod traits;
+mod validated;
 mod windowed;
 
+pub use artifacts::{ArtifactMap, WithArtifactsStep};
 pub use batch::{BatchStep, SingleItemAdapter};
+pub use branch::BranchStep;
 pub use chain::{ChainStep, ChainTupleStep};
 pub use checkpoint::{CheckpointStep, ConditionalCheckpointStep};
 pub use events::{TraceEntry, WorkflowEvent};
+pub use fallback::{AlternateStep, FallbackStep, RecoverStep, StepFailure};
+pub use graph::{GraphEdge, GraphNode, GraphSpan, WorkflowGraph};
 pub use instrumented::InstrumentedStep;
 pub use legacy::{WorkflowAction, WorkflowFuture, WorkflowStep};
+pub use loop_step::LoopStep;
+pub use metered::MeteredStep;
 pub use metrics::{ExecutionContext, WorkflowMetrics};
-pub use parallel::{ParallelMapBuilder, ParallelMapStep};
-pub use reduce::{ConfiguredReduceStep, ReduceStep, ReduceStepBuilder};
+pub use parallel::{ParallelCollectStep, ParallelMapBuilder, ParallelMapStep};
+pub use reduce::{ConfiguredReduceStep, ProvenanceReduceStep, ReduceStep, ReduceStepBuilder};
+pub use resumable::ResumableWorkflow;
+pub use retry::RetryStep;
 pub use review::ReviewStep;
 pub use router::RouterStep;
-pub use state::{LambdaStateStep, StateStep, StateWorkflow, StepAdapter};
+pub use state::{FinalizedStateWorkflow, LambdaStateStep, StateStep, StateWorkflow, StepAdapter};
 pub use tap::TapStep;
+pub use timeout::TimeoutStep;
 pub use traits::{BoxedStepExt, LambdaStep, MapStep, Step};
+pub use validated::ValidatedStep;
 pub use windowed::WindowedContextStep;
 
 use std::sync::Arc;
 
-use crate::Result;
+use serde::de::DeserializeOwned;
+
+use crate::schema::GeminiStructured;
+use crate::{Result, StructuredError};
 
 /// A high-level container for a workflow process with automatic metrics collection.
 ///
@@ -115,6 +161,28 @@ where
         self
     }
 
+    /// Describe the wrapped pipeline as a [`WorkflowGraph`].
+    pub fn describe_graph(&self) -> WorkflowGraph {
+        self.step.describe_graph()
+    }
+
+    /// Render the wrapped pipeline as a Graphviz DOT digraph.
+    ///
+    /// Node labels come from `.named()` where set, otherwise from the step's type name.
+    ///
+    /// ```rust,ignore
+    /// std::fs::write("pipeline.dot", workflow.to_dot())?;
+    /// // dot -Tsvg pipeline.dot -o pipeline.svg
+    /// ```
+    pub fn to_dot(&self) -> String {
+        self.describe_graph().to_dot()
+    }
+
+    /// Render the wrapped pipeline as a Mermaid flowchart.
+    pub fn to_mermaid(&self) -> String {
+        self.describe_graph().to_mermaid()
+    }
+
     /// Run the workflow and return the result along with execution metrics.
     ///
     /// This method:
@@ -189,4 +257,26 @@ where
 
         result
     }
+
+    /// Dry-run the pipeline on an example input derived from `Input`'s schema.
+    ///
+    /// Catches wiring mistakes the type system cannot see, such as a `StepAdapter`
+    /// getter or `RouterStep` branch assembled at runtime. Build the steps on a client
+    /// configured with
+    /// [`with_schema_example_mock`](crate::StructuredClientBuilder::with_schema_example_mock)
+    /// so each model call is answered locally with a schema-derived example; the
+    /// returned output is the result of that run.
+    pub async fn validate_wiring(&self) -> Result<Output>
+    where
+        Input: GeminiStructured + DeserializeOwned,
+    {
+        let example = crate::schema::schema_example(&Input::gemini_schema());
+        let input: Input = serde_json::from_value(example).map_err(|e| {
+            StructuredError::Validation(format!(
+                "example input for {} did not deserialize: {e}",
+                std::any::type_name::<Input>()
+            ))
+        })?;
+        self.run(input).await.map(|(output, _)| output)
+    }
 }
@@ -1,7 +1,9 @@
 //! Parallel step execution for concurrent workflow processing.
 //!
 //! This module provides `ParallelMapStep` which applies a worker step to multiple
-//! inputs concurrently, with configurable concurrency limits.
+//! inputs concurrently, with configurable concurrency limits. Outputs keep the order
+//! of the inputs. `ParallelCollectStep` does the same but returns every item's
+//! `Result` instead of failing on the first error.
 //!
 //! Internally, this is implemented using `BatchStep` with a batch size of 1,
 //! demonstrating how the batch primitive can be used for different parallel
@@ -14,13 +16,16 @@ use futures::stream::{self, StreamExt};
 
 use crate::Result;
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
 /// Apply a worker step to each item concurrently, returning collected outputs.
 ///
 /// This step takes a `Vec<Input>` and runs the worker step on each item
-/// in parallel, respecting the configured concurrency limit.
+/// in parallel, respecting the configured concurrency limit. Outputs are returned in
+/// input order; the first failing item fails the whole map (see
+/// [`collect_errors`](Self::collect_errors) to keep going).
 ///
 /// # Implementation
 ///
@@ -58,6 +63,38 @@ where
     pub fn concurrency(&self) -> usize {
         self.concurrency
     }
+
+    /// Return every item's `Result` instead of failing on the first error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let results = ParallelMapStep::new(extractor, 4)
+    ///     .collect_errors()
+    ///     .run(documents, &ctx)
+    ///     .await?;
+    /// let failed = results.iter().filter(|r| r.is_err()).count();
+    /// ```
+    pub fn collect_errors(self) -> ParallelCollectStep<Input, Output> {
+        ParallelCollectStep { inner: self }
+    }
+
+    /// Run the worker on every input, keeping input order.
+    ///
+    /// Items complete in any order so a slow item never holds back free slots; the
+    /// results are sorted back into input order afterwards.
+    async fn run_all(&self, inputs: Vec<Input>, ctx: &ExecutionContext) -> Vec<Result<Output>> {
+        let mut results = stream::iter(inputs.into_iter().enumerate().map(|(index, input)| {
+            let worker = self.worker.clone();
+            let ctx_clone = ctx.clone();
+            async move { (index, worker.run(input, &ctx_clone).await) }
+        }))
+        .buffer_unordered(self.concurrency)
+        .collect::<Vec<_>>()
+        .await;
+        results.sort_by_key(|(index, _)| *index);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
 }
 
 #[async_trait]
@@ -71,14 +108,7 @@ where
             return Ok(Vec::new());
         }
 
-        let results = stream::iter(inputs.into_iter().map(|input| {
-            let worker = self.worker.clone();
-            let ctx_clone = ctx.clone();
-            async move { worker.run(input, &ctx_clone).await }
-        }))
-        .buffer_unordered(self.concurrency)
-        .collect::<Vec<_>>()
-        .await;
+        let results = self.run_all(inputs, ctx).await;
 
         let mut outputs = Vec::with_capacity(results.len());
         for result in results {
@@ -87,6 +117,42 @@ where
 
         Ok(outputs)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let node = graph.add_node(
+            format!("ParallelMap (concurrency {})", self.concurrency),
+            "parallel",
+        );
+        let worker = self.worker.describe(graph);
+        graph.add_edge(node, worker.entry, Some("each item"));
+        GraphSpan {
+            entry: node,
+            exit: worker.exit,
+        }
+    }
+}
+
+/// A parallel map that returns each item's `Result` in input order.
+///
+/// Created by calling [`ParallelMapStep::collect_errors`]. The step itself only fails
+/// if it cannot run at all; per-item failures are left for the caller to inspect.
+pub struct ParallelCollectStep<Input, Output> {
+    inner: ParallelMapStep<Input, Output>,
+}
+
+#[async_trait]
+impl<Input, Output> Step<Vec<Input>, Vec<Result<Output>>> for ParallelCollectStep<Input, Output>
+where
+    Input: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    async fn run(&self, inputs: Vec<Input>, ctx: &ExecutionContext) -> Result<Vec<Result<Output>>> {
+        Ok(self.inner.run_all(inputs, ctx).await)
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        Step::<Vec<Input>, Vec<Output>>::describe(&self.inner, graph)
+    }
 }
 
 /// Builder for creating parallel processing pipelines.
@@ -124,3 +190,87 @@ where
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::time::Duration;
+
+    use super::*;
+    use crate::workflow::LambdaStep;
+    use crate::StructuredError;
+
+    #[tokio::test]
+    async fn bounds_in_flight_items_and_keeps_input_order() {
+        let active = Arc::new(AtomicUsize::new(0));
+        let peak = Arc::new(AtomicUsize::new(0));
+        let (active_in_worker, peak_in_worker) = (Arc::clone(&active), Arc::clone(&peak));
+
+        let worker = LambdaStep(move |x: u64| {
+            let active = Arc::clone(&active_in_worker);
+            let peak = Arc::clone(&peak_in_worker);
+            async move {
+                let now = active.fetch_add(1, Ordering::SeqCst) + 1;
+                peak.fetch_max(now, Ordering::SeqCst);
+                // Later items finish first, so completion order differs from input order.
+                tokio::time::sleep(Duration::from_millis(40 - x * 5)).await;
+                active.fetch_sub(1, Ordering::SeqCst);
+                Ok(x * 10)
+            }
+        });
+        let step = ParallelMapBuilder::new(worker).concurrency(3).build();
+
+        let ctx = ExecutionContext::new();
+        let outputs = step.run((0..8).collect(), &ctx).await.unwrap();
+
+        assert_eq!(outputs, (0..8).map(|x| x * 10).collect::<Vec<_>>());
+        assert_eq!(peak.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn slow_item_does_not_hold_back_later_items() {
+        let fast_done = Arc::new(AtomicUsize::new(0));
+        let done_in_worker = Arc::clone(&fast_done);
+
+        let worker = LambdaStep(move |x: usize| {
+            let fast_done = Arc::clone(&done_in_worker);
+            async move {
+                if x == 0 {
+                    tokio::time::sleep(Duration::from_millis(80)).await;
+                    return Ok(fast_done.load(Ordering::SeqCst));
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+                fast_done.fetch_add(1, Ordering::SeqCst);
+                Ok(x)
+            }
+        });
+        let step = ParallelMapStep::new(worker, 2);
+
+        let ctx = ExecutionContext::new();
+        let outputs = step.run((0..5).collect(), &ctx).await.unwrap();
+
+        // Every fast item ran in the second slot while the first was still sleeping.
+        assert_eq!(outputs, vec![4, 1, 2, 3, 4]);
+    }
+
+    #[tokio::test]
+    async fn collect_errors_returns_every_result() {
+        let worker = LambdaStep(|x: i32| async move {
+            if x % 2 == 0 {
+                Ok(x)
+            } else {
+                Err(StructuredError::Context(format!("odd item {x}")))
+            }
+        });
+        let step = ParallelMapStep::new(worker, 2).collect_errors();
+
+        let ctx = ExecutionContext::new();
+        let results = step.run(vec![0, 1, 2, 3], &ctx).await.unwrap();
+
+        assert_eq!(results.len(), 4);
+        assert_eq!(*results[0].as_ref().unwrap(), 0);
+        assert!(results[1].is_err());
+        assert_eq!(*results[2].as_ref().unwrap(), 2);
+        assert!(results[3].is_err());
+    }
+}
@@ -11,6 +11,7 @@ use serde::{de::DeserializeOwned, Serialize};
 
 use crate::{schema::GeminiStructured, Result, StructuredClient};
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -101,10 +102,49 @@ impl<InputItem, Output> ReduceStepBuilder<InputItem, Output> {
             system_prompt: self.system_prompt,
             user_format: self
                 .user_format
-                .unwrap_or_else(|| "Aggregate the following data:\n{}".to_string()),
+                .unwrap_or_else(|| DEFAULT_USER_FORMAT.to_string()),
             _marker: PhantomData,
         }
     }
+
+    /// Build a reduce step over `(source, output)` pairs so the result can cite sources.
+    ///
+    /// Each pair is sent as `{ "source_index", "source", "output" }` and the model is
+    /// asked to attribute claims by `source_index`. Pair each input with its output
+    /// upstream, e.g. with `.map(...)` inside the worker of a `ParallelMapStep`.
+    pub fn with_provenance<Source>(self) -> ProvenanceReduceStep<Source, InputItem, Output> {
+        ProvenanceReduceStep {
+            client: self.client,
+            system_prompt: format!("{}\n\n{PROVENANCE_INSTRUCTION}", self.system_prompt),
+            user_format: self
+                .user_format
+                .unwrap_or_else(|| DEFAULT_USER_FORMAT.to_string()),
+            _marker: PhantomData,
+        }
+    }
+}
+
+const DEFAULT_USER_FORMAT: &str = "Aggregate the following data:\n{}";
+
+const PROVENANCE_INSTRUCTION: &str = "Each entry pairs an `output` with the `source` it was \
+     derived from and a `source_index`. Attribute every claim to the source_index of the \
+     entries that support it.";
+
+/// A reduce step whose items carry the input that produced them.
+///
+/// Created by [`ReduceStepBuilder::with_provenance`].
+pub struct ProvenanceReduceStep<Source, InputItem, Output> {
+    client: StructuredClient,
+    system_prompt: String,
+    user_format: String,
+    _marker: PhantomData<(Source, InputItem, Output)>,
+}
+
+#[derive(Serialize)]
+struct ProvenanceEntry<'a, Source, InputItem> {
+    source_index: usize,
+    source: &'a Source,
+    output: &'a InputItem,
 }
 
 /// A `ReduceStep` with custom formatting options.
@@ -138,6 +178,10 @@ where
 
         Ok(outcome.value)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node("Reduce", "reduce"))
+    }
 }
 
 #[async_trait]
@@ -164,4 +208,87 @@ where
 
         Ok(outcome.value)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node("Reduce", "reduce"))
+    }
+}
+
+#[async_trait]
+impl<Source, InputItem, Output> Step<Vec<(Source, InputItem)>, Output>
+    for ProvenanceReduceStep<Source, InputItem, Output>
+where
+    Source: Serialize + Send + Sync + 'static,
+    InputItem: Serialize + Send + Sync + 'static,
+    Output: GeminiStructured + Serialize + DeserializeOwned + Clone + Send + Sync + 'static,
+{
+    async fn run(&self, items: Vec<(Source, InputItem)>, ctx: &ExecutionContext) -> Result<Output> {
+        let entries: Vec<_> = items
+            .iter()
+            .enumerate()
+            .map(|(source_index, (source, output))| ProvenanceEntry {
+                source_index,
+                source,
+                output,
+            })
+            .collect();
+        let input_text = serde_json::to_string_pretty(&entries)?;
+        let user_prompt = self.user_format.replace("{}", &input_text);
+
+        let outcome = self
+            .client
+            .request::<Output>()
+            .system(&self.system_prompt)
+            .user_text(user_prompt)
+            .execute()
+            .await?;
+
+        // Record metrics from this step
+        ctx.record_outcome(&outcome);
+        ctx.record_step();
+
+        Ok(outcome.value)
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node("Reduce (with provenance)", "reduce"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StructuredClientBuilder;
+    use schemars::JsonSchema;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+    struct Answer {
+        text: String,
+        citations: Vec<usize>,
+    }
+
+    #[tokio::test]
+    async fn provenance_reduce_sends_indexed_sources() {
+        let client = StructuredClientBuilder::new("test-key")
+            .with_mock(|request| {
+                let system = request.system_instruction.unwrap_or_default();
+                assert!(system.contains("source_index"));
+                assert!(request.prompt_preview.contains("doc-b.md"));
+                assert!(request.prompt_preview.contains("source_index"));
+                Ok(r#"{"text": "B says so", "citations": [1]}"#.to_string())
+            })
+            .build()
+            .unwrap();
+        let step = ReduceStep::<String, Answer>::with_format(client, "Answer the question.")
+            .with_provenance::<String>();
+
+        let items = vec![
+            ("doc-a.md".to_string(), "A is silent".to_string()),
+            ("doc-b.md".to_string(), "B says so".to_string()),
+        ];
+        let answer = step.run(items, &ExecutionContext::new()).await.unwrap();
+
+        assert_eq!(answer.citations, vec![1]);
+    }
 }
@@ -0,0 +1,280 @@
+//! Pipelines that can be resumed from a named checkpoint.
+//!
+//! A `CheckpointStep` halts a pipeline with `StructuredError::Checkpoint`, handing the
+//! intermediate data to a human. `ResumableWorkflow` remembers which steps follow each
+//! checkpoint so the edited data can be fed straight into the rest of the pipeline.
+
+use std::collections::HashMap;
+use std::sync::Arc;
+
+use async_trait::async_trait;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::Value;
+
+use crate::{Result, StructuredError};
+
+use super::chain::ChainStep;
+use super::checkpoint::CheckpointStep;
+use super::graph::{GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::traits::LambdaStep;
+use super::Step;
+
+/// A pipeline built step by step, with named checkpoints it can be resumed from.
+///
+/// Build it with [`new`](Self::new), [`then`](Self::then) and
+/// [`checkpoint`](Self::checkpoint). Running it stops at the first checkpoint with
+/// `StructuredError::Checkpoint { step_name, data }`; after editing `data`, call
+/// [`resume`](Self::resume) with the same `step_name` to deserialize it into the
+/// checkpoint's type and run the downstream steps.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// use gemini_structured_output::workflow::{ResumableWorkflow, ExecutionContext};
+///
+/// let workflow = ResumableWorkflow::new(drafter)
+///     .checkpoint("ReviewDraft")
+///     .then(publisher);
+///
+/// match workflow.run(brief, &ExecutionContext::new()).await {
+///     Err(StructuredError::Checkpoint { step_name, mut data }) => {
+///         data["title"] = json!("Edited by a human");
+///         let published = workflow.resume(&step_name, data).await?;
+///     }
+///     other => { /* completed or failed */ }
+/// }
+/// ```
+pub struct ResumableWorkflow<Input, Output> {
+    pipeline: SharedStep<Input, Output>,
+    resumers: HashMap<String, SharedStep<Value, Output>>,
+}
+
+impl<Input, Output> ResumableWorkflow<Input, Output>
+where
+    Input: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    /// Start a resumable pipeline with `first`.
+    pub fn new(first: impl Step<Input, Output> + 'static) -> Self {
+        Self {
+            pipeline: SharedStep(Arc::new(first)),
+            resumers: HashMap::new(),
+        }
+    }
+
+    /// Append `next` to the pipeline and to every checkpoint's downstream steps.
+    pub fn then<Next>(
+        self,
+        next: impl Step<Output, Next> + 'static,
+    ) -> ResumableWorkflow<Input, Next>
+    where
+        Next: Send + Sync + 'static,
+    {
+        let next = SharedStep(Arc::new(next));
+        let resumers = self
+            .resumers
+            .into_iter()
+            .map(|(name, resumer)| {
+                let chained = ChainStep::new(resumer, next.clone());
+                (name, SharedStep(Arc::new(chained)))
+            })
+            .collect();
+        ResumableWorkflow {
+            pipeline: SharedStep(Arc::new(ChainStep::new(self.pipeline, next))),
+            resumers,
+        }
+    }
+
+    /// Halt here with a [`CheckpointStep`] named `name`.
+    ///
+    /// Later steps become resumable from this point via [`resume`](Self::resume).
+    /// Resuming from an earlier checkpoint halts again here.
+    ///
+    /// # Panics
+    ///
+    /// Panics if a checkpoint named `name` was already added.
+    pub fn checkpoint(self, name: impl Into<String>) -> Self
+    where
+        Output: Serialize + DeserializeOwned,
+    {
+        let name = name.into();
+        assert!(
+            !self.resumers.contains_key(&name),
+            "duplicate checkpoint name '{name}'"
+        );
+
+        let mut resumers: HashMap<_, _> = self
+            .resumers
+            .into_iter()
+            .map(|(earlier, resumer)| {
+                let halted = ChainStep::new(resumer, CheckpointStep::<Output>::new(name.clone()));
+                (earlier, SharedStep(Arc::new(halted)))
+            })
+            .collect();
+        let decode = LambdaStep(|data: Value| async move {
+            serde_json::from_value::<Output>(data).map_err(StructuredError::Json)
+        });
+        resumers.insert(name.clone(), SharedStep(Arc::new(decode)));
+
+        let checkpoint = CheckpointStep::<Output>::new(name);
+        ResumableWorkflow {
+            pipeline: SharedStep(Arc::new(ChainStep::new(self.pipeline, checkpoint))),
+            resumers,
+        }
+    }
+
+    /// Names of the checkpoints this workflow can resume from.
+    pub fn checkpoints(&self) -> Vec<&str> {
+        self.resumers.keys().map(String::as_str).collect()
+    }
+
+    /// Resume after the checkpoint `step_name` with (possibly edited) `data`.
+    ///
+    /// Runs on a fresh [`ExecutionContext`]; use
+    /// [`resume_with_context`](Self::resume_with_context) to collect metrics.
+    pub async fn resume(&self, step_name: &str, data: Value) -> Result<Output> {
+        self.resume_with_context(step_name, data, &ExecutionContext::new())
+            .await
+    }
+
+    /// Resume after the checkpoint `step_name`, recording into `ctx`.
+    pub async fn resume_with_context(
+        &self,
+        step_name: &str,
+        data: Value,
+        ctx: &ExecutionContext,
+    ) -> Result<Output> {
+        let resumer = self
+            .resumers
+            .get(step_name)
+            .ok_or_else(|| StructuredError::Context(format!("Unknown checkpoint '{step_name}'")))?;
+        resumer.run(data, ctx).await
+    }
+}
+
+#[async_trait]
+impl<Input, Output> Step<Input, Output> for ResumableWorkflow<Input, Output>
+where
+    Input: Send + Sync + 'static,
+    Output: Send + Sync + 'static,
+{
+    async fn run(&self, input: Input, ctx: &ExecutionContext) -> Result<Output> {
+        self.pipeline.run(input, ctx).await
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        self.pipeline.describe(graph)
+    }
+}
+
+/// A step shared between the main pipeline and checkpoint resumers.
+struct SharedStep<I, O>(Arc<dyn Step<I, O>>);
+
+impl<I, O> Clone for SharedStep<I, O> {
+    fn clone(&self) -> Self {
+        Self(Arc::clone(&self.0))
+    }
+}
+
+#[async_trait]
+impl<I, O> Step<I, O> for SharedStep<I, O>
+where
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        self.0.run(input, ctx).await
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        self.0.describe(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, Clone, Serialize, Deserialize)]
+    struct Draft {
+        title: String,
+        words: usize,
+    }
+
+    fn workflow() -> ResumableWorkflow<String, String> {
+        ResumableWorkflow::new(LambdaStep(|topic: String| async move {
+            Ok(Draft {
+                title: format!("All about {topic}"),
+                words: 120,
+            })
+        }))
+        .checkpoint("ReviewDraft")
+        .then(LambdaStep(|draft: Draft| async move {
+            Ok(format!("{} ({} words)", draft.title, draft.words))
+        }))
+    }
+
+    #[tokio::test]
+    async fn resumes_downstream_steps_with_edited_data() {
+        let workflow = workflow();
+        let ctx = ExecutionContext::new();
+
+        let (step_name, mut data) = match workflow.run("otters".to_string(), &ctx).await {
+            Err(StructuredError::Checkpoint { step_name, data }) => (step_name, data),
+            other => panic!("expected checkpoint, got {other:?}"),
+        };
+        assert_eq!(step_name, "ReviewDraft");
+        assert_eq!(data["title"], "All about otters");
+
+        data["title"] = Value::from("Otters, edited");
+        let output = workflow.resume(&step_name, data).await.unwrap();
+        assert_eq!(output, "Otters, edited (120 words)");
+    }
+
+    #[tokio::test]
+    async fn rejects_unknown_checkpoint_and_bad_data() {
+        let workflow = workflow();
+
+        let err = workflow.resume("Nope", Value::Null).await.unwrap_err();
+        assert!(matches!(err, StructuredError::Context(_)));
+
+        let err = workflow
+            .resume("ReviewDraft", serde_json::json!({ "title": 3 }))
+            .await
+            .unwrap_err();
+        assert!(matches!(err, StructuredError::Json(_)));
+    }
+
+    #[tokio::test]
+    async fn resuming_from_first_checkpoint_halts_at_the_second() {
+        let workflow = workflow().checkpoint("ApproveSummary").then(LambdaStep(
+            |summary: String| async move { Ok(summary.len()) },
+        ));
+
+        let err = workflow
+            .resume(
+                "ReviewDraft",
+                serde_json::json!({ "title": "Otters", "words": 5 }),
+            )
+            .await
+            .unwrap_err();
+        let (step_name, data) = match err {
+            StructuredError::Checkpoint { step_name, data } => (step_name, data),
+            other => panic!("expected checkpoint, got {other:?}"),
+        };
+        assert_eq!(step_name, "ApproveSummary");
+        assert_eq!(data, "Otters (5 words)");
+
+        let output = workflow.resume(&step_name, data).await.unwrap();
+        assert_eq!(output, "Otters (5 words)".len());
+    }
+
+    #[test]
+    #[should_panic(expected = "duplicate checkpoint name 'ReviewDraft'")]
+    fn rejects_duplicate_checkpoint_names() {
+        let _ = workflow().checkpoint("ReviewDraft");
+    }
+}
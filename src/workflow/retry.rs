@@ -0,0 +1,204 @@
+//! Retries for failing steps.
+//!
+//! The `RetryStep` combinator re-runs its inner step when it fails with a transient
+//! error (rate limits, unavailability), backing off exponentially between attempts.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::Result;
+
+use super::events::WorkflowEvent;
+use super::graph::{short_type_name, GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A step that re-runs its inner step on retryable errors.
+///
+/// Every failed attempt is recorded on the context. Attempts that will be retried emit
+/// a `Retry` trace event with the running retry count; the final failure emits an
+/// `Error` event and is returned.
+///
+/// Created by calling `.retry()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let pipeline = summarizer
+///     .retry(3, Duration::from_secs(1))
+///     .then(writer);
+/// ```
+pub struct RetryStep<S> {
+    inner: S,
+    max_attempts: usize,
+    backoff: Duration,
+}
+
+impl<S> RetryStep<S> {
+    /// Create a step running `inner` up to `max_attempts` times (at least once).
+    pub fn new(inner: S, max_attempts: usize, backoff: Duration) -> Self {
+        Self {
+            inner,
+            max_attempts: max_attempts.max(1),
+            backoff,
+        }
+    }
+
+    /// Backoff before retry number `retries` (1-based): `backoff * 2^(retries - 1)`
+    /// plus up to 50% random jitter.
+    fn delay_for(&self, retries: usize) -> Duration {
+        let exponent = retries.saturating_sub(1).min(16) as u32;
+        let base = self.backoff.saturating_mul(1 << exponent);
+        let jitter_ms = (base.as_millis() / 2) as u64;
+        if jitter_ms == 0 {
+            return base;
+        }
+        let random = (uuid::Uuid::new_v4().as_u128() as u64) % (jitter_ms + 1);
+        base + Duration::from_millis(random)
+    }
+}
+
+#[async_trait]
+impl<S, I, O> Step<I, O> for RetryStep<S>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        let step_name = short_type_name(std::any::type_name::<S>());
+        let mut retries = 0;
+        loop {
+            let err = match self.inner.run(input.clone(), ctx).await {
+                Ok(output) => return Ok(output),
+                Err(err) => err,
+            };
+            let attempt = retries + 1;
+            ctx.record_failure(format!(
+                "{step_name}: attempt {attempt}/{} failed: {err}",
+                self.max_attempts
+            ));
+
+            if attempt >= self.max_attempts || !err.is_retryable() {
+                ctx.emit(WorkflowEvent::Error {
+                    step_name,
+                    message: format!("Giving up after {retries} retries: {err}"),
+                });
+                return Err(err);
+            }
+
+            retries += 1;
+            let delay = self.delay_for(retries);
+            ctx.emit(WorkflowEvent::Retry {
+                step_name: step_name.clone(),
+                retries,
+                delay_ms: delay.as_millis(),
+                message: err.to_string(),
+            });
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        let label = format!("retry x{}", self.max_attempts);
+        let retry = graph.add_node(label, "retry");
+        graph.add_edge(inner.exit, retry, None);
+        GraphSpan {
+            entry: inner.entry,
+            exit: retry,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+
+    use super::*;
+    use crate::workflow::LambdaStep;
+    use crate::StructuredError;
+
+    fn flaky(
+        failures: usize,
+        error: fn() -> StructuredError,
+    ) -> (impl Step<i32, i32>, Arc<AtomicUsize>) {
+        let calls = Arc::new(AtomicUsize::new(0));
+        let counter = Arc::clone(&calls);
+        let step = LambdaStep(move |x: i32| {
+            let call = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                if call < failures {
+                    Err(error())
+                } else {
+                    Ok(x + 1)
+                }
+            }
+        });
+        (step, calls)
+    }
+
+    fn unavailable() -> StructuredError {
+        StructuredError::ServiceUnavailable {
+            message: "overloaded".into(),
+            attempts: 1,
+        }
+    }
+
+    #[tokio::test]
+    async fn retries_transient_errors_until_success() {
+        let (step, calls) = flaky(2, unavailable);
+        let step = step.retry(3, Duration::from_millis(1));
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(1, &ctx).await.unwrap(), 2);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+        assert_eq!(ctx.snapshot().failures.len(), 2);
+
+        let retries: Vec<usize> = ctx
+            .trace_snapshot()
+            .into_iter()
+            .filter_map(|entry| match entry.event {
+                WorkflowEvent::Retry { retries, .. } => Some(retries),
+                _ => None,
+            })
+            .collect();
+        assert_eq!(retries, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn stops_on_non_retryable_error() {
+        let (step, calls) = flaky(5, || StructuredError::Context("bad input".into()));
+        let step = step.retry(3, Duration::from_millis(1));
+
+        let ctx = ExecutionContext::new();
+        assert!(step.run(1, &ctx).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        assert!(matches!(
+            ctx.trace_snapshot()[0].event,
+            WorkflowEvent::Error { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn gives_up_after_max_attempts() {
+        let (step, calls) = flaky(5, unavailable);
+        let step = step.retry(2, Duration::from_millis(1));
+
+        let ctx = ExecutionContext::new();
+        assert!(step.run(1, &ctx).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 2);
+        assert_eq!(ctx.snapshot().failures.len(), 2);
+    }
+
+    #[test]
+    fn backoff_doubles_with_bounded_jitter() {
+        let step = RetryStep::new((), 4, Duration::from_millis(100));
+        for (retries, base) in [(1, 100), (2, 200), (3, 400)] {
+            let delay = step.delay_for(retries).as_millis();
+            assert!((base..=base + base / 2).contains(&delay), "{delay}");
+        }
+    }
+}
@@ -12,6 +12,7 @@ use crate::{
     StructuredClient,
 };
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -55,4 +56,8 @@ where
 
         Ok(outcome.value)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        GraphSpan::single(graph.add_node("Review", "review"))
+    }
 }
@@ -11,6 +11,7 @@ use serde::Serialize;
 
 use crate::{GeminiStructured, Result, StructuredClient, StructuredValidator};
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -81,4 +82,13 @@ where
         let next_step = (self.dispatcher)(outcome.value);
         next_step.run(input, ctx).await
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        // Branches are produced by the dispatcher at runtime and cannot be enumerated.
+        let label = format!(
+            "Router ({})",
+            super::graph::short_type_name(std::any::type_name::<Decision>())
+        );
+        GraphSpan::single(graph.add_node(label, "router"))
+    }
 }
@@ -1,6 +1,7 @@
 use std::{future::Future, marker::PhantomData, sync::Arc};
 
 use async_trait::async_trait;
+use gemini_rust::Model;
 
 use crate::{workflow::Step, Result};
 
@@ -47,6 +48,8 @@ where
 /// Adapter that allows a regular [`Step`] to participate in a stateful workflow.
 pub struct StepAdapter<I, O, S, G, Set> {
     inner: Arc<dyn Step<I, O>>,
+    label: String,
+    model: Option<Model>,
     getter: G,
     setter: Set,
     _marker: PhantomData<S>,
@@ -60,14 +63,28 @@ where
     G: Fn(&S) -> I + Send + Sync + 'static,
     Set: Fn(&mut S, O) + Send + Sync + 'static,
 {
-    pub fn new(step: impl Step<I, O> + 'static, getter: G, setter: Set) -> Self {
+    pub fn new<St>(step: St, getter: G, setter: Set) -> Self
+    where
+        St: Step<I, O> + 'static,
+    {
         Self {
             inner: Arc::new(step),
+            label: std::any::type_name::<St>().to_string(),
+            model: None,
             getter,
             setter,
             _marker: PhantomData,
         }
     }
+
+    /// Pin the wrapped step to a specific model.
+    ///
+    /// The step runs under [`ExecutionContext::with_model`], so agents use this model
+    /// instead of the one their client was built with.
+    pub fn with_model(mut self, model: Model) -> Self {
+        self.model = Some(model);
+        self
+    }
 }
 
 #[async_trait]
@@ -81,7 +98,14 @@ where
 {
     async fn run(&self, state: &mut S, ctx: &ExecutionContext) -> Result<()> {
         let input = (self.getter)(state);
-        let output = self.inner.run(input, ctx).await?;
+        let output = match &self.model {
+            Some(model) => {
+                ctx.record_step_model(self.label.clone(), model.as_str());
+                let scoped = ctx.with_model(model.clone());
+                self.inner.run(input, &scoped).await?
+            }
+            None => self.inner.run(input, ctx).await?,
+        };
         (self.setter)(state, output);
         Ok(())
     }
@@ -144,6 +168,62 @@ where
         self.step(StepAdapter::new(step, getter, setter))
     }
 
+    /// Add a regular [`Step`] that runs against a specific model.
+    ///
+    /// Useful for assigning cheaper models to extraction stages and stronger ones to
+    /// synthesis. The assignment is recorded in [`WorkflowMetrics::step_models`].
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let workflow = StateWorkflow::new(state)
+    ///     .with_adapter_using(extractor, Model::Gemini25Flash, |s| s.text.clone(), |s, o| s.facts = Some(o))
+    ///     .with_adapter_using(synthesizer, Model::Custom("models/gemini-2.5-pro".into()), |s| s.facts.clone(), |s, o| s.report = Some(o));
+    /// ```
+    pub fn with_adapter_using<I, O, G, Set>(
+        self,
+        step: impl Step<I, O> + 'static,
+        model: Model,
+        getter: G,
+        setter: Set,
+    ) -> Self
+    where
+        I: Send + Sync + 'static,
+        O: Send + Sync + 'static,
+        G: Fn(&S) -> I + Send + Sync + 'static,
+        Set: Fn(&mut S, O) + Send + Sync + 'static,
+    {
+        self.step(StepAdapter::new(step, getter, setter).with_model(model))
+    }
+
+    /// Turn the final state into a domain output once all steps have run.
+    ///
+    /// The returned workflow exposes [`run_finalized`](FinalizedStateWorkflow::run_finalized),
+    /// so callers get a clean output type instead of unpacking the state's `Option` fields.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let (report, metrics) = StateWorkflow::new(state)
+    ///     .with_adapter(extractor, |s| s.text.clone(), |s, o| s.facts = Some(o))
+    ///     .with_finalizer(|s| {
+    ///         s.facts
+    ///             .map(Report::from)
+    ///             .ok_or_else(|| StructuredError::Context("facts missing".into()))
+    ///     })
+    ///     .run_finalized()
+    ///     .await?;
+    /// ```
+    pub fn with_finalizer<O, F>(self, finalizer: F) -> FinalizedStateWorkflow<S, O>
+    where
+        F: Fn(S) -> Result<O> + Send + Sync + 'static,
+    {
+        FinalizedStateWorkflow {
+            workflow: self,
+            finalizer: Box::new(finalizer),
+        }
+    }
+
     /// Run the workflow, returning the final state and metrics.
     pub async fn run(self) -> Result<(S, WorkflowMetrics)> {
         let ctx = ExecutionContext::new();
@@ -183,3 +263,147 @@ where
         Ok((state, metrics))
     }
 }
+
+/// A [`StateWorkflow`] whose final state is post-processed into an output type.
+///
+/// Created by [`StateWorkflow::with_finalizer`].
+pub struct FinalizedStateWorkflow<S, O> {
+    workflow: StateWorkflow<S>,
+    finalizer: Box<dyn Fn(S) -> Result<O> + Send + Sync>,
+}
+
+impl<S, O> FinalizedStateWorkflow<S, O>
+where
+    S: Send + Sync + 'static,
+{
+    /// Run the workflow and finalize its state, returning the output and metrics.
+    pub async fn run_finalized(self) -> Result<(O, WorkflowMetrics)> {
+        let ctx = ExecutionContext::new();
+        self.run_finalized_with_context(ctx).await
+    }
+
+    /// Run with a provided execution context, then finalize the state.
+    ///
+    /// A finalizer error is recorded as a workflow failure.
+    pub async fn run_finalized_with_context(
+        self,
+        ctx: ExecutionContext,
+    ) -> Result<(O, WorkflowMetrics)> {
+        let (state, _) = self.workflow.run_with_context(ctx.clone()).await?;
+        match (self.finalizer)(state) {
+            Ok(output) => Ok((output, ctx.snapshot())),
+            Err(err) => {
+                ctx.record_failure(err.to_string());
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::StructuredError;
+
+    #[derive(Default)]
+    struct Counter {
+        total: Option<i32>,
+    }
+
+    #[tokio::test]
+    async fn finalizer_maps_state_to_output() {
+        let (output, metrics) = StateWorkflow::new(Counter::default())
+            .step_fn(|state: &mut Counter, _ctx: &ExecutionContext| {
+                state.total = Some(41);
+                async { Ok(()) }
+            })
+            .with_finalizer(|state: Counter| {
+                state
+                    .total
+                    .map(|total| format!("total={}", total + 1))
+                    .ok_or_else(|| StructuredError::Context("total missing".into()))
+            })
+            .run_finalized()
+            .await
+            .unwrap();
+
+        assert_eq!(output, "total=42");
+        assert_eq!(metrics.steps_completed, 1);
+    }
+
+    #[tokio::test]
+    async fn finalizer_error_is_recorded() {
+        let ctx = ExecutionContext::new();
+        let result = StateWorkflow::new(Counter::default())
+            .with_finalizer(|state: Counter| {
+                state
+                    .total
+                    .ok_or_else(|| StructuredError::Context("total missing".into()))
+            })
+            .run_finalized_with_context(ctx.clone())
+            .await;
+
+        assert!(result.is_err());
+        assert_eq!(
+            ctx.snapshot().failures,
+            vec!["Context error: total missing"]
+        );
+    }
+
+    /// Asks the model for a string, honoring the context's model override the way
+    /// `#[gemini_agent]` steps do.
+    struct ModelEcho {
+        client: crate::StructuredClient,
+    }
+
+    #[async_trait]
+    impl Step<String, String> for ModelEcho {
+        async fn run(&self, input: String, ctx: &ExecutionContext) -> Result<String> {
+            let mut request = self.client.request::<String>().user_text(input);
+            if let Some(model) = ctx.model_override() {
+                request = request.with_model(model.clone());
+            }
+            Ok(request.execute().await?.value)
+        }
+    }
+
+    #[derive(Default)]
+    struct Stages {
+        cheap: Option<String>,
+        default: Option<String>,
+    }
+
+    #[tokio::test]
+    async fn adapter_model_reaches_the_request_and_metrics() {
+        let client = crate::StructuredClientBuilder::new("test-key")
+            .with_model(Model::Gemini25Pro)
+            .with_mock(|req| Ok(serde_json::to_string(&req.model).unwrap()))
+            .build()
+            .unwrap();
+        let cheap = Model::Custom("models/gemini-2.5-flash-lite".to_string());
+
+        let (state, metrics) = StateWorkflow::new(Stages::default())
+            .with_adapter_using(
+                ModelEcho {
+                    client: client.clone(),
+                },
+                cheap.clone(),
+                |_| "extract".to_string(),
+                |s, o| s.cheap = Some(o),
+            )
+            .with_adapter(
+                ModelEcho { client },
+                |_| "synthesize".to_string(),
+                |s, o| s.default = Some(o),
+            )
+            .run()
+            .await
+            .unwrap();
+
+        assert_eq!(state.cheap.as_deref(), Some(cheap.as_str()));
+        assert_eq!(state.default.as_deref(), Some(Model::Gemini25Pro.as_str()));
+        assert_eq!(metrics.step_models.len(), 1);
+        assert!(metrics.step_models[0].0.ends_with("ModelEcho"));
+        assert_eq!(metrics.step_models[0].1, cheap.as_str());
+    }
+}
@@ -9,6 +9,7 @@ use async_trait::async_trait;
 
 use crate::Result;
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -63,6 +64,16 @@ where
         (self.func)(&output, ctx);
         Ok(output)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        let tap = graph.add_node("tap", "tap");
+        graph.add_edge(inner.exit, tap, None);
+        GraphSpan {
+            entry: inner.entry,
+            exit: tap,
+        }
+    }
 }
 
 #[cfg(test)]
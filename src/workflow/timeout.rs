@@ -0,0 +1,104 @@
+//! Deadlines for slow steps.
+//!
+//! The `TimeoutStep` combinator bounds how long its inner step may run, so a single
+//! stalled LLM call cannot hold up a chain or the join of a parallel map.
+
+use std::time::Duration;
+
+use async_trait::async_trait;
+
+use crate::{Result, StructuredError};
+
+use super::events::WorkflowEvent;
+use super::graph::{short_type_name, GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A step that fails with [`StructuredError::Timeout`] when its inner step overruns.
+///
+/// The inner future is dropped at the deadline. The timeout is recorded on the context
+/// as a failure and an `Error` trace event.
+///
+/// Created by calling `.with_timeout()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let pipeline = extractor
+///     .with_timeout(Duration::from_secs(20))
+///     .then(writer);
+/// ```
+pub struct TimeoutStep<S> {
+    inner: S,
+    limit: Duration,
+}
+
+impl<S> TimeoutStep<S> {
+    /// Create a step that aborts `inner` after `limit`.
+    pub fn new(inner: S, limit: Duration) -> Self {
+        Self { inner, limit }
+    }
+}
+
+#[async_trait]
+impl<S, I, O> Step<I, O> for TimeoutStep<S>
+where
+    I: Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        match tokio::time::timeout(self.limit, self.inner.run(input, ctx)).await {
+            Ok(result) => result,
+            Err(_) => {
+                let err = StructuredError::Timeout {
+                    elapsed: self.limit,
+                };
+                let step_name = short_type_name(std::any::type_name::<S>());
+                let message = err.to_string();
+                ctx.record_failure(format!("{step_name}: {message}"));
+                ctx.emit(WorkflowEvent::Error { step_name, message });
+                Err(err)
+            }
+        }
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        self.inner.describe(graph)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+
+    #[tokio::test]
+    async fn aborts_step_that_sleeps_past_deadline() {
+        let step = LambdaStep(|x: i32| async move {
+            tokio::time::sleep(Duration::from_secs(5)).await;
+            Ok(x)
+        })
+        .with_timeout(Duration::from_millis(20));
+
+        let ctx = ExecutionContext::new();
+        let err = step.run(1, &ctx).await.unwrap_err();
+
+        assert!(matches!(err, StructuredError::Timeout { .. }));
+        assert_eq!(ctx.snapshot().failures.len(), 1);
+        assert!(matches!(
+            ctx.trace_snapshot()[0].event,
+            WorkflowEvent::Error { .. }
+        ));
+    }
+
+    #[tokio::test]
+    async fn passes_through_fast_steps() {
+        let step =
+            LambdaStep(|x: i32| async move { Ok(x + 1) }).with_timeout(Duration::from_secs(1));
+
+        let ctx = ExecutionContext::new();
+        assert_eq!(step.run(1, &ctx).await.unwrap(), 2);
+        assert!(ctx.trace_snapshot().is_empty());
+    }
+}
@@ -7,6 +7,7 @@ use async_trait::async_trait;
 use crate::Result;
 
 use super::chain::{ChainStep, ChainTupleStep};
+use super::graph::{short_type_name, GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 
 /// A unit of asynchronous work that transforms an input into an output.
@@ -29,6 +30,29 @@ pub trait Step<Input, Output>: Send + Sync {
     /// Execute this step with the given input and execution context.
     async fn run(&self, input: Input, ctx: &ExecutionContext) -> Result<Output>;
 
+    /// Add this step's structure to `graph` and return its entry/exit nodes.
+    ///
+    /// Leaf steps get a single node named after their type. Combinators override this
+    /// to describe their children.
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let name = short_type_name(std::any::type_name::<Self>());
+        GraphSpan::single(graph.add_node(name, "step"))
+    }
+
+    /// Build a [`WorkflowGraph`] of this step for export to Mermaid or DOT.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = extractor.named("Extract").then(reducer.named("Reduce"));
+    /// std::fs::write("pipeline.mmd", pipeline.describe_graph().to_mermaid())?;
+    /// ```
+    fn describe_graph(&self) -> WorkflowGraph {
+        let mut graph = WorkflowGraph::new();
+        self.describe(&mut graph);
+        graph
+    }
+
     /// Chain this step with another step, creating a pipeline.
     ///
     /// The output of this step becomes the input of the next step.
@@ -125,6 +149,217 @@ pub trait Step<Input, Output>: Send + Sync {
         super::tap::TapStep::new(self, func)
     }
 
+    /// Build this step's input from the incoming value and the artifacts emitted so far.
+    ///
+    /// Artifacts recorded by earlier steps (e.g. via `.tap()` and
+    /// [`ExecutionContext::emit_artifact`]) are passed to `builder` alongside the input.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = extractor
+    ///     .tap(|facts, ctx| ctx.emit_artifact("Extract", "facts", facts))
+    ///     .then(classifier)
+    ///     .then(writer.with_artifacts(|label, artifacts| WriterInput {
+    ///         label,
+    ///         facts: artifacts.get("facts").unwrap_or_default(),
+    ///     }));
+    /// ```
+    fn with_artifacts<Prior, F>(
+        self,
+        builder: F,
+    ) -> super::artifacts::WithArtifactsStep<Self, F, Prior, Input>
+    where
+        Self: Sized + 'static,
+        Prior: Send + Sync + 'static,
+        Input: Send + Sync + 'static,
+        Output: Send + Sync + 'static,
+        F: Fn(Prior, &super::artifacts::ArtifactMap) -> Input + Send + Sync + 'static,
+    {
+        super::artifacts::WithArtifactsStep::new(self, builder)
+    }
+
+    /// Re-run this step with feedback until its output passes `predicate`.
+    ///
+    /// `predicate` returns `Some(error)` to reject an output. `feedback` builds the next
+    /// input from the previous input, the rejected output and the error. After
+    /// `max_retries` re-runs the step fails with a validation error.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let checked = drafter.validate_output(
+    ///     |draft: &Draft| draft.body.is_empty().then(|| "Body must not be empty".into()),
+    ///     2,
+    ///     |prompt: String, _draft, error| format!("{prompt}\n\nPrevious attempt rejected: {error}"),
+    /// );
+    /// ```
+    fn validate_output<P, F>(
+        self,
+        predicate: P,
+        max_retries: usize,
+        feedback: F,
+    ) -> super::validated::ValidatedStep<Self, P, F, Input, Output>
+    where
+        Self: Sized + 'static,
+        Input: Clone + Send + Sync + 'static,
+        Output: Send + Sync + 'static,
+        P: Fn(&Output) -> Option<String> + Send + Sync + 'static,
+        F: Fn(Input, &Output, &str) -> Input + Send + Sync + 'static,
+    {
+        super::validated::ValidatedStep::new(self, predicate, max_retries, feedback)
+    }
+
+    /// Return `default` instead of failing when this step errors.
+    ///
+    /// The error is recorded on the context as a failure and an `Error` trace event, so
+    /// optional enrichment steps can fail without aborting the pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = extractor
+    ///     .then(keyword_tagger.or_default(Vec::new()))
+    ///     .then(writer);
+    /// ```
+    fn or_default(self, default: Output) -> super::fallback::FallbackStep<Self, Output>
+    where
+        Self: Sized + 'static,
+        Input: Send + Sync + 'static,
+        Output: Clone + Send + Sync + 'static,
+    {
+        super::fallback::FallbackStep::new(self, default)
+    }
+
+    /// Produce the output with `agent` when this step fails.
+    ///
+    /// `agent` receives a serializable [`StepFailure`](super::StepFailure) describing
+    /// the error, which is also recorded as a failure and stored as the
+    /// `{step}.recovered_error` artifact, keyed by this step's short type name.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = extractor.recover_with_agent(FailureExplainer::new(client));
+    /// ```
+    fn recover_with_agent<R>(self, agent: R) -> super::fallback::RecoverStep<Self, R>
+    where
+        Self: Sized + 'static,
+        R: Step<super::StepFailure, Output> + 'static,
+        Input: Send + Sync + 'static,
+        Output: Send + Sync + 'static,
+    {
+        super::fallback::RecoverStep::new(self, agent)
+    }
+
+    /// Run `alternate` on the same input when this step fails.
+    ///
+    /// The primary failure is recorded on the context, emitted as an `Error` trace
+    /// event and stored as the `{step}.fallback_fired` artifact (keyed by this step's
+    /// short type name), so traces show that the fallback fired. Like
+    /// [`FallbackStrategy`](crate::FallbackStrategy) at the model level, but for
+    /// arbitrary steps.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let extractor = cheap_extractor.or_else(expensive_extractor);
+    /// ```
+    fn or_else<A>(self, alternate: A) -> super::fallback::AlternateStep<Self, A>
+    where
+        Self: Sized + 'static,
+        A: Step<Input, Output> + 'static,
+        Input: Clone + Send + Sync + 'static,
+        Output: Send + Sync + 'static,
+    {
+        super::fallback::AlternateStep::new(self, alternate)
+    }
+
+    /// Run this step when `predicate` holds for the input, and `otherwise` when it does not.
+    ///
+    /// Shorthand for [`BranchStep::new(predicate, self, otherwise)`](super::BranchStep::new):
+    /// deterministic routing without the model call a `RouterStep` makes.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let summarize =
+    ///     chunked_summarizer.branch(|doc: &Document| doc.text.len() > 20_000, summarizer);
+    /// ```
+    fn branch<P, S>(self, predicate: P, otherwise: S) -> super::branch::BranchStep<Input, Output>
+    where
+        Self: Sized + 'static,
+        P: Fn(&Input) -> bool + Send + Sync + 'static,
+        S: Step<Input, Output> + 'static,
+    {
+        super::branch::BranchStep::new(predicate, self, otherwise)
+    }
+
+    /// Re-run this step when it fails with a retryable error.
+    ///
+    /// The step runs at most `max_attempts` times. Between attempts it sleeps for
+    /// `backoff` doubled per retry, plus up to 50% jitter. Errors for which
+    /// [`StructuredError::is_retryable`](crate::StructuredError::is_retryable) is false
+    /// are returned immediately. Each failed attempt is recorded on the context and
+    /// emitted as a `Retry` trace event carrying the retry count.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = extractor
+    ///     .retry(3, Duration::from_millis(500))
+    ///     .then(writer);
+    /// ```
+    fn retry(
+        self,
+        max_attempts: usize,
+        backoff: std::time::Duration,
+    ) -> super::retry::RetryStep<Self>
+    where
+        Self: Sized,
+    {
+        super::retry::RetryStep::new(self, max_attempts, backoff)
+    }
+
+    /// Abort this step if it does not finish within `limit`.
+    ///
+    /// On expiry the inner future is dropped, an `Error` trace event and failure are
+    /// recorded on the context, and [`StructuredError::Timeout`](crate::StructuredError::Timeout)
+    /// is returned. Useful inside parallel maps so one slow branch cannot stall the join.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = ParallelMapStep::new(summarizer.with_timeout(Duration::from_secs(30)), 4);
+    /// ```
+    fn with_timeout(self, limit: std::time::Duration) -> super::timeout::TimeoutStep<Self>
+    where
+        Self: Sized,
+    {
+        super::timeout::TimeoutStep::new(self, limit)
+    }
+
+    /// Count this step in `steps_completed` each time it succeeds.
+    ///
+    /// Steps generated by `#[gemini_agent]`, `ReduceStep`, `RouterStep`, `ReviewStep` and
+    /// `StateWorkflow` steps record themselves. `LambdaStep`, `.map()`, `.tap()`,
+    /// `.then()` and `.named()` do not, so wrap non-agent transforms with `.metered()` to
+    /// keep the step count consistent across a pipeline.
+    ///
+    /// # Example
+    ///
+    /// ```rust,ignore
+    /// let pipeline = extractor
+    ///     .then(LambdaStep(|facts: Facts| async move { Ok(dedupe(facts)) }).metered())
+    ///     .then(writer);
+    /// ```
+    fn metered(self) -> super::metered::MeteredStep<Self>
+    where
+        Self: Sized,
+    {
+        super::metered::MeteredStep::new(self)
+    }
+
     /// Wrap this step with automatic start/end event instrumentation.
     ///
     /// When the step runs, it will automatically emit:
@@ -133,6 +368,7 @@ pub trait Step<Input, Output>: Send + Sync {
     /// - `Error` event on failure
     ///
     /// This provides comprehensive workflow tracing without manual instrumentation.
+    /// It does not count towards `steps_completed`; combine with `.metered()` for that.
     ///
     /// # Example
     ///
@@ -217,6 +453,16 @@ where
         let output = self.inner.run(input, ctx).await?;
         Ok((self.func)(output))
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        let map = graph.add_node("map", "map");
+        graph.add_edge(inner.exit, map, None);
+        GraphSpan {
+            entry: inner.entry,
+            exit: map,
+        }
+    }
 }
 
 /// Extension trait for boxed steps to enable chaining.
@@ -267,4 +513,8 @@ where
     async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
         self.0.run(input, ctx).await
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        self.0.describe(graph)
+    }
 }
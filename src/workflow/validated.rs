@@ -0,0 +1,151 @@
+//! Output validation with feedback-driven retries for arbitrary steps.
+//!
+//! The `ValidatedStep` combinator checks a step's output against a predicate and,
+//! when it is rejected, re-runs the step with an input amended by a feedback function.
+
+use async_trait::async_trait;
+
+use crate::{Result, StructuredError};
+
+use super::events::WorkflowEvent;
+use super::graph::{short_type_name, GraphSpan, WorkflowGraph};
+use super::metrics::ExecutionContext;
+use super::Step;
+
+/// A step that re-runs its inner step until the output passes a predicate.
+///
+/// The predicate returns `Some(error)` to reject an output. The feedback function then
+/// builds the next input from the previous input, the rejected output and the error,
+/// typically by appending the error to a prompt. Each rejection is recorded on the
+/// context as a failure and an `Error` trace event.
+///
+/// Created by calling `.validate_output()` on any `Step`.
+///
+/// # Example
+///
+/// ```rust,ignore
+/// let checked = summarizer.validate_output(
+///     |summary: &Summary| (summary.bullets.len() > 5).then(|| "Use at most 5 bullets".into()),
+///     2,
+///     |article: Article, _rejected, error| article.with_note(error),
+/// );
+/// ```
+pub struct ValidatedStep<S, P, F, I, O> {
+    inner: S,
+    predicate: P,
+    feedback: F,
+    max_retries: usize,
+    _marker: std::marker::PhantomData<(I, O)>,
+}
+
+impl<S, P, F, I, O> ValidatedStep<S, P, F, I, O> {
+    /// Create a validated step allowing up to `max_retries` re-runs after the first attempt.
+    pub fn new(inner: S, predicate: P, max_retries: usize, feedback: F) -> Self {
+        Self {
+            inner,
+            predicate,
+            feedback,
+            max_retries,
+            _marker: std::marker::PhantomData,
+        }
+    }
+}
+
+#[async_trait]
+impl<S, P, F, I, O> Step<I, O> for ValidatedStep<S, P, F, I, O>
+where
+    I: Clone + Send + Sync + 'static,
+    O: Send + Sync + 'static,
+    S: Step<I, O> + Send + Sync,
+    P: Fn(&O) -> Option<String> + Send + Sync + 'static,
+    F: Fn(I, &O, &str) -> I + Send + Sync + 'static,
+{
+    async fn run(&self, input: I, ctx: &ExecutionContext) -> Result<O> {
+        let step_name = short_type_name(std::any::type_name::<S>());
+        let mut current = input;
+
+        for attempt in 0..=self.max_retries {
+            let output = self.inner.run(current.clone(), ctx).await?;
+            let Some(error) = (self.predicate)(&output) else {
+                return Ok(output);
+            };
+
+            let message = format!(
+                "Output rejected (attempt {}/{}): {}",
+                attempt + 1,
+                self.max_retries + 1,
+                error
+            );
+            ctx.record_failure(format!("{step_name}: {message}"));
+            ctx.emit(WorkflowEvent::Error {
+                step_name: step_name.clone(),
+                message,
+            });
+
+            if attempt == self.max_retries {
+                return Err(StructuredError::Validation(format!(
+                    "{step_name} output failed validation after {} attempts: {error}",
+                    self.max_retries + 1
+                )));
+            }
+            current = (self.feedback)(current, &output, &error);
+        }
+
+        unreachable!("validation loop always returns")
+    }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let inner = self.inner.describe(graph);
+        let check = graph.add_node(
+            format!("validate (max {} retries)", self.max_retries),
+            "validate",
+        );
+        graph.add_edge(inner.exit, check, None);
+        graph.add_edge(check, inner.entry, Some("retry"));
+        GraphSpan {
+            entry: inner.entry,
+            exit: check,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::workflow::LambdaStep;
+
+    fn length_step() -> impl Step<String, usize> {
+        LambdaStep(|text: String| async move { Ok(text.len()) })
+    }
+
+    #[tokio::test]
+    async fn retries_with_feedback_until_valid() {
+        let step = length_step().validate_output(
+            |len: &usize| (*len < 10).then(|| "too short".to_string()),
+            3,
+            |input: String, _len: &usize, error: &str| format!("{input} [{error}]"),
+        );
+
+        let ctx = ExecutionContext::new();
+        let len = step.run("abc".to_string(), &ctx).await.unwrap();
+
+        assert_eq!(len, "abc [too short]".len());
+        assert_eq!(ctx.snapshot().failures.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn fails_after_max_retries() {
+        let step = length_step().validate_output(
+            |_len: &usize| Some("never good enough".to_string()),
+            1,
+            |input: String, _len: &usize, _error: &str| input,
+        );
+
+        let ctx = ExecutionContext::new();
+        let err = step.run("abc".to_string(), &ctx).await.unwrap_err();
+
+        assert!(matches!(err, StructuredError::Validation(_)));
+        assert_eq!(ctx.snapshot().failures.len(), 2);
+        assert_eq!(ctx.trace_snapshot().len(), 2);
+    }
+}
@@ -10,6 +10,7 @@ use futures::stream::{self, StreamExt};
 
 use crate::Result;
 
+use super::graph::{GraphSpan, WorkflowGraph};
 use super::metrics::ExecutionContext;
 use super::Step;
 
@@ -97,4 +98,20 @@ where
 
         Ok(outputs)
     }
+
+    fn describe(&self, graph: &mut WorkflowGraph) -> GraphSpan {
+        let node = graph.add_node(
+            format!(
+                "Windowed (size {}, concurrency {})",
+                self.window_size, self.concurrency
+            ),
+            "windowed",
+        );
+        let worker = self.worker.describe(graph);
+        graph.add_edge(node, worker.entry, Some("each window"));
+        GraphSpan {
+            entry: node,
+            exit: worker.exit,
+        }
+    }
 }
@@ -0,0 +1,20 @@
+use gemini_structured_output::prelude::*;
+
+#[tokio::test]
+async fn invalidating_unknown_keys_is_a_no_op() {
+    let client = StructuredClientBuilder::new("test-key").build().unwrap();
+
+    assert!(!client.invalidate_cache_key("never-cached").await);
+    client.clear_cache().await;
+    assert_eq!(client.cache_stats().evictions, 0);
+}
+
+#[test]
+fn force_refresh_keeps_key_and_ttl() {
+    let settings = CacheSettings::with_key_and_ttl("brief", std::time::Duration::from_secs(60))
+        .force_refresh();
+
+    assert!(settings.force_refresh);
+    assert_eq!(settings.key.as_deref(), Some("brief"));
+    assert!(!CacheSettings::with_key("brief").force_refresh);
+}
@@ -0,0 +1,107 @@
+use std::sync::Arc;
+use std::time::{Duration, SystemTime};
+
+use gemini_rust::Gemini;
+use gemini_structured_output::caching::SchemaCache;
+use gemini_structured_output::prelude::*;
+
+/// Above the size cutoff below which caching is skipped.
+fn large_system() -> String {
+    "x".repeat(8000)
+}
+
+/// A cache holding one verified entry `key` that expires after `ttl`.
+async fn cache_with_entry(key: &str, ttl: Duration) -> SchemaCache {
+    let client = Arc::new(Gemini::new("test-key").unwrap());
+    let cache = SchemaCache::new(
+        client.clone(),
+        CachePolicy::Enabled {
+            ttl: Duration::from_secs(600),
+        },
+    );
+    let handle = client.get_cached_content(&format!("cachedContents/{key}"));
+    cache.insert(key, handle, SystemTime::now() + ttl).await;
+    cache
+}
+
+#[test]
+fn fresh_client_reports_empty_cache_stats() {
+    let client = StructuredClientBuilder::new("test-key").build().unwrap();
+
+    let stats = client.cache_stats();
+    assert_eq!(stats, CacheStats::default());
+    assert_eq!(stats.hit_rate(), 0.0);
+}
+
+#[test]
+fn cache_stats_display_shows_hit_rate() {
+    let stats = CacheStats {
+        hits: 3,
+        misses: 1,
+        creations: 1,
+        evictions: 0,
+    };
+
+    assert_eq!(stats.hit_rate(), 0.75);
+    assert_eq!(
+        stats.to_string(),
+        "hit rate 75.0% (3 hits, 1 misses, 1 created, 0 evicted)"
+    );
+}
+
+#[tokio::test]
+async fn fresh_entry_counts_as_hit() {
+    let cache = cache_with_entry("warm", Duration::from_secs(3600)).await;
+
+    let handle = cache
+        .get_or_create("warm", &large_system(), &[], None)
+        .await
+        .unwrap();
+
+    assert!(handle.is_some());
+    assert_eq!(
+        cache.stats(),
+        CacheStats {
+            hits: 1,
+            ..Default::default()
+        }
+    );
+}
+
+#[tokio::test]
+async fn near_expiry_entry_counts_as_eviction_and_miss() {
+    // Inside the one-minute expiry margin, so the entry is recreated (which fails offline).
+    let cache = cache_with_entry("stale", Duration::from_secs(30)).await;
+
+    let _ = cache
+        .get_or_create("stale", &large_system(), &[], None)
+        .await;
+
+    let stats = cache.stats();
+    assert_eq!(stats.hits, 0);
+    assert_eq!(stats.evictions, 1);
+    assert_eq!(stats.misses, 1);
+}
+
+#[tokio::test]
+async fn unknown_key_counts_as_miss() {
+    let cache = cache_with_entry("warm", Duration::from_secs(3600)).await;
+
+    let _ = cache
+        .get_or_create("cold", &large_system(), &[], None)
+        .await;
+
+    let stats = cache.stats();
+    assert_eq!(stats.misses, 1);
+    assert_eq!(stats.evictions, 0);
+}
+
+#[tokio::test]
+async fn invalidate_counts_as_eviction() {
+    let cache = cache_with_entry("warm", Duration::from_secs(3600)).await;
+
+    assert!(cache.invalidate("warm").await);
+    assert!(!cache.invalidate("warm").await);
+
+    assert_eq!(cache.stats().evictions, 1);
+}
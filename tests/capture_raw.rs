@@ -0,0 +1,39 @@
+use gemini_structured_output::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+struct Contact {
+    name: String,
+}
+
+fn client() -> StructuredClient {
+    StructuredClientBuilder::new("mock-key")
+        .with_mock(|_| Ok(r#"{"name": "Ada"}"#.to_string()))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn raw_text_is_only_captured_when_requested() {
+    let client = client();
+
+    let plain = client
+        .request::<Contact>()
+        .user_text("Ada")
+        .execute()
+        .await
+        .unwrap();
+    assert!(plain.raw_text.is_none());
+    assert!(plain.cleaned_text.is_none());
+
+    let captured = client
+        .request::<Contact>()
+        .user_text("Ada")
+        .capture_raw(true)
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(captured.raw_text.as_deref(), Some(r#"{"name": "Ada"}"#));
+    assert_eq!(captured.cleaned_text.as_deref(), Some(r#"{"name": "Ada"}"#));
+}
@@ -0,0 +1,74 @@
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::tools::ToolError;
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Query {
+    q: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Answer {
+    source: String,
+}
+
+fn answering(name: &str, source: &'static str) -> ToolRegistry {
+    ToolRegistry::new().register_with_handler::<Query, Answer, _, _>(
+        name,
+        "Answer a query",
+        move |_| async move {
+            Ok::<_, ToolError>(Answer {
+                source: source.to_string(),
+            })
+        },
+    )
+}
+
+#[tokio::test]
+async fn merge_unions_tools_and_prefers_other_on_collision() {
+    let defaults = answering("lookup", "default").merge(answering("search", "default"));
+    let merged = defaults.merge(answering("lookup", "request"));
+
+    assert_eq!(merged.definitions().len(), 2);
+
+    let lookup = merged.execute("lookup", json!({"q": "x"})).await.unwrap();
+    assert_eq!(lookup["source"], "request");
+    let search = merged.execute("search", json!({"q": "x"})).await.unwrap();
+    assert_eq!(search["source"], "default");
+}
+
+#[tokio::test]
+async fn merge_keeps_builtin_tools_once() {
+    let merged = ToolRegistry::new()
+        .with_google_search()
+        .merge(ToolRegistry::new().with_google_search());
+
+    assert_eq!(merged.definitions().len(), 1);
+}
+
+#[tokio::test]
+async fn requests_run_with_default_tools_configured() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_default_tools(answering("lookup", "default"))
+        .with_mock(|_| Ok("{\"source\": \"mock\"}".to_string()))
+        .build()
+        .unwrap();
+
+    let outcome = client
+        .request::<Answer>()
+        .user_text("hi")
+        .with_tools(answering("search", "request"))
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(outcome.value.source, "mock");
+
+    let opted_out = client
+        .request::<Answer>()
+        .user_text("hi")
+        .without_default_tools()
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(opted_out.value.source, "mock");
+}
@@ -0,0 +1,151 @@
+use std::sync::{Arc, Mutex};
+
+use gemini_structured_output::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Answer {
+    source: String,
+}
+
+/// Build a client whose mock returns an empty body first and valid JSON afterwards,
+/// recording every request it sees.
+fn empty_then_valid(
+    strategy: FallbackStrategy,
+) -> (StructuredClient, Arc<Mutex<Vec<MockRequest>>>) {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let client = StructuredClientBuilder::new("test-key")
+        .with_model(Model::Gemini25Flash)
+        .with_fallback_strategy(strategy)
+        .with_mock(move |req| {
+            let mut log = log.lock().unwrap();
+            log.push(req);
+            Ok(match log.len() {
+                1 => "  ".to_string(),
+                _ => "{\"source\": \"mock\"}".to_string(),
+            })
+        })
+        .build()
+        .unwrap();
+    (client, seen)
+}
+
+fn escalation() -> FallbackStrategy {
+    FallbackStrategy::Escalate {
+        after_attempts: 5,
+        target: Model::Gemini25Pro,
+    }
+}
+
+#[tokio::test]
+async fn empty_response_sends_custom_prompt() {
+    let (client, seen) = empty_then_valid(FallbackStrategy::None);
+
+    let outcome = client
+        .request::<Answer>()
+        .user_text("hi")
+        .empty_response_prompt("Say something this time.")
+        .execute()
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.value.source, "mock");
+    assert_eq!(outcome.parse_attempts, 1);
+    let calls = seen.lock().unwrap();
+    assert_eq!(calls.len(), 2);
+    assert!(!calls[0].prompt_preview.contains("Say something this time."));
+    assert!(calls[1]
+        .prompt_preview
+        .ends_with("Say something this time."));
+}
+
+#[tokio::test]
+async fn empty_response_escalates_when_enabled() {
+    let (client, seen) = empty_then_valid(escalation());
+
+    client
+        .request::<Answer>()
+        .user_text("hi")
+        .escalate_on_empty_response(true)
+        .execute()
+        .await
+        .unwrap();
+
+    let calls = seen.lock().unwrap();
+    assert_eq!(calls[0].model, Model::Gemini25Flash.as_str());
+    assert_eq!(calls[1].model, Model::Gemini25Pro.as_str());
+}
+
+#[tokio::test]
+async fn empty_response_stays_on_primary_by_default() {
+    let (client, seen) = empty_then_valid(escalation());
+
+    client
+        .request::<Answer>()
+        .user_text("hi")
+        .execute()
+        .await
+        .unwrap();
+
+    let calls = seen.lock().unwrap();
+    assert_eq!(calls[1].model, Model::Gemini25Flash.as_str());
+}
+
+#[tokio::test]
+async fn repeated_empty_responses_give_up() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(|_| Ok(String::new()))
+        .build()
+        .unwrap();
+
+    let err = client
+        .request::<Answer>()
+        .user_text("hi")
+        .execute()
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, StructuredError::Context(_)));
+}
+
+#[tokio::test]
+async fn repeated_empty_responses_keep_climbing_a_chain() {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let client = StructuredClientBuilder::new("test-key")
+        .with_model(Model::Gemini25Flash)
+        .with_fallback_strategy(FallbackStrategy::Chain(vec![
+            (1, Model::Gemini25Pro),
+            (2, Model::Gemini3Flash),
+        ]))
+        .with_mock(move |req: MockRequest| {
+            let mut log = log.lock().unwrap();
+            log.push(req.model);
+            Ok(match log.len() {
+                1..=3 => String::new(),
+                _ => "{\"source\": \"mock\"}".to_string(),
+            })
+        })
+        .build()
+        .unwrap();
+
+    client
+        .request::<Answer>()
+        .user_text("hi")
+        .max_parse_attempts(5)
+        .escalate_on_empty_response(true)
+        .execute()
+        .await
+        .unwrap();
+
+    let models = seen.lock().unwrap();
+    assert_eq!(
+        *models,
+        [
+            Model::Gemini25Flash.as_str(),
+            Model::Gemini25Pro.as_str(),
+            Model::Gemini25Pro.as_str(),
+            Model::Gemini3Flash.as_str(),
+        ]
+    );
+}
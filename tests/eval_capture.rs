@@ -0,0 +1,50 @@
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::EvalSuite;
+use serde_json::json;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Triage {
+    urgent: bool,
+}
+
+#[tokio::test]
+async fn run_capturing_returns_outputs_in_case_order() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(|req| {
+            let urgent = req.prompt_preview.contains("down");
+            Ok(format!("{{\"urgent\": {urgent}}}"))
+        })
+        .build()
+        .unwrap();
+    let cases = vec![
+        ("outage".to_string(), "Site down"),
+        ("broken".to_string(), ""),
+        ("question".to_string(), "How do I export?"),
+    ];
+
+    let (report, outputs) = EvalSuite::new("triage")
+        .with_concurrency(3)
+        .run_capturing(cases, move |subject: &'static str| {
+            let client = client.clone();
+            async move {
+                if subject.is_empty() {
+                    return Err(StructuredError::Context("empty ticket".to_string()));
+                }
+                let outcome = client
+                    .request::<Triage>()
+                    .user_text(subject)
+                    .execute()
+                    .await?;
+                Ok::<_, StructuredError>((outcome, true))
+            }
+        })
+        .await;
+
+    assert_eq!(report.total_cases, 3);
+    assert_eq!(report.failed, 1);
+    let names: Vec<&str> = outputs.iter().map(|(name, _)| name.as_str()).collect();
+    assert_eq!(names, ["outage", "broken", "question"]);
+    assert_eq!(outputs[0].1.as_ref().unwrap(), &json!({ "urgent": true }));
+    assert!(matches!(outputs[1].1, Err(StructuredError::Context(_))));
+    assert_eq!(outputs[2].1.as_ref().unwrap(), &json!({ "urgent": false }));
+}
@@ -0,0 +1,76 @@
+use std::env;
+
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::EvalSuite;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Ticket {
+    subject: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Triage {
+    urgent: bool,
+}
+
+#[tokio::test]
+async fn runs_cases_from_jsonl_and_reports_bad_lines() {
+    let path = env::temp_dir().join(format!("gso-eval-cases-{}.jsonl", std::process::id()));
+    std::fs::write(
+        &path,
+        concat!(
+            "{\"name\": \"outage\", \"input\": {\"subject\": \"Site down\"}}\n",
+            "\n",
+            "{\"name\": \"missing input\"}\n",
+            "{\"name\": \"question\", \"input\": {\"subject\": \"How do I export?\"}}\n",
+        ),
+    )
+    .unwrap();
+
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(|req| {
+            let urgent = req.prompt_preview.contains("Site down");
+            Ok(format!("{{\"urgent\": {urgent}}}"))
+        })
+        .build()
+        .unwrap();
+
+    let report = EvalSuite::new("triage")
+        .with_concurrency(2)
+        .run_from_jsonl(&path, move |ticket: Ticket| {
+            let client = client.clone();
+            async move {
+                let expect_urgent = ticket.subject == "Site down";
+                let outcome = client
+                    .request::<Triage>()
+                    .user_text(ticket.subject)
+                    .execute()
+                    .await?;
+                let passed = outcome.value.urgent == expect_urgent;
+                Ok::<_, StructuredError>((outcome, passed))
+            }
+        })
+        .await
+        .unwrap();
+    std::fs::remove_file(&path).ok();
+
+    assert_eq!(report.total_cases, 3);
+    assert_eq!(report.passed, 2);
+    let bad_line = report
+        .results
+        .iter()
+        .find(|r| r.case_name == "line 3")
+        .unwrap();
+    assert!(bad_line.error.as_deref().unwrap().contains("line 3"));
+}
+
+#[tokio::test]
+async fn missing_file_is_an_error() {
+    let result = EvalSuite::new("missing")
+        .run_from_jsonl("/nonexistent/cases.jsonl", |ticket: Ticket| async move {
+            Err::<(GenerationOutcome<Triage>, bool), _>(StructuredError::Context(ticket.subject))
+        })
+        .await;
+
+    assert!(matches!(result, Err(StructuredError::Io(_))));
+}
@@ -0,0 +1,143 @@
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::{EvalSuite, EvaluatorOutcome};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Triage {
+    team: String,
+    urgent: bool,
+}
+
+/// A client whose mock routes outages to "ops" and everything else to "support",
+/// never marking anything urgent.
+fn triage_client() -> StructuredClient {
+    StructuredClientBuilder::new("test-key")
+        .with_mock(|req| {
+            let team = if req.prompt_preview.contains("down") {
+                "ops"
+            } else {
+                "support"
+            };
+            Ok(format!("{{\"team\": \"{team}\", \"urgent\": false}}"))
+        })
+        .build()
+        .unwrap()
+}
+
+fn cases() -> Vec<(String, &'static str, Triage)> {
+    vec![
+        (
+            "question".to_string(),
+            "How do I export?",
+            Triage {
+                team: "support".to_string(),
+                urgent: false,
+            },
+        ),
+        (
+            "outage".to_string(),
+            "Site down",
+            Triage {
+                team: "ops".to_string(),
+                urgent: true,
+            },
+        ),
+    ]
+}
+
+fn result<'a>(report: &'a SuiteReport, name: &str) -> &'a EvalResult {
+    report.results.iter().find(|r| r.case_name == name).unwrap()
+}
+
+#[tokio::test]
+async fn run_exact_reports_field_diff_for_mismatches() {
+    let client = triage_client();
+
+    let report = EvalSuite::new("triage")
+        .run_exact(cases(), move |subject: &'static str| {
+            let client = client.clone();
+            async move {
+                client
+                    .request::<Triage>()
+                    .user_text(subject)
+                    .execute()
+                    .await
+            }
+        })
+        .await;
+
+    assert_eq!(report.passed, 1);
+    assert_eq!(result(&report, "question").score, Some(1.0));
+    let outage = result(&report, "outage");
+    assert_eq!(outage.score, Some(0.0));
+    let error = outage.error.as_deref().unwrap();
+    assert!(error.starts_with("score 0.00"));
+    assert!(error.contains("urgent"));
+    assert!(!error.contains("team"));
+}
+
+#[tokio::test]
+async fn run_with_scorer_passes_at_threshold() {
+    let client = triage_client();
+    let field_match = |expected: &Triage, actual: &Triage| {
+        let matching = [
+            expected.team == actual.team,
+            expected.urgent == actual.urgent,
+        ];
+        matching.iter().filter(|m| **m).count() as f64 / matching.len() as f64
+    };
+
+    let report = EvalSuite::new("triage")
+        .run_with_scorer(
+            cases(),
+            move |subject: &'static str| {
+                let client = client.clone();
+                async move {
+                    client
+                        .request::<Triage>()
+                        .user_text(subject)
+                        .execute()
+                        .await
+                }
+            },
+            field_match,
+            0.5,
+        )
+        .await;
+
+    assert_eq!(report.passed, 2);
+    assert_eq!(result(&report, "outage").score, Some(0.5));
+    assert!(result(&report, "outage").error.is_none());
+}
+
+#[tokio::test]
+async fn evaluator_outcome_score_overrides_pass_default() {
+    let client = triage_client();
+
+    let report = EvalSuite::new("triage")
+        .run(
+            vec![("question".to_string(), "How do I export?")],
+            move |subject: &'static str| {
+                let client = client.clone();
+                async move {
+                    let outcome = client
+                        .request::<Triage>()
+                        .user_text(subject)
+                        .execute()
+                        .await?;
+                    Ok::<_, StructuredError>(
+                        EvaluatorOutcome::new(outcome, false)
+                            .with_message("team was right, urgency unchecked")
+                            .with_score(0.5),
+                    )
+                }
+            },
+        )
+        .await;
+
+    let question = result(&report, "question");
+    assert_eq!(question.score, Some(0.5));
+    assert_eq!(
+        question.error.as_deref(),
+        Some("team was right, urgency unchecked")
+    );
+}
@@ -0,0 +1,105 @@
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::{CaseTransition, EvalSuite, SuiteReport};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Label {
+    label: String,
+}
+
+async fn report() -> SuiteReport {
+    let cases = vec![
+        ("spam, obviously".to_string(), "buy now".to_string()),
+        ("broken".to_string(), "broken".to_string()),
+    ];
+    run_suite(cases, "broken").await
+}
+
+/// Run `cases` against a mock that returns invalid JSON for prompts containing `failing`.
+async fn run_suite(cases: Vec<(String, String)>, failing: &'static str) -> SuiteReport {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(move |req| {
+            if req.prompt_preview.contains(failing) {
+                Ok("not json".to_string())
+            } else {
+                Ok("{\"label\": \"spam\"}".to_string())
+            }
+        })
+        .build()
+        .unwrap();
+
+    EvalSuite::new("labels")
+        .run(cases, move |text: String| {
+            let client = client.clone();
+            async move {
+                let outcome = client.request::<Label>().user_text(text).execute().await?;
+                let passed = outcome.value.label == "spam";
+                Ok::<_, StructuredError>((outcome, passed))
+            }
+        })
+        .await
+}
+
+#[tokio::test]
+async fn json_export_uses_stable_field_names() {
+    let json = report().await.to_json();
+
+    assert_eq!(json["suite_name"], "labels");
+    assert_eq!(json["total_cases"], 2);
+    assert_eq!(json["passed"], 1);
+    let results = json["results"].as_array().unwrap();
+    assert_eq!(results.len(), 2);
+    for result in results {
+        assert!(result["latency_ms"].is_u64());
+        assert!(result.get("network_attempts").is_some());
+    }
+}
+
+#[tokio::test]
+async fn csv_export_has_one_row_per_case() {
+    let csv = report().await.to_csv();
+    let lines: Vec<&str> = csv.lines().collect();
+
+    assert_eq!(lines[0], SuiteReport::CSV_HEADER);
+    assert_eq!(lines.len(), 3);
+    let passed = lines
+        .iter()
+        .find(|line| line.starts_with("\"spam, obviously\""))
+        .unwrap();
+    assert!(passed.contains(",true,"));
+    let failed = lines
+        .iter()
+        .find(|line| line.starts_with("broken,"))
+        .unwrap();
+    assert!(failed.contains(",false,"));
+}
+
+#[tokio::test]
+async fn compare_reports_transitions_by_case_name() {
+    let cases = |names: &[&str]| {
+        names
+            .iter()
+            .map(|name| (name.to_string(), format!("{name} message")))
+            .collect::<Vec<_>>()
+    };
+    let baseline = run_suite(cases(&["alpha", "beta", "gamma"]), "beta").await;
+    let current = run_suite(cases(&["alpha", "beta", "delta"]), "alpha").await;
+
+    let diff = current.compare(&baseline);
+    let transition = |name: &str| {
+        diff.cases
+            .iter()
+            .find(|case| case.case_name == name)
+            .map(|case| case.transition)
+    };
+
+    assert_eq!(transition("alpha"), Some(CaseTransition::Regressed));
+    assert_eq!(transition("beta"), Some(CaseTransition::Fixed));
+    assert_eq!(transition("gamma"), Some(CaseTransition::Removed));
+    assert_eq!(transition("delta"), Some(CaseTransition::Added));
+    assert!(diff.has_regressions());
+    assert!(diff.pass_rate_delta().abs() < 1e-9);
+
+    let rendered = diff.to_string();
+    assert!(rendered.contains("REGRESSIONS (1)"));
+    assert!(rendered.contains("[alpha] PASS -> FAIL"));
+}
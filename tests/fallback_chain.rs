@@ -0,0 +1,42 @@
+use gemini_structured_output::prelude::*;
+
+fn chain() -> FallbackStrategy {
+    FallbackStrategy::Chain(vec![
+        (3, Model::Gemini25Pro),
+        (1, Model::Custom("models/gemini-2.5-flash".to_string())),
+    ])
+}
+
+#[test]
+fn chain_tiers_are_sorted_by_threshold() {
+    let tiers = chain().tiers();
+
+    assert_eq!(tiers[0].0, 1);
+    assert_eq!(tiers[0].1.as_str(), "models/gemini-2.5-flash");
+    assert_eq!(tiers[1].0, 3);
+}
+
+#[test]
+fn highest_exceeded_threshold_is_active() {
+    let strategy = chain();
+
+    assert_eq!(strategy.active_tier(0), None);
+    assert_eq!(strategy.active_tier(1), None);
+    assert_eq!(strategy.active_tier(2), Some(0));
+    assert_eq!(strategy.active_tier(3), Some(0));
+    assert_eq!(strategy.active_tier(4), Some(1));
+    assert_eq!(FallbackStrategy::None.active_tier(10), None);
+}
+
+#[test]
+fn client_builds_with_fallback_chain() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_fallback_strategy(chain())
+        .build()
+        .unwrap();
+
+    assert!(client.fallback_client.is_some());
+    assert!(
+        matches!(client.fallback_strategy(), FallbackStrategy::Chain(tiers) if tiers.len() == 2)
+    );
+}
@@ -0,0 +1,68 @@
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::JsonlOutcome;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct LogEvent {
+    level: String,
+    message: String,
+}
+
+const RESPONSE: &str = "```jsonl\n\
+    {\"level\": \"info\", \"message\": \"started\"}\n\
+    \n\
+    {\"level\": \"warn\", \"message\": \"disk\n\
+    {\"level\": \"error\", \"message\": \"crashed\"}\n\
+    ```";
+
+fn client() -> StructuredClient {
+    StructuredClientBuilder::new("test-key")
+        .with_mock(|_| Ok(RESPONSE.to_string()))
+        .build()
+        .unwrap()
+}
+
+#[test]
+fn parse_reports_failed_line_numbers() {
+    let outcome = JsonlOutcome::<LogEvent>::parse(RESPONSE);
+
+    assert_eq!(outcome.items.len(), 2);
+    assert_eq!(outcome.items[1].message, "crashed");
+    assert_eq!(outcome.failed_lines.len(), 1);
+    assert_eq!(outcome.failed_lines[0].line, 4);
+}
+
+#[tokio::test]
+async fn generate_jsonl_skips_malformed_lines_when_asked() {
+    let events: Vec<LogEvent> = client().generate_jsonl("logs", true).await.unwrap();
+
+    let levels: Vec<&str> = events.iter().map(|e| e.level.as_str()).collect();
+    assert_eq!(levels, vec!["info", "error"]);
+}
+
+#[tokio::test]
+async fn generate_jsonl_fails_on_malformed_lines_by_default() {
+    let err = client()
+        .generate_jsonl::<LogEvent>("logs", false)
+        .await
+        .unwrap_err();
+
+    assert!(matches!(err, StructuredError::Validation(ref msg) if msg.contains("line 4")));
+}
+
+#[tokio::test]
+async fn response_hook_runs_on_every_line() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_response_hook(|value: &mut serde_json::Value| {
+            if let Some(level) = value["level"].as_str() {
+                value["level"] = level.to_uppercase().into();
+            }
+        })
+        .with_mock(|_| Ok(RESPONSE.to_string()))
+        .build()
+        .unwrap();
+
+    let events: Vec<LogEvent> = client.generate_jsonl("logs", true).await.unwrap();
+
+    let levels: Vec<&str> = events.iter().map(|e| e.level.as_str()).collect();
+    assert_eq!(levels, vec!["INFO", "ERROR"]);
+}
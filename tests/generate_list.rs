@@ -0,0 +1,49 @@
+use gemini_structured_output::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+struct Contact {
+    name: String,
+}
+
+fn client(response: &'static str) -> StructuredClient {
+    StructuredClientBuilder::new("mock-key")
+        .with_mock(move |_| Ok(response.to_string()))
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn unwraps_item_envelope() {
+    let contacts: Vec<Contact> = client(r#"{"items": [{"name": "Ada"}, {"name": "Grace"}]}"#)
+        .generate_list("Ada and Grace")
+        .await
+        .unwrap();
+
+    assert_eq!(contacts.len(), 2);
+    assert_eq!(contacts[1].name, "Grace");
+}
+
+#[tokio::test]
+async fn accepts_bare_array_response() {
+    let contacts: Vec<Contact> = client(r#"[{"name": "Ada"}]"#)
+        .generate_list_with_system("Extract every contact.", "Ada")
+        .await
+        .unwrap();
+
+    assert_eq!(contacts, vec![Contact { name: "Ada".into() }]);
+}
+
+#[test]
+fn envelope_reports_the_failing_item_field() {
+    let err = serde_json::from_str::<ListEnvelope<Contact>>(r#"{"items": [{"name": 7}]}"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("invalid type: integer `7`"), "{err}");
+
+    let err = serde_json::from_str::<ListEnvelope<Contact>>(r#"[{"nom": "Ada"}]"#)
+        .unwrap_err()
+        .to_string();
+    assert!(err.contains("missing field `name`"), "{err}");
+}
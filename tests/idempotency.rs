@@ -0,0 +1,52 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+
+use gemini_structured_output::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+struct Invoice {
+    number: String,
+}
+
+#[tokio::test]
+async fn repeated_idempotency_key_reuses_outcome() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let counter = Arc::clone(&calls);
+    let client = StructuredClientBuilder::new("mock-key")
+        .with_mock(move |_| {
+            let n = counter.fetch_add(1, Ordering::SeqCst);
+            Ok(format!(r#"{{"number": "INV-{n}"}}"#))
+        })
+        .build()
+        .unwrap();
+
+    let first = client
+        .request::<Invoice>()
+        .user_text("extract")
+        .idempotency_key("msg-42")
+        .execute()
+        .await
+        .unwrap();
+    let replay = client
+        .request::<Invoice>()
+        .user_text("extract")
+        .idempotency_key("msg-42")
+        .execute()
+        .await
+        .unwrap();
+    let other = client
+        .request::<Invoice>()
+        .user_text("extract")
+        .idempotency_key("msg-43")
+        .execute()
+        .await
+        .unwrap();
+
+    assert_eq!(first.value, replay.value);
+    assert_ne!(first.value, other.value);
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
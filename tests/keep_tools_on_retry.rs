@@ -0,0 +1,75 @@
+use std::sync::{Arc, Mutex};
+
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::tools::ToolError;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Query {
+    q: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Answer {
+    source: String,
+}
+
+fn lookup() -> ToolRegistry {
+    ToolRegistry::new().register_with_handler::<Query, Answer, _, _>(
+        "lookup",
+        "Answer a query",
+        |_| async move {
+            Ok::<_, ToolError>(Answer {
+                source: "tool".to_string(),
+            })
+        },
+    )
+}
+
+/// Run a request whose first mock response fails to parse, returning the tools the
+/// mock saw on each call.
+async fn tools_per_call(keep_tools: bool) -> Vec<Vec<String>> {
+    let seen = Arc::new(Mutex::new(Vec::new()));
+    let log = Arc::clone(&seen);
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(move |req| {
+            let mut log = log.lock().unwrap();
+            log.push(req.tools.clone());
+            Ok(match log.len() {
+                1 => "{\"source\": ".to_string(),
+                _ => "{\"source\": \"mock\"}".to_string(),
+            })
+        })
+        .build()
+        .unwrap();
+
+    let outcome = client
+        .request::<Answer>()
+        .user_text("hi")
+        .with_tools(lookup())
+        .keep_tools_on_retry(keep_tools)
+        .execute()
+        .await
+        .unwrap();
+    assert_eq!(outcome.value.source, "mock");
+    assert_eq!(outcome.parse_attempts, 1);
+
+    let calls = seen.lock().unwrap().clone();
+    calls
+}
+
+#[tokio::test]
+async fn parse_retry_withholds_tools_by_default() {
+    let calls = tools_per_call(false).await;
+
+    assert_eq!(calls, [vec!["lookup".to_string()], vec![]]);
+}
+
+#[tokio::test]
+async fn parse_retry_keeps_tools_when_requested() {
+    let calls = tools_per_call(true).await;
+
+    assert_eq!(
+        calls,
+        [vec!["lookup".to_string()], vec!["lookup".to_string()]]
+    );
+}
@@ -0,0 +1,48 @@
+use std::env;
+use std::sync::{Arc, Mutex};
+
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::DocumentBundle;
+
+// File handles only come from the Files API, so the upload needs a key; generation
+// itself runs against a mock to check how the documents reach the prompt.
+#[tokio::test]
+#[ignore = "Uploads a small file to build a labeled document, requires key"]
+async fn labels_precede_each_document_in_the_prompt() {
+    let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
+    let uploader = StructuredClientBuilder::new(api_key)
+        .build()
+        .expect("Failed to create client");
+    let handle = uploader
+        .file_manager
+        .upload_bytes_and_wait("Revenue: 120".as_bytes(), "text/plain", Some("actuals.txt"))
+        .await
+        .unwrap();
+    let uri = handle.get_file_meta().uri.as_ref().unwrap().to_string();
+
+    let prompts = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&prompts);
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(move |req| {
+            seen.lock().unwrap().push(req.prompt_preview);
+            Ok("\"Revenue was 120.\"".to_string())
+        })
+        .build()
+        .unwrap();
+
+    let document =
+        DocumentBundle::new(handle, "2023 Actuals").with_role_hint("Source of truth for revenue");
+    client
+        .request::<String>()
+        .with_labeled_documents(vec![document])
+        .unwrap()
+        .user_text("Summarize revenue")
+        .execute()
+        .await
+        .unwrap();
+
+    let prompt = prompts.lock().unwrap()[0].clone();
+    let label = prompt.find("=== Document: 2023 Actuals ===").unwrap();
+    assert!(prompt.contains("Source of truth for revenue"));
+    assert!(label < prompt.find(&uri).unwrap());
+}
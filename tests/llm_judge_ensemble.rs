@@ -0,0 +1,55 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::LLMJudge;
+
+fn judge(verdicts: Vec<&'static str>) -> LLMJudge {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(move |_| {
+            let call = calls.fetch_add(1, Ordering::SeqCst);
+            Ok(verdicts[call % verdicts.len()].to_string())
+        })
+        .build()
+        .unwrap();
+    LLMJudge::new(client, "The answer must be correct.").with_ensemble_temperature(1.0)
+}
+
+#[tokio::test]
+async fn ensemble_averages_scores_and_takes_majority_vote() {
+    let judge = judge(vec![
+        r#"{"score": 0.9, "pass": true, "reasoning": "Correct."}"#,
+        r#"{"score": 0.2, "pass": false, "reasoning": "Off by one."}"#,
+        r#"{"score": 0.7, "pass": true, "reasoning": "Mostly right."}"#,
+    ]);
+
+    let ensemble = judge
+        .evaluate_ensemble(&"2 + 2", &4, None::<&()>, 3)
+        .await
+        .unwrap();
+
+    assert_eq!(ensemble.verdicts.len(), 3);
+    assert!(ensemble.verdict.pass);
+    assert!((ensemble.verdict.score - 0.6).abs() < 1e-9);
+    assert!((ensemble.agreement() - 2.0 / 3.0).abs() < 1e-9);
+    let first_reason = ensemble.verdict.reasoning.lines().next().unwrap();
+    assert!(first_reason.ends_with("score 0.90] Correct."));
+    assert!(!ensemble.verdict.reasoning.contains("Off by one."));
+}
+
+#[tokio::test]
+async fn split_panel_fails() {
+    let judge = judge(vec![
+        r#"{"score": 0.8, "pass": true, "reasoning": "Fine."}"#,
+        r#"{"score": 0.3, "pass": false, "reasoning": "Wrong units."}"#,
+    ]);
+
+    let ensemble = judge
+        .evaluate_ensemble(&"distance", &"5", None::<&()>, 2)
+        .await
+        .unwrap();
+
+    assert!(!ensemble.verdict.pass);
+    assert!(ensemble.verdict.reasoning.contains("Wrong units."));
+}
@@ -0,0 +1,98 @@
+use gemini_structured_output::prelude::*;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+struct Contact {
+    name: String,
+    score: f32,
+}
+
+#[tokio::test]
+async fn validated_mock_accepts_conforming_output() {
+    let client = StructuredClientBuilder::new("mock-key")
+        .with_mock_validated(|_| Ok(r#"{"name": "Ada", "score": 0.5}"#.to_string()))
+        .build()
+        .unwrap();
+
+    let outcome = client
+        .request::<Contact>()
+        .user_text("Ada")
+        .execute()
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.value.name, "Ada");
+}
+
+#[tokio::test]
+async fn validated_mock_reports_schema_violations() {
+    let client = StructuredClientBuilder::new("mock-key")
+        .with_mock_validated(|_| Ok(r#"{"name": "Ada", "score": "high"}"#.to_string()))
+        .build()
+        .unwrap();
+
+    let err = client
+        .request::<Contact>()
+        .user_text("Ada")
+        .execute()
+        .await
+        .unwrap_err();
+
+    match err {
+        StructuredError::Validation(message) => {
+            assert!(message.contains("does not match its schema"));
+            assert!(message.contains("/score"));
+        }
+        other => panic!("expected validation error, got {other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Scores {
+    by_name: std::collections::HashMap<String, u32>,
+}
+
+#[tokio::test]
+async fn validated_mock_normalizes_map_entry_arrays_like_live_responses() {
+    let client = StructuredClientBuilder::new("mock-key")
+        .with_mock_validated(|_| {
+            Ok(r#"{"by_name": [{"__key__": "ada", "__value__": 3}]}"#.to_string())
+        })
+        .build()
+        .unwrap();
+
+    let outcome = client
+        .request::<Scores>()
+        .user_text("scores")
+        .execute()
+        .await
+        .unwrap();
+
+    assert_eq!(outcome.value.by_name["ada"], 3);
+}
+
+#[tokio::test]
+async fn mocks_get_the_request_field_defaults() {
+    for validated in [true, false] {
+        let builder = StructuredClientBuilder::new("mock-key");
+        let mock = |_: MockRequest| -> Result<String> { Ok(r#"{"name": "Ada"}"#.to_string()) };
+        let client = if validated {
+            builder.with_mock_validated(mock)
+        } else {
+            builder.with_mock(mock)
+        }
+        .build()
+        .unwrap();
+
+        let outcome = client
+            .request::<Contact>()
+            .user_text("Ada")
+            .with_field_defaults([("score".to_string(), serde_json::json!(1.0))].into())
+            .execute()
+            .await
+            .unwrap();
+
+        assert_eq!(outcome.value.score, 1.0, "validated: {validated}");
+    }
+}
@@ -0,0 +1,68 @@
+use std::sync::{Arc, Mutex};
+
+use gemini_structured_output::prelude::*;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Summary {
+    headline: String,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Metadata {
+    pages: u32,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Language {
+    code: String,
+}
+
+#[tokio::test]
+async fn generate_pair_splits_one_combined_response() {
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&requests);
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(move |req| {
+            seen.lock().unwrap().push(req);
+            Ok(r#"{"first": {"headline": "Q3 up"}, "second": {"pages": 12}}"#.to_string())
+        })
+        .build()
+        .unwrap();
+
+    let (summary, metadata): (Summary, Metadata) =
+        client.generate_pair("quarterly report").await.unwrap();
+
+    assert_eq!(
+        summary,
+        Summary {
+            headline: "Q3 up".to_string()
+        }
+    );
+    assert_eq!(metadata, Metadata { pages: 12 });
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 1);
+    let schema = requests[0].schema.as_ref().unwrap();
+    assert!(schema["properties"]["first"].is_object());
+    assert!(schema["properties"]["second"].is_object());
+}
+
+#[tokio::test]
+async fn generate_triple_keeps_declaration_order() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(|_| {
+            Ok(r#"{
+                "first": {"headline": "Q3 up"},
+                "second": {"pages": 12},
+                "third": {"code": "en"}
+            }"#
+            .to_string())
+        })
+        .build()
+        .unwrap();
+
+    let (_, metadata, language): (Summary, Metadata, Language) =
+        client.generate_triple("quarterly report").await.unwrap();
+
+    assert_eq!(metadata.pages, 12);
+    assert_eq!(language.code, "en");
+}
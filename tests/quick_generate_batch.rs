@@ -0,0 +1,51 @@
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::Duration;
+
+use gemini_structured_output::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Invoice {
+    number: String,
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn batch_preserves_order_isolates_errors_and_caps_concurrency() {
+    let active = Arc::new(AtomicUsize::new(0));
+    let peak = Arc::new(AtomicUsize::new(0));
+    let (active_in_mock, peak_in_mock) = (Arc::clone(&active), Arc::clone(&peak));
+
+    let client = StructuredClientBuilder::new("test-key")
+        .with_batch_concurrency(2)
+        .with_mock(move |req| {
+            let now = active_in_mock.fetch_add(1, Ordering::SeqCst) + 1;
+            peak_in_mock.fetch_max(now, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(20));
+            active_in_mock.fetch_sub(1, Ordering::SeqCst);
+
+            let number = ["INV-1", "INV-2", "INV-3", "INV-4"]
+                .into_iter()
+                .find(|n| req.prompt_preview.contains(n));
+            match number {
+                Some(n) => Ok(format!("{{\"number\": \"{n}\"}}")),
+                None => Ok("not json".to_string()),
+            }
+        })
+        .build()
+        .unwrap();
+
+    let prompts = ["INV-1", "garbled", "INV-3", "INV-4"]
+        .map(String::from)
+        .to_vec();
+    let results = client
+        .quick_generate_batch::<Invoice>(prompts)
+        .await
+        .unwrap();
+
+    assert_eq!(results.len(), 4);
+    assert_eq!(results[0].as_ref().unwrap().number, "INV-1");
+    assert!(results[1].is_err());
+    assert_eq!(results[2].as_ref().unwrap().number, "INV-3");
+    assert_eq!(results[3].as_ref().unwrap().number, "INV-4");
+    assert!(peak.load(Ordering::SeqCst) <= 2);
+}
@@ -0,0 +1,29 @@
+use gemini_structured_output::prelude::*;
+
+#[test]
+fn client_exposes_refinement_config_from_builder() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_refinement_retries(5)
+        .with_refinement_strategy(PatchStrategy::Atomic)
+        .with_validation_failure_strategy(ValidationFailureStrategy::Rollback)
+        .build()
+        .unwrap();
+
+    let config = client.refinement_config();
+    assert_eq!(config.max_retries, 5);
+    assert!(matches!(config.patch_strategy, PatchStrategy::Atomic));
+    assert!(matches!(
+        config.validation_failure_strategy,
+        ValidationFailureStrategy::Rollback
+    ));
+}
+
+#[test]
+fn validation_failure_strategy_defaults_to_iterate_forward() {
+    let client = StructuredClientBuilder::new("test-key").build().unwrap();
+
+    assert!(matches!(
+        client.refinement_config().validation_failure_strategy,
+        ValidationFailureStrategy::IterateForward
+    ));
+}
@@ -0,0 +1,122 @@
+use gemini_rust::{FunctionDeclaration, Tool};
+use gemini_structured_output::prelude::*;
+use serde_json::{json, Value};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Lookup {
+    query: String,
+}
+
+fn echo_handler() -> Box<ToolHandler> {
+    Box::new(|args: Value, _| Box::pin(async move { Ok(json!({ "echo": args["query"] })) }))
+}
+
+fn echo_definition() -> FunctionDeclaration {
+    FunctionDeclaration::new("echo", "Echo the query", None).with_parameters::<Lookup>()
+}
+
+#[tokio::test]
+async fn runtime_registration_is_shared_by_clones() {
+    let registry = ToolRegistry::new();
+    let session_view = registry.clone();
+
+    registry
+        .register_boxed("echo", echo_definition(), echo_handler())
+        .unwrap();
+
+    assert_eq!(session_view.tool_names(), vec!["echo"]);
+    assert_eq!(session_view.definitions().len(), 1);
+    let result = session_view
+        .execute("echo", json!({"query": "hi"}))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({"echo": "hi"}));
+}
+
+#[tokio::test]
+async fn removed_tools_disappear_from_definitions_and_execution() {
+    let registry = ToolRegistry::new().register::<Lookup, Lookup>("lookup", "Look something up");
+    registry
+        .register_boxed("echo", echo_definition(), echo_handler())
+        .unwrap();
+
+    assert!(registry.remove_tool("echo"));
+    assert!(!registry.remove_tool("echo"));
+    assert_eq!(registry.tool_names(), vec!["lookup"]);
+    assert!(registry
+        .execute("echo", json!({"query": "hi"}))
+        .await
+        .is_err());
+}
+
+#[test]
+fn re_registering_replaces_the_previous_definition() {
+    let registry = ToolRegistry::new();
+    registry
+        .register_boxed("echo", echo_definition(), echo_handler())
+        .unwrap();
+    registry
+        .register_boxed("echo", echo_definition(), echo_handler())
+        .unwrap();
+
+    assert_eq!(registry.tool_names(), vec!["echo"]);
+}
+
+#[test]
+fn register_boxed_rejects_mismatched_names() {
+    let registry = ToolRegistry::new();
+
+    let err = registry
+        .register_boxed("lookup", echo_definition(), echo_handler())
+        .unwrap_err();
+
+    assert!(matches!(err, StructuredError::Config(_)));
+    assert!(registry.tool_names().is_empty());
+}
+
+#[test]
+fn remove_tool_keeps_sibling_declarations() {
+    let grouped: Tool = serde_json::from_value(json!({
+        "functionDeclarations": [
+            {"name": "echo", "description": "Echo the query"},
+            {"name": "shout", "description": "Echo the query loudly"}
+        ]
+    }))
+    .unwrap();
+    let registry = ToolRegistry::new().with_tool(grouped);
+
+    assert!(registry.remove_tool("echo"));
+    assert_eq!(registry.tool_names(), vec!["shout"]);
+}
+
+#[test]
+fn builder_methods_leave_earlier_clones_untouched() {
+    let base = ToolRegistry::new().register::<Lookup, Lookup>("lookup", "Look something up");
+    let extended = base
+        .clone()
+        .register::<Lookup, Lookup>("search", "Search for something");
+
+    assert_eq!(base.tool_names(), vec!["lookup"]);
+    assert_eq!(extended.tool_names(), vec!["lookup", "search"]);
+}
+
+#[tokio::test]
+async fn merged_registry_shares_runtime_changes_with_its_clones_only() {
+    let base = ToolRegistry::new().register::<Lookup, Lookup>("lookup", "Look something up");
+    let extra = ToolRegistry::new();
+    let merged = base.clone().merge(extra.clone());
+    let session_view = merged.clone();
+
+    merged
+        .register_boxed("echo", echo_definition(), echo_handler())
+        .unwrap();
+
+    assert_eq!(session_view.tool_names(), vec!["lookup", "echo"]);
+    let result = session_view
+        .execute("echo", json!({"query": "hi"}))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({"echo": "hi"}));
+    assert_eq!(base.tool_names(), vec!["lookup"]);
+    assert!(extra.tool_names().is_empty());
+}
@@ -0,0 +1,26 @@
+use gemini_structured_output::prelude::*;
+use std::env;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Haiku {
+    title: String,
+    lines: Vec<String>,
+}
+
+#[tokio::test]
+#[ignore = "Checks seeded sampling is reproducible, requires key"]
+async fn same_seed_produces_identical_output() {
+    let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
+    let client = StructuredClientBuilder::new(api_key)
+        .with_model(Model::Gemini3Flash)
+        .with_default_seed(1234)
+        .with_default_temperature(0.7)
+        .build()
+        .expect("Failed to create client");
+
+    let prompt = "Write a haiku about a lighthouse in winter.";
+    let first: Haiku = client.quick_generate(prompt).await.unwrap();
+    let second: Haiku = client.quick_generate(prompt).await.unwrap();
+
+    assert_eq!(first, second);
+}
@@ -0,0 +1,106 @@
+use std::sync::{Arc, Mutex};
+
+use gemini_rust::Role;
+use gemini_structured_output::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Budget {
+    limit: u32,
+}
+
+fn seeded_session() -> InteractiveSession<Budget, String> {
+    let mut session = InteractiveSession::new(Budget { limit: 100 }, None);
+    session
+        .history
+        .push(SessionEntry::new_chat(Role::User, "Raise the limit"));
+    session.history.push(SessionEntry::new_state_change(
+        "limit: 100 -> 150",
+        None,
+        Role::Model,
+        "Updated the limit.",
+    ));
+    session
+        .history
+        .push(SessionEntry::new_chat(Role::User, "Why 150?"));
+    session
+        .history
+        .push(SessionEntry::new_chat(Role::Model, "It covers Q3 spend."));
+    session
+}
+
+#[tokio::test]
+async fn compaction_summarizes_older_entries_and_keeps_recent_ones() {
+    let prompts = Arc::new(Mutex::new(Vec::new()));
+    let seen = Arc::clone(&prompts);
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(move |req| {
+            seen.lock().unwrap().push(req.prompt_preview);
+            Ok("\"User asked to raise the limit.\"".to_string())
+        })
+        .build()
+        .unwrap();
+
+    let mut session = seeded_session();
+    let compacted = session.compact_history(&client, 2).await.unwrap();
+
+    assert_eq!(compacted, 2);
+    assert_eq!(session.history.len(), 3);
+    assert!(matches!(session.history[0].kind, EntryKind::SystemNote));
+    let note = session.history[0].text();
+    assert!(note.contains("User asked to raise the limit."));
+    assert!(note.contains("- limit: 100 -> 150"));
+    assert_eq!(session.history[1].text(), "Why 150?");
+    assert!(prompts.lock().unwrap()[0].contains("Raise the limit"));
+}
+
+#[tokio::test]
+async fn chat_compacts_once_entry_threshold_is_crossed() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(|_| Ok("\"summary or answer\"".to_string()))
+        .build()
+        .unwrap();
+
+    let mut session =
+        seeded_session().with_history_compaction(HistoryCompaction::new(1).with_max_entries(3));
+    session.chat(&client, "And now?").await.unwrap();
+
+    // One summary note, the kept entry, then the new question and answer.
+    assert_eq!(session.history.len(), 4);
+    assert_eq!(
+        session.history[0].metadata.get("type").map(String::as_str),
+        Some("history_summary")
+    );
+    assert_eq!(session.history[1].text(), "It covers Q3 spend.");
+}
+
+#[tokio::test]
+async fn second_compaction_keeps_earlier_change_list() {
+    let client = StructuredClientBuilder::new("test-key")
+        .with_mock(|_| Ok("\"Condensed.\"".to_string()))
+        .build()
+        .unwrap();
+
+    let mut session = seeded_session();
+    session.compact_history(&client, 2).await.unwrap();
+    session.history.push(SessionEntry::new_state_change(
+        "limit: 150 -> 120",
+        None,
+        Role::Model,
+        "Lowered the limit.",
+    ));
+    session
+        .history
+        .push(SessionEntry::new_chat(Role::User, "Thanks."));
+    session.compact_history(&client, 1).await.unwrap();
+
+    assert_eq!(session.history.len(), 2);
+    let note = session.history[0].text();
+    assert!(note.contains("- limit: 100 -> 150\n- limit: 150 -> 120"));
+    assert_eq!(
+        session.history[0]
+            .metadata
+            .get("changes")
+            .map(String::as_str),
+        Some(r#"["limit: 100 -> 150","limit: 150 -> 120"]"#)
+    );
+}
@@ -0,0 +1,115 @@
+use gemini_structured_output::prelude::*;
+use gemini_structured_output::session::SESSION_FORMAT_VERSION;
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Order {
+    sku: String,
+    #[schemars(range(min = 1))]
+    quantity: u32,
+}
+
+type OrderSession = InteractiveSession<Order, String>;
+
+fn session() -> OrderSession {
+    let mut session = InteractiveSession::new(
+        Order {
+            sku: "A-1".into(),
+            quantity: 2,
+        },
+        Some("2 x A-1".to_string()),
+    );
+    session
+        .history
+        .push(SessionEntry::new_system_note("Created."));
+    session
+}
+
+#[test]
+fn round_trips_through_writer_and_reader() {
+    let mut buffer = Vec::new();
+    session().save_to_writer(&mut buffer).unwrap();
+
+    let restored = OrderSession::load_from_reader(buffer.as_slice()).unwrap();
+
+    assert_eq!(restored.version, SESSION_FORMAT_VERSION);
+    assert_eq!(restored.config, session().config);
+    assert_eq!(restored.output.as_deref(), Some("2 x A-1"));
+    assert_eq!(restored.history.len(), 1);
+}
+
+#[test]
+fn loads_sessions_saved_before_the_version_tag() {
+    let mut persisted = serde_json::to_value(session()).unwrap();
+    persisted.as_object_mut().unwrap().remove("version");
+
+    let restored = OrderSession::load_from_reader(persisted.to_string().as_bytes()).unwrap();
+
+    assert_eq!(restored.version, SESSION_FORMAT_VERSION);
+}
+
+#[test]
+fn rejects_newer_format_versions() {
+    let mut persisted = serde_json::to_value(session()).unwrap();
+    persisted["version"] = json!(SESSION_FORMAT_VERSION + 1);
+
+    let err = OrderSession::load_from_reader(persisted.to_string().as_bytes()).unwrap_err();
+
+    assert!(matches!(err, StructuredError::Context(_)));
+}
+
+#[test]
+fn rejects_config_that_is_no_longer_valid() {
+    let mut persisted = serde_json::to_value(session()).unwrap();
+    persisted["config"]["quantity"] = json!(0);
+
+    let err = OrderSession::load_from_reader(persisted.to_string().as_bytes()).unwrap_err();
+
+    match err {
+        StructuredError::Validation(message) => assert!(message.contains("quantity")),
+        other => panic!("expected validation error, got {other:?}"),
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+enum Delivery {
+    Pickup,
+    Courier { address: String },
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, JsonSchema)]
+struct Basket {
+    quantities: std::collections::BTreeMap<String, u32>,
+    delivery: Delivery,
+    note: Option<String>,
+}
+
+#[test]
+fn round_trips_configs_with_maps_enums_and_nulls() {
+    let basket = Basket {
+        quantities: [("A-1".to_string(), 2), ("B-2".to_string(), 1)].into(),
+        delivery: Delivery::Courier {
+            address: "1 Queen St".to_string(),
+        },
+        note: None,
+    };
+    let mut buffer = Vec::new();
+    InteractiveSession::<Basket, String>::new(basket.clone(), None)
+        .save_to_writer(&mut buffer)
+        .unwrap();
+
+    let restored =
+        InteractiveSession::<Basket, String>::load_from_reader(buffer.as_slice()).unwrap();
+
+    assert_eq!(restored.config, basket);
+}
+
+#[test]
+fn rejects_config_that_no_longer_deserializes() {
+    let mut persisted = serde_json::to_value(session()).unwrap();
+    persisted["config"]["quantity"] = json!("two");
+
+    let err = OrderSession::load_from_reader(persisted.to_string().as_bytes()).unwrap_err();
+
+    assert!(matches!(err, StructuredError::Validation(_)));
+}
@@ -0,0 +1,124 @@
+use std::sync::{
+    atomic::{AtomicUsize, Ordering},
+    Arc,
+};
+use std::time::Duration;
+
+use gemini_structured_output::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema, PartialEq)]
+struct Invoice {
+    number: String,
+}
+
+fn slow_client(calls: Arc<AtomicUsize>, single_flight: bool) -> StructuredClient {
+    StructuredClientBuilder::new("mock-key")
+        .with_mock(move |_| {
+            let n = calls.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(200));
+            Ok(format!(r#"{{"number": "INV-{n}"}}"#))
+        })
+        .with_single_flight(single_flight)
+        .build()
+        .unwrap()
+}
+
+async fn run_concurrently(client: &StructuredClient, prompts: [&str; 2]) -> Vec<Invoice> {
+    let mut values = Vec::new();
+    for result in run_with_temperatures(client, prompts, [None, None]).await {
+        values.push(result.unwrap());
+    }
+    values
+}
+
+async fn run_with_temperatures(
+    client: &StructuredClient,
+    prompts: [&str; 2],
+    temperatures: [Option<f32>; 2],
+) -> Vec<Result<Invoice>> {
+    let handles = std::array::from_fn::<_, 2, _>(|i| {
+        let client = client.clone();
+        let prompt = prompts[i].to_string();
+        let temperature = temperatures[i];
+        tokio::spawn(async move {
+            let mut request = client.request::<Invoice>().user_text(prompt);
+            if let Some(temperature) = temperature {
+                request = request.temperature(temperature);
+            }
+            request.execute().await.map(|outcome| outcome.value)
+        })
+    });
+    let mut results = Vec::new();
+    for handle in handles {
+        results.push(handle.await.unwrap());
+    }
+    results
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn identical_concurrent_requests_share_one_call() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = slow_client(Arc::clone(&calls), true);
+
+    let values = run_concurrently(&client, ["extract", "extract"]).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert_eq!(values[0], values[1]);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn different_requests_are_not_deduplicated() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = slow_client(Arc::clone(&calls), true);
+
+    run_concurrently(&client, ["extract", "summarize"]).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn single_flight_is_off_by_default() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = slow_client(Arc::clone(&calls), false);
+
+    run_concurrently(&client, ["extract", "extract"]).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn requests_differing_in_generation_config_are_not_deduplicated() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let client = slow_client(Arc::clone(&calls), true);
+
+    run_with_temperatures(&client, ["extract", "extract"], [Some(0.1), Some(0.9)]).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 2);
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+async fn followers_receive_the_leaders_error_variant() {
+    let calls = Arc::new(AtomicUsize::new(0));
+    let calls_in_mock = Arc::clone(&calls);
+    let client = StructuredClientBuilder::new("mock-key")
+        .with_mock(move |_| {
+            calls_in_mock.fetch_add(1, Ordering::SeqCst);
+            std::thread::sleep(Duration::from_millis(200));
+            Err(StructuredError::QuotaExhausted {
+                message: "daily limit".to_string(),
+            })
+        })
+        .with_single_flight(true)
+        .build()
+        .unwrap();
+
+    let results = run_with_temperatures(&client, ["extract", "extract"], [None, None]).await;
+
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    for result in results {
+        assert!(matches!(
+            result,
+            Err(StructuredError::QuotaExhausted { ref message }) if message == "daily limit"
+        ));
+    }
+}
@@ -0,0 +1,83 @@
+use std::sync::Arc;
+
+use gemini_structured_output::tools::{ToolContext, ToolError};
+use gemini_structured_output::{gemini_tool, ToolRegistry};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+struct Tenant {
+    id: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct InvoiceQuery {
+    status: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, JsonSchema)]
+struct InvoiceList {
+    tenant: String,
+    status: String,
+}
+
+#[gemini_tool(description = "List the current tenant's invoices", context = true)]
+async fn list_invoices(
+    args: InvoiceQuery,
+    ctx: Option<ToolContext>,
+) -> Result<InvoiceList, ToolError> {
+    let tenant = ctx
+        .as_deref()
+        .and_then(|ctx| ctx.downcast_ref::<Tenant>())
+        .ok_or("missing tenant")?;
+    Ok(InvoiceList {
+        tenant: tenant.id.clone(),
+        status: args.status,
+    })
+}
+
+#[gemini_tool(description = "Echo a query")]
+async fn echo(args: InvoiceQuery) -> Result<InvoiceQuery, ToolError> {
+    Ok(args)
+}
+
+fn registry() -> ToolRegistry {
+    ToolRegistry::new()
+        .register_tool(list_invoices_tool::registrar())
+        .register_tool(echo_tool::registrar())
+}
+
+#[tokio::test]
+async fn context_tools_receive_the_request_context() {
+    let ctx: ToolContext = Arc::new(Tenant {
+        id: "acme".to_string(),
+    });
+
+    let result = registry()
+        .execute_with_context("list_invoices", json!({"status": "open"}), Some(ctx))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({"tenant": "acme", "status": "open"}));
+}
+
+#[tokio::test]
+async fn context_tools_can_reject_missing_context() {
+    let err = registry()
+        .execute("list_invoices", json!({"status": "open"}))
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("missing tenant"));
+}
+
+#[tokio::test]
+async fn stateless_tools_ignore_the_context() {
+    let ctx: ToolContext = Arc::new(Tenant {
+        id: "acme".to_string(),
+    });
+
+    let result = registry()
+        .execute_with_context("echo", json!({"status": "paid"}), Some(ctx))
+        .await
+        .unwrap();
+    assert_eq!(result, json!({"status": "paid"}));
+}
@@ -0,0 +1,42 @@
+use std::borrow::Cow;
+
+use gemini_structured_output::prelude::*;
+use schemars::{json_schema, Schema, SchemaGenerator};
+
+#[derive(Serialize, Deserialize, JsonSchema)]
+struct Lookup {
+    query: String,
+}
+
+#[derive(Serialize)]
+struct Broken;
+
+impl JsonSchema for Broken {
+    fn schema_name() -> Cow<'static, str> {
+        "Broken".into()
+    }
+
+    fn json_schema(_: &mut SchemaGenerator) -> Schema {
+        json_schema!({ "type": "not-a-type" })
+    }
+}
+
+#[test]
+fn valid_registry_passes_definition_check() {
+    let registry = ToolRegistry::new()
+        .register::<Lookup, Lookup>("lookup", "Look something up")
+        .with_google_search();
+
+    assert!(registry.validate_definitions().is_ok());
+}
+
+#[test]
+fn broken_parameter_schema_is_reported_by_name() {
+    let registry = ToolRegistry::new()
+        .register::<Lookup, Lookup>("lookup", "Look something up")
+        .register::<Broken, Lookup>("broken", "Has an invalid schema");
+
+    let problems = registry.validate_definitions().unwrap_err();
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].starts_with("broken:"));
+}
@@ -0,0 +1,72 @@
+use gemini_structured_output::prelude::*;
+use serde_json::Value;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Ticket {
+    title: String,
+    body: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+enum Priority {
+    Low,
+    High,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Triage {
+    priority: Priority,
+    tags: Vec<String>,
+}
+
+fn client() -> StructuredClient {
+    StructuredClientBuilder::new("test-key")
+        .with_schema_example_mock()
+        .build()
+        .unwrap()
+}
+
+fn triage_step(client: StructuredClient) -> impl Step<Ticket, Triage> {
+    LambdaStep(move |ticket: Ticket| {
+        let client = client.clone();
+        async move {
+            let outcome = client
+                .request::<Triage>()
+                .user_text(format!("{}\n{}", ticket.title, ticket.body))
+                .execute()
+                .await?;
+            Ok::<_, StructuredError>(outcome.value)
+        }
+    })
+}
+
+#[tokio::test]
+async fn validate_wiring_runs_pipeline_on_schema_examples() {
+    let client = client();
+    let workflow = Workflow::new(triage_step(client).map(|triage: Triage| triage.tags.len()));
+
+    assert_eq!(workflow.validate_wiring().await.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn validate_wiring_surfaces_mismatched_json_handoff() {
+    let client = client();
+    // The second step decodes its input as the wrong payload type.
+    let pipeline = LambdaStep(move |ticket: Ticket| {
+        let client = client.clone();
+        async move {
+            let echoed = client
+                .request::<Ticket>()
+                .user_text(ticket.title)
+                .execute()
+                .await?;
+            Ok::<_, StructuredError>(serde_json::to_value(echoed.value)?)
+        }
+    })
+    .then(LambdaStep(|value: Value| async move {
+        Ok::<_, StructuredError>(serde_json::from_value::<Triage>(value)?)
+    }));
+
+    let err = Workflow::new(pipeline).validate_wiring().await.unwrap_err();
+    assert!(matches!(err, StructuredError::Json(_)));
+}
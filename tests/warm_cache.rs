@@ -0,0 +1,69 @@
+use std::env;
+use std::time::{Duration, SystemTime};
+
+use gemini_structured_output::prelude::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize, JsonSchema)]
+struct Summary {
+    headline: String,
+}
+
+fn client_with(policy: CachePolicy) -> StructuredClient {
+    StructuredClientBuilder::new("test-key")
+        .with_cache_policy(policy)
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn warm_cache_is_a_no_op_when_caching_is_disabled() {
+    let client = client_with(CachePolicy::Disabled);
+
+    let expiry = client
+        .warm_cache::<Summary>("x".repeat(10_000), &[])
+        .await
+        .unwrap();
+
+    assert!(expiry.is_none());
+    assert_eq!(client.cache_stats(), CacheStats::default());
+}
+
+#[tokio::test]
+async fn warm_cache_skips_prompts_below_the_size_cutoff() {
+    let client = client_with(CachePolicy::Enabled {
+        ttl: Duration::from_secs(600),
+    });
+
+    let expiry = client
+        .warm_cache::<Summary>("Summarize the report.", &[])
+        .await
+        .unwrap();
+
+    assert!(expiry.is_none());
+    assert_eq!(client.cache_stats().misses, 0);
+}
+
+#[tokio::test]
+#[ignore = "Creates cached content, requires key"]
+async fn warmed_entry_is_reused_by_the_next_warm() {
+    let api_key = env::var("GEMINI_API_KEY").expect("GEMINI_API_KEY must be set");
+    let ttl = Duration::from_secs(600);
+    let client = StructuredClientBuilder::new(api_key)
+        .with_cache_policy(CachePolicy::Enabled { ttl })
+        .build()
+        .expect("Failed to create client");
+    let system = "You summarize quarterly reports. ".repeat(400);
+
+    let before = SystemTime::now();
+    let first = client
+        .warm_cache::<Summary>(system.clone(), &[])
+        .await
+        .unwrap()
+        .expect("prompt is large enough to cache");
+    let second = client.warm_cache::<Summary>(system, &[]).await.unwrap();
+
+    assert!(first >= before + ttl);
+    assert_eq!(second, Some(first));
+    assert_eq!(client.cache_stats().creations, 1);
+    assert_eq!(client.cache_stats().hits, 1);
+}